@@ -1,8 +1,34 @@
 // src/app.rs
 // This module will contain the AppState struct and related logic.
 
-use crate::ollama_api::{ModelInfo, ShowModelResponse};
-use ratatui::widgets::ListState;
+use crate::chat::ChatState;
+use crate::config::{Config, Confirmations, HostProfile};
+use crate::copy_dialog::CopyDialog;
+use crate::derive::DeriveEditor;
+use crate::details::DetailsPane;
+use crate::downloads::{DownloadQueue, DEFAULT_MAX_CONCURRENT_DOWNLOADS};
+use crate::filter_query::FilterQuery;
+use crate::fuzzy::{self, MatchMode};
+use crate::inventory::InventoryState;
+use crate::keymap::{KeyBinding, Keymap};
+use crate::model_table::{ModelColumn, ModelSort};
+use crate::ollama_api::{ModelInfo, RunningModel, ShowModelResponse};
+use crate::param_form::ParamForm;
+use crate::quantize::QuantizeDialog;
+use crate::registry_api::Manifest;
+use crate::show_cache::ShowCache;
+use crate::tensors::TensorView;
+use crate::text_input::{KillRing, TextInput};
+use crate::theme::Theme;
+use ratatui::widgets::{ListState, TableState};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How often the loaded-models list (`/api/ps`) is refreshed unless configured otherwise.
+pub const DEFAULT_RUNNING_MODELS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long the selection has to rest on a model before its details are fetched.
+pub const DETAILS_FETCH_DELAY: Duration = Duration::from_millis(150);
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum AppMode {
@@ -13,26 +39,82 @@ pub enum AppMode {
     InstallSelectModelFilter,
     InstallSelectTag,
     InstallConfirm,
-    Installing,
+    Downloads,
     RunningOllama,
+    Chat,
+    RunningModels,
+    HostSelect,
+    Inventory,
+    InventoryConfirmDelete,
     Help,
+    /// Details pane focused: keys scroll it instead of the model list.
+    Details,
+    /// Typing a search inside the details pane.
+    DetailsSearch,
+    /// Tensor inspector of the selected model.
+    Tensors,
+    /// Typing the new name of a model to copy or rename.
+    CopyInput,
+    /// Editing a Modelfile to derive a new model from the selected one.
+    Derive,
+    /// The Modelfile is open in `$EDITOR`; the TUI is suspended.
+    EditingExternally,
+    /// Editing the parameters of the selected model to derive a new one.
+    ParamForm,
+    /// Picking a quantization type and name for a quantized copy of the selected model.
+    Quantize,
+}
+
+impl AppMode {
+    /// An external program has the terminal; keys aren't read until it exits.
+    pub fn is_suspended(&self) -> bool {
+        matches!(self, AppMode::RunningOllama | AppMode::EditingExternally)
+    }
+}
+
+/// Fetch state of a registry manifest for one tag of the selected registry model.
+#[derive(Debug, Clone)]
+pub enum ManifestStatus {
+    Loading,
+    Loaded(Manifest),
+    Failed(String),
+}
+
+/// Result of comparing a local model against its registry manifest.
+#[derive(Debug, Clone)]
+pub enum UpdateStatus {
+    Checking,
+    /// Hex digest of the registry manifest.
+    Checked(String),
+    Failed(String),
 }
 
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub models: Vec<ModelInfo>,
     pub filtered_models: Vec<ModelInfo>,
-    pub list_state: ListState,
-    pub selected_model_details: Option<ShowModelResponse>,
+    pub list_state: TableState,
+    /// Columns of the model table, in display order.
+    pub model_columns: Vec<ModelColumn>,
+    pub model_sort: ModelSort,
     pub status_message: Option<String>,
     pub current_mode: AppMode,
     pub should_quit: bool,
-    pub is_fetching_details: bool,
+    /// When to fetch the selected model's details; set when the selection changes,
+    /// so holding a key down only fetches the model it stops on.
+    pub details_fetch_due: Option<Instant>,
 
     // Filter-related fields
-    pub filter_input: String,
+    pub filter_input: TextInput,
     pub is_filtered: bool,
-    pub filter_cursor_pos: usize,
+    /// Fuzzy or plain substring matching, for both filters.
+    pub filter_mode: MatchMode,
+    /// Parsed `filter_input`, including any invalid terms to highlight.
+    pub filter_query: FilterQuery,
+    /// Matched name characters (char indices) of each filtered model.
+    pub filter_matches: HashMap<String, Vec<usize>>,
+    /// `/api/show` responses of the active host, and the requests in flight.
+    pub show_cache: ShowCache,
 
     // Registry-related fields
     pub registry_models: Vec<String>,
@@ -42,15 +124,61 @@ pub struct AppState {
     pub registry_tag_list_state: ListState,
     pub selected_registry_model: Option<String>,
     pub selected_registry_tag: Option<String>,
+    /// Manifests of the selected registry model, keyed by tag.
+    pub registry_manifests: HashMap<String, ManifestStatus>,
     pub is_fetching_registry: bool,
     pub install_error: Option<String>,
     pub install_status: Option<String>,
+    pub downloads: DownloadQueue,
+    pub chat: Option<ChatState>,
+    pub tensors: Option<TensorView>,
+    pub copy_dialog: Option<CopyDialog>,
+    pub derive: Option<DeriveEditor>,
+    pub param_form: Option<ParamForm>,
+    pub quantize: Option<QuantizeDialog>,
+    /// Last `/api/create` request, so results for a closed editor aren't shown in a new one.
+    pub create_request_id: u64,
+    /// Last `/api/chat` request across all chats, so chunks meant for a replaced chat are dropped.
+    pub chat_request_id: u64,
+
+    /// Update check results keyed by local model name.
+    pub update_status: HashMap<String, UpdateStatus>,
+
+    // Loaded models (/api/ps)
+    pub running_models: Vec<RunningModel>,
+    pub running_table_state: TableState,
+    pub running_models_error: Option<String>,
+    pub is_fetching_running: bool,
+    pub last_running_refresh: Option<Instant>,
+    pub running_refresh_interval: Duration,
+
+    // Host profiles
+    pub hosts: Vec<HostProfile>,
+    pub active_host: usize,
+    pub host_list_state: ListState,
+    pub inventory: Option<InventoryState>,
+
+    // Settings from the config file
+    pub theme: Theme,
+    pub keymap: Keymap,
+    /// Keys of a sequence typed so far, e.g. the first `g` of `gg`.
+    pub pending_keys: Vec<KeyBinding>,
+    pub help_scroll: u16,
+    pub details: DetailsPane,
+    /// Mouse capture is on (`mouse` in the config).
+    pub mouse_enabled: bool,
+    /// Time and screen row of the last left click, to detect double-clicks.
+    pub last_click: Option<(Instant, u16)>,
+    pub confirm: Confirmations,
     pub previous_mode: Option<AppMode>,
     
     // Registry filter fields
-    pub registry_filter_input: String,
+    pub registry_filter_input: TextInput,
     pub is_registry_filtered: bool,
-    pub registry_filter_cursor_pos: usize,
+    /// Matched characters (char indices) of each filtered registry model.
+    pub registry_filter_matches: HashMap<String, Vec<usize>>,
+    /// Text cut from any input, for pasting with yank.
+    pub kill_ring: KillRing,
 }
 
 impl AppState {
@@ -58,17 +186,21 @@ impl AppState {
         Self {
             models: Vec::new(),
             filtered_models: Vec::new(),
-            list_state: ListState::default(),
-            selected_model_details: None,
+            list_state: TableState::default(),
+            model_columns: ModelColumn::ALL.to_vec(),
+            model_sort: ModelSort::default(),
             status_message: Some("Loading models...".to_string()),
             current_mode: AppMode::Normal,
             should_quit: false,
-            is_fetching_details: false,
+            details_fetch_due: None,
 
             // --- Initialize New filter fields ---
-            filter_input: String::new(),
+            filter_input: TextInput::default(),
             is_filtered: false,
-            filter_cursor_pos: 0,
+            filter_mode: MatchMode::default(),
+            filter_query: FilterQuery::default(),
+            filter_matches: HashMap::new(),
+            show_cache: ShowCache::default(),
             // --- End Initialize New filter fields ---
 
             // Registry fields
@@ -79,18 +211,103 @@ impl AppState {
             registry_tag_list_state: ListState::default(),
             selected_registry_model: None,
             selected_registry_tag: None,
+            registry_manifests: HashMap::new(),
             is_fetching_registry: false,
             install_error: None,
             install_status: None,
+            downloads: DownloadQueue::new(DEFAULT_MAX_CONCURRENT_DOWNLOADS),
+            chat: None,
+            tensors: None,
+            copy_dialog: None,
+            derive: None,
+            param_form: None,
+            quantize: None,
+            create_request_id: 0,
+            chat_request_id: 0,
+
+            update_status: HashMap::new(),
+
+            running_models: Vec::new(),
+            running_table_state: TableState::default(),
+            running_models_error: None,
+            is_fetching_running: false,
+            last_running_refresh: None,
+            running_refresh_interval: DEFAULT_RUNNING_MODELS_REFRESH_INTERVAL,
+
+            hosts: Vec::new(),
+            active_host: 0,
+            host_list_state: ListState::default(),
+            inventory: None,
+
+            theme: Theme::default(),
+            keymap: Keymap::default(),
+            pending_keys: Vec::new(),
+            help_scroll: 0,
+            details: DetailsPane::default(),
+            mouse_enabled: true,
+            last_click: None,
+            confirm: Confirmations::default(),
             previous_mode: None,
             
             // Registry filter fields
-            registry_filter_input: String::new(),
+            registry_filter_input: TextInput::default(),
             is_registry_filtered: false,
-            registry_filter_cursor_pos: 0,
+            registry_filter_matches: HashMap::new(),
+            kill_ring: KillRing::default(),
         }
     }
 
+    /// Applies the settings of a loaded (and already validated) config.
+    pub fn apply_config(&mut self, config: &Config) {
+        (self.hosts, self.active_host) = config.resolve_hosts();
+        self.theme = Theme::from_config(&config.theme).unwrap_or_default();
+        self.keymap = Keymap::from_config(&config.keys).unwrap_or_default();
+        self.confirm = config.confirm;
+        self.mouse_enabled = config.mouse;
+        self.filter_mode = config.filter.mode;
+        self.model_columns = config.model_list.columns.clone();
+        self.model_sort = ModelSort {
+            column: config.model_list.sort,
+            descending: config
+                .model_list
+                .descending
+                .unwrap_or_else(|| config.model_list.sort.default_descending()),
+        };
+        self.downloads.max_concurrent = config.max_concurrent_downloads.max(1);
+        self.running_refresh_interval = config.running_refresh_interval();
+    }
+
+    /// Replaces the model list, keeping it in the current sort order.
+    pub fn set_models(&mut self, mut models: Vec<ModelInfo>) {
+        self.model_sort.sort(&mut models);
+        self.models = models;
+    }
+
+    /// Re-sorts the list and the filtered view, keeping the selected model selected.
+    /// Filtered models stay ranked by match score; the sort order breaks ties.
+    pub fn sort_models(&mut self) {
+        let selected = self.get_selected_model_name();
+        self.model_sort.sort(&mut self.models);
+        if self.is_filtered {
+            self.filtered_models = self.filter_models();
+        }
+        if let Some(name) = selected
+            && let Some(i) = self.get_current_models().iter().position(|m| m.name == name)
+        {
+            self.list_state.select(Some(i));
+        }
+    }
+
+    /// Sorts by the next visible column, starting in that column's natural direction.
+    pub fn cycle_sort_column(&mut self) {
+        let next = match self.model_columns.iter().position(|c| *c == self.model_sort.column) {
+            Some(i) => self.model_columns[(i + 1) % self.model_columns.len()],
+            None => self.model_columns[0],
+        };
+        self.model_sort = ModelSort { column: next, descending: next.default_descending() };
+        self.sort_models();
+    }
+
     pub fn get_current_models(&self) -> &[ModelInfo] {
         if self.is_filtered {
             &self.filtered_models
@@ -99,93 +316,123 @@ impl AppState {
         }
     }
 
+    /// Models matching the query, best match first, and records their matched characters.
+    fn filter_models(&mut self) -> Vec<ModelInfo> {
+        let now = chrono::Utc::now();
+        let mut matched: Vec<(i64, &ModelInfo)> = Vec::new();
+        self.filter_matches.clear();
+        for model in &self.models {
+            if let Some(found) = self.filter_query.match_model(model, self.show_cache.peek(&model.name, &model.digest), now) {
+                matched.push((found.score, model));
+                self.filter_matches.insert(model.name.clone(), found.positions);
+            }
+        }
+        // Stable, so equal scores keep the table's sort order
+        matched.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        matched.into_iter().map(|(_, m)| m.clone()).collect()
+    }
+
     pub fn apply_filter(&mut self) {
-        if self.filter_input.is_empty() {
+        self.filter_query = FilterQuery::parse(self.filter_input.text(), self.filter_mode);
+        if self.filter_input.text().trim().is_empty() {
             self.filtered_models.clear();
+            self.filter_matches.clear();
             self.is_filtered = false;
         } else {
-            let filter_lower = self.filter_input.to_lowercase();
-            self.filtered_models = self.models
-                .iter()
-                .filter(|model| model.name.to_lowercase().contains(&filter_lower))
-                .cloned()
-                .collect();
+            self.filtered_models = self.filter_models();
             self.is_filtered = true;
         }
 
-        let current_models = self.get_current_models();
-        if current_models.is_empty() {
-            self.list_state.select(None);
-            self.selected_model_details = None;
-        } else {
-            self.list_state.select(Some(0));
-            self.selected_model_details = None; // Clear to trigger refetch
-            self.is_fetching_details = false;
-        }
+        self.select_and_prepare_fetch(Some(0));
     }
 
-    // Clear the filter
-    pub fn clear_filter(&mut self) {
-        self.filter_input.clear();
-        self.filter_cursor_pos = 0;
-        self.is_filtered = false;
-        self.filtered_models.clear();
-        
-        // Reset selection to first item in full list
-        if self.models.is_empty() {
-            self.list_state.select(None);
-        } else {
-            self.list_state.select(Some(0));
-            self.selected_model_details = None;
-            self.is_fetching_details = false;
+    /// Re-runs the active filter after new model data arrived, keeping the selection if it still matches.
+    pub fn refresh_filter(&mut self) {
+        if !self.is_filtered {
+            return;
+        }
+        let selected = self.get_selected_model_name();
+        self.filtered_models = self.filter_models();
+        let index = selected.and_then(|name| self.filtered_models.iter().position(|m| m.name == name));
+        match index {
+            Some(i) => self.list_state.select(Some(i)),
+            None => self.select_and_prepare_fetch(Some(0)),
         }
     }
 
-    // Add character to filter input
-    pub fn filter_input_char(&mut self, c: char) {
-        self.filter_input.insert(self.filter_cursor_pos, c);
-        self.filter_cursor_pos += 1;
-        self.apply_filter();
+    /// Models whose `/api/show` data (capability badges and filters) is neither cached nor being fetched.
+    /// Limited to what the cache holds, so prefetching never evicts its own results.
+    pub fn models_needing_show_details(&self) -> Vec<(String, String)> {
+        self.models
+            .iter()
+            .take(self.show_cache.capacity())
+            .filter(|m| !self.show_cache.contains(&m.name, &m.digest) && !self.show_cache.is_pending(&m.name))
+            .map(|m| (m.name.clone(), m.digest.clone()))
+            .collect()
     }
 
-    // Remove character from filter input (backspace)
-    pub fn filter_input_backspace(&mut self) {
-        if self.filter_cursor_pos > 0 {
-            self.filter_cursor_pos -= 1;
-            self.filter_input.remove(self.filter_cursor_pos);
-            self.apply_filter();
-        }
+    /// Cached `/api/show` response of the selected model.
+    pub fn selected_model_details(&self) -> Option<&ShowModelResponse> {
+        let model = self.get_current_models().get(self.list_state.selected()?)?;
+        self.show_cache.peek(&model.name, &model.digest)
     }
 
-    pub fn filter_cursor_left(&mut self) {
-        if self.filter_cursor_pos > 0 {
-            self.filter_cursor_pos -= 1;
-        }
+    /// The selected model's details are missing and about to be, or being, fetched.
+    pub fn is_fetching_details(&self) -> bool {
+        self.get_selected_model_name().is_some_and(|name| {
+            self.selected_model_details().is_none()
+                && (self.details_fetch_due.is_some() || self.show_cache.is_pending(&name))
+        })
     }
 
-    pub fn filter_cursor_right(&mut self) {
-        if self.filter_cursor_pos < self.filter_input.len() {
-            self.filter_cursor_pos += 1;
+    /// The selected model and the ones next to it, whose details are worth having ready.
+    pub fn selected_model_neighbourhood(&self) -> Vec<(String, String)> {
+        let models = self.get_current_models();
+        let Some(i) = self.list_state.selected().filter(|&i| i < models.len()) else {
+            return Vec::new();
+        };
+        let mut indices = vec![i];
+        if i + 1 < models.len() {
+            indices.push(i + 1);
+        }
+        if i > 0 {
+            indices.push(i - 1);
         }
+        indices.into_iter().map(|i| (models[i].name.clone(), models[i].digest.clone())).collect()
     }
 
-    // Selects a model and clears existing details to trigger a fetch
+    // Clear the filter
+    pub fn clear_filter(&mut self) {
+        self.filter_input.clear();
+        self.is_filtered = false;
+        self.filtered_models.clear();
+        self.filter_matches.clear();
+        self.filter_query = FilterQuery::default();
+        
+        // Reset selection to first item in full list
+        self.select_and_prepare_fetch(Some(0));
+    }
+
+    // Selects a model and schedules a fetch of its details and its neighbours' unless cached
     pub fn select_and_prepare_fetch(&mut self, index: Option<usize>) {
         let current_models = self.get_current_models();
-        
+
         if current_models.is_empty() {
             self.list_state.select(None);
-            self.selected_model_details = None;
-            self.is_fetching_details = false;
-        } else {
-            let valid_index = index.unwrap_or(0).min(current_models.len() - 1);
-            if self.list_state.selected() != Some(valid_index) || self.selected_model_details.is_none() {
-                self.list_state.select(Some(valid_index));
-                self.selected_model_details = None;
-                self.status_message = Some("Fetching details...".to_string());
-                self.is_fetching_details = false;
-            }
+            self.details_fetch_due = None;
+            return;
+        }
+        let valid_index = index.unwrap_or(0).min(current_models.len() - 1);
+        let model = &current_models[valid_index];
+        let (name, digest) = (model.name.clone(), model.digest.clone());
+        if self.list_state.selected() != Some(valid_index) {
+            self.details.reset_scroll();
+            self.list_state.select(Some(valid_index));
         }
+        // Keeps the selected model's details from being evicted first
+        self.show_cache.get(&name, &digest);
+        // Also restarts the delay while the selection keeps moving
+        self.details_fetch_due = Some(Instant::now() + DETAILS_FETCH_DELAY);
     }
 
     pub fn next_model(&mut self) {
@@ -226,6 +473,103 @@ impl AppState {
             .map(|m| m.name.clone())
     }
 
+    /// Opens the chat pane for the selected model, keeping the transcript
+    /// if the same model was chatted with before.
+    pub fn open_chat(&mut self) -> bool {
+        let Some(name) = self.get_selected_model_name() else {
+            return false;
+        };
+        self.open_chat_on(name, None);
+        true
+    }
+
+    /// Opens the chat with `model` on `host`, or on the active host if `None`.
+    /// An open chat with the same model and host is kept.
+    pub fn open_chat_on(&mut self, model: String, host: Option<HostProfile>) {
+        if self.chat.as_ref().is_none_or(|c| c.model != model || c.host != host) {
+            let mut chat = ChatState::new(model, host);
+            if let Some(old) = self.chat.as_mut() {
+                old.cancel();
+                // Sent messages stay in the input history across models
+                chat.input = std::mem::take(&mut old.input);
+                chat.input.clear();
+            }
+            self.chat = Some(chat);
+        }
+        self.current_mode = AppMode::Chat;
+        self.status_message = None;
+    }
+
+    /// Returns true if the loaded-models list should be fetched again.
+    pub fn running_refresh_due(&self) -> bool {
+        !self.is_fetching_running
+            && self
+                .last_running_refresh
+                .is_none_or(|t| t.elapsed() >= self.running_refresh_interval)
+    }
+
+    /// True if the registry has a different manifest than the local copy.
+    pub fn is_model_outdated(&self, model: &ModelInfo) -> bool {
+        matches!(self.update_status.get(&model.name), Some(UpdateStatus::Checked(remote)) if *remote != model.digest)
+    }
+
+    pub fn outdated_model_names(&self) -> Vec<String> {
+        self.models
+            .iter()
+            .filter(|m| self.is_model_outdated(m))
+            .map(|m| m.name.clone())
+            .collect()
+    }
+
+    pub fn is_model_running(&self, name: &str) -> bool {
+        self.running_models.iter().any(|m| m.name == name)
+    }
+
+    pub fn next_running_model(&mut self) {
+        let len = self.running_models.len();
+        if len > 0 {
+            let i = self.running_table_state.selected().map_or(0, |i| (i + 1) % len);
+            self.running_table_state.select(Some(i));
+        }
+    }
+
+    pub fn previous_running_model(&mut self) {
+        let len = self.running_models.len();
+        if len > 0 {
+            let i = self.running_table_state.selected().map_or(len - 1, |i| (i + len - 1) % len);
+            self.running_table_state.select(Some(i));
+        }
+    }
+
+    pub fn active_host(&self) -> Option<&HostProfile> {
+        self.hosts.get(self.active_host)
+    }
+
+    /// Makes `index` the active host and drops everything loaded from the previous one.
+    /// The caller rebuilds the client and reloads the model list.
+    pub fn switch_host(&mut self, index: usize) {
+        self.active_host = index;
+        self.models.clear();
+        self.show_cache.clear();
+        self.clear_filter();
+        self.list_state.select(None);
+        self.details_fetch_due = None;
+        self.update_status.clear();
+        self.running_models.clear();
+        self.running_table_state.select(None);
+        self.running_models_error = None;
+        self.is_fetching_running = false;
+        self.last_running_refresh = None;
+        self.tensors = None;
+        if let Some(chat) = self.chat.as_mut() {
+            chat.cancel();
+        }
+        self.chat = None;
+        self.status_message = self
+            .active_host()
+            .map(|h| format!("Loading models from {}...", h.name));
+    }
+
     // Registry filter methods
     pub fn get_current_registry_models(&self) -> &[String] {
         if self.is_registry_filtered {
@@ -238,14 +582,19 @@ impl AppState {
     pub fn apply_registry_filter(&mut self) {
         if self.registry_filter_input.is_empty() {
             self.filtered_registry_models.clear();
+            self.registry_filter_matches.clear();
             self.is_registry_filtered = false;
         } else {
-            let filter_lower = self.registry_filter_input.to_lowercase();
-            self.filtered_registry_models = self.registry_models
-                .iter()
-                .filter(|model| model.to_lowercase().contains(&filter_lower))
-                .cloned()
-                .collect();
+            let mut matched: Vec<(i64, &String)> = Vec::new();
+            self.registry_filter_matches.clear();
+            for model in &self.registry_models {
+                if let Some(found) = fuzzy::find(self.filter_mode, self.registry_filter_input.text(), model) {
+                    matched.push((found.score, model));
+                    self.registry_filter_matches.insert(model.clone(), found.positions);
+                }
+            }
+            matched.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            self.filtered_registry_models = matched.into_iter().map(|(_, m)| m.clone()).collect();
             self.is_registry_filtered = true;
         }
 
@@ -259,9 +608,9 @@ impl AppState {
 
     pub fn clear_registry_filter(&mut self) {
         self.registry_filter_input.clear();
-        self.registry_filter_cursor_pos = 0;
         self.is_registry_filtered = false;
         self.filtered_registry_models.clear();
+        self.registry_filter_matches.clear();
         
         if self.registry_models.is_empty() {
             self.registry_model_list_state.select(None);
@@ -270,40 +619,11 @@ impl AppState {
         }
     }
 
-    pub fn registry_filter_input_char(&mut self, c: char) {
-        self.registry_filter_input.insert(self.registry_filter_cursor_pos, c);
-        self.registry_filter_cursor_pos += 1;
-        self.apply_registry_filter();
-    }
-
-    pub fn registry_filter_input_backspace(&mut self) {
-        if self.registry_filter_cursor_pos > 0 {
-            self.registry_filter_cursor_pos -= 1;
-            self.registry_filter_input.remove(self.registry_filter_cursor_pos);
-            self.apply_registry_filter();
-        }
-    }
-
-    pub fn registry_filter_cursor_left(&mut self) {
-        if self.registry_filter_cursor_pos > 0 {
-            self.registry_filter_cursor_pos -= 1;
-        }
-    }
-
-    pub fn registry_filter_cursor_right(&mut self) {
-        if self.registry_filter_cursor_pos < self.registry_filter_input.len() {
-            self.registry_filter_cursor_pos += 1;
-        }
+    /// Returns the tag highlighted in the tag selection list.
+    pub fn highlighted_registry_tag(&self) -> Option<&String> {
+        self.registry_tag_list_state
+            .selected()
+            .and_then(|i| self.registry_tags.get(i))
     }
 
-    /// Returns true if global key handling (like help) should be enabled.
-    /// Global keys are disabled in modes that handle their own input.
-    pub fn is_global_key_handling_enabled(&self) -> bool {
-        !matches!(self.current_mode, 
-            AppMode::RunningOllama 
-            | AppMode::Help 
-            | AppMode::Filter 
-            | AppMode::InstallSelectModelFilter
-        )
-    }
 }
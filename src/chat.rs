@@ -0,0 +1,130 @@
+// src/chat.rs
+// State for the built-in chat pane backed by /api/chat.
+
+use crate::config::HostProfile;
+use crate::ollama_api::ChatMessage;
+use crate::text_input::TextInput;
+use tokio::task::AbortHandle;
+
+pub const ROLE_USER: &str = "user";
+pub const ROLE_ASSISTANT: &str = "assistant";
+
+#[derive(Debug, Clone)]
+pub struct ChatState {
+    pub model: String,
+    /// Host to chat with when it isn't the active one, for chats opened from the inventory.
+    pub host: Option<HostProfile>,
+    pub messages: Vec<ChatMessage>,
+    pub input: TextInput,
+    /// Number of lines scrolled up from the bottom of the transcript.
+    pub scroll_from_bottom: u16,
+    pub error: Option<String>,
+    /// The request being streamed. Ids are unique across chats, so chunks of a stopped
+    /// request or of another model's chat never match.
+    pub request_id: Option<u64>,
+    pub abort_handle: Option<AbortHandle>,
+    /// Indices of replies stopped part-way; they stay visible but aren't sent back.
+    pub interrupted: Vec<usize>,
+}
+
+impl ChatState {
+    pub fn new(model: String, host: Option<HostProfile>) -> Self {
+        Self {
+            model,
+            host,
+            messages: Vec::new(),
+            input: TextInput::default(),
+            scroll_from_bottom: 0,
+            error: None,
+            request_id: None,
+            abort_handle: None,
+            interrupted: Vec::new(),
+        }
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.request_id.is_some()
+    }
+
+    /// Moves the input into the transcript and returns the messages to send.
+    /// Returns `None` if the input is empty or a response is still streaming.
+    pub fn begin_request(&mut self, request_id: u64) -> Option<Vec<ChatMessage>> {
+        if self.is_streaming() || self.input.text().trim().is_empty() {
+            return None;
+        }
+
+        self.input.push_history();
+        let content = self.input.take();
+        self.messages.push(ChatMessage { role: ROLE_USER.to_string(), content });
+        let history = self
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.interrupted.contains(i))
+            .map(|(_, m)| m.clone())
+            .collect();
+        self.messages.push(ChatMessage { role: ROLE_ASSISTANT.to_string(), content: String::new() });
+
+        self.request_id = Some(request_id);
+        self.scroll_from_bottom = 0;
+        self.error = None;
+        Some(history)
+    }
+
+    pub fn append_token(&mut self, request_id: u64, token: &str) {
+        if self.request_id != Some(request_id) {
+            return;
+        }
+        if let Some(last) = self.messages.last_mut()
+            && last.role == ROLE_ASSISTANT
+        {
+            last.content.push_str(token);
+        }
+    }
+
+    /// Ends the request. A failed reply is dropped, so it isn't sent back with the next message.
+    pub fn finish(&mut self, request_id: u64, error: Option<String>) {
+        if self.request_id != Some(request_id) {
+            return;
+        }
+        self.request_id = None;
+        self.abort_handle = None;
+        if error.is_some() && self.messages.last().is_some_and(|m| m.role == ROLE_ASSISTANT) {
+            self.messages.pop();
+        }
+        self.error = error;
+    }
+
+    /// Stops the in-flight response. Text received so far stays visible, marked as interrupted.
+    pub fn cancel(&mut self) {
+        if let Some(handle) = self.abort_handle.take() {
+            handle.abort();
+        }
+        if self.request_id.take().is_some()
+            && let Some(last) = self.messages.last()
+            && last.role == ROLE_ASSISTANT
+        {
+            if last.content.is_empty() {
+                self.messages.pop();
+            } else {
+                self.interrupted.push(self.messages.len() - 1);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cancel();
+        self.messages.clear();
+        self.interrupted.clear();
+        self.scroll_from_bottom = 0;
+        self.error = None;
+    }
+
+    pub fn scroll_up(&mut self, lines: u16) {
+        self.scroll_from_bottom = self.scroll_from_bottom.saturating_add(lines);
+    }
+
+    pub fn scroll_down(&mut self, lines: u16) {
+        self.scroll_from_bottom = self.scroll_from_bottom.saturating_sub(lines);
+    }
+}
@@ -0,0 +1,289 @@
+// src/cli.rs
+// Non-interactive subcommands for scripting. These talk to the APIs directly
+// and never touch the terminal state used by the TUI.
+
+use crate::{
+    config::Config,
+    error::{ApiError, AppError, Result},
+    ollama_api::OllamaClient,
+    registry_api::{self, RegistryClient},
+};
+use chrono::SecondsFormat;
+use clap::{Subcommand, ValueEnum};
+use humansize::{format_size, BINARY};
+use serde::Serialize;
+use std::io::Write;
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// List locally installed models
+    List,
+    /// Show details of a local model
+    Show { model: String },
+    /// Delete a local model
+    Rm { model: String },
+    /// Pull a model (`name` or `name:tag`) from the registry
+    Pull { model: String },
+    /// List models currently loaded into memory
+    Ps,
+    /// Query the Ollama registry
+    Registry {
+        #[command(subcommand)]
+        command: RegistryCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RegistryCommand {
+    /// Search registry models by name
+    Search { query: String },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+/// Rows of string cells rendered as an aligned table or CSV.
+struct TableOutput {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl TableOutput {
+    fn render_table(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.chars().count());
+            }
+        }
+
+        let format_row = |cells: Vec<&str>| {
+            let padded: Vec<String> = cells
+                .iter()
+                .enumerate()
+                .map(|(i, c)| format!("{:<width$}", c, width = widths[i]))
+                .collect();
+            padded.join("  ").trim_end().to_string()
+        };
+
+        let mut lines = vec![format_row(self.headers.clone())];
+        for row in &self.rows {
+            lines.push(format_row(row.iter().map(String::as_str).collect()));
+        }
+        lines.join("\n")
+    }
+
+    fn render_csv(&self) -> String {
+        let mut lines = vec![self.headers.iter().map(|h| csv_escape(h)).collect::<Vec<_>>().join(",")];
+        for row in &self.rows {
+            lines.push(row.iter().map(|c| csv_escape(c)).collect::<Vec<_>>().join(","));
+        }
+        lines.join("\n")
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Multi-line text would break table and CSV rows, so it is reduced to a line count there.
+fn one_line_summary(text: &str) -> String {
+    let text = text.trim();
+    match text.lines().count() {
+        0 | 1 => text.to_string(),
+        lines => format!("{} lines (see --json)", lines),
+    }
+}
+
+/// Prints `data` as JSON, or `table` as a table/CSV depending on `format`.
+fn print_output<T: Serialize>(format: OutputFormat, data: &T, table: TableOutput) -> Result<()> {
+    let output = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(data)
+            .map_err(|e| AppError::Api(ApiError::Deserialization(e)))?,
+        OutputFormat::Table => table.render_table(),
+        OutputFormat::Csv => table.render_csv(),
+    };
+    println!("{}", output);
+    Ok(())
+}
+
+/// Runs a subcommand to completion against the starting host of `config`.
+pub async fn run(command: Command, format: OutputFormat, config: &Config) -> Result<()> {
+    let (hosts, active) = config.resolve_hosts();
+    let client = OllamaClient::new(hosts[active].url.clone(), &config.timeouts);
+
+    match command {
+        Command::List => {
+            let models = client.list_models().await?;
+            let table = TableOutput {
+                headers: vec!["NAME", "SIZE", "MODIFIED", "DIGEST"],
+                rows: models
+                    .iter()
+                    .map(|m| {
+                        vec![
+                            m.name.clone(),
+                            m.size_formatted(),
+                            m.modified_at.clone(),
+                            m.digest.chars().take(12).collect(),
+                        ]
+                    })
+                    .collect(),
+            };
+            print_output(format, &models, table)
+        }
+        Command::Show { model } => {
+            let details = client.show_model_details(&model).await?;
+            let mut rows = vec![vec!["name".to_string(), model.clone()]];
+            if let Some(extra) = &details.details {
+                let fields = [
+                    ("family", &extra.family),
+                    ("format", &extra.format),
+                    ("parameter_size", &extra.parameter_size),
+                    ("quantization_level", &extra.quantization_level),
+                ];
+                for (key, value) in fields {
+                    if let Some(value) = value {
+                        rows.push(vec![key.to_string(), value.clone()]);
+                    }
+                }
+            }
+            if !details.capabilities.is_empty() {
+                let names: Vec<&str> = details.capabilities.iter().map(|c| c.name()).collect();
+                rows.push(vec!["capabilities".to_string(), names.join(", ")]);
+            }
+            if let Some(info) = &details.model_info {
+                let numbers = [
+                    ("context_length", info.context_length),
+                    ("embedding_length", info.embedding_length),
+                    ("block_count", info.block_count),
+                    ("head_count", info.head_count),
+                    ("head_count_kv", info.head_count_kv),
+                    ("vocab_size", info.vocab_size),
+                ];
+                for (key, value) in numbers {
+                    if let Some(value) = value {
+                        rows.push(vec![key.to_string(), value.to_string()]);
+                    }
+                }
+            }
+            if let Some(modified_at) = details.modified_at {
+                rows.push(vec!["modified_at".to_string(), modified_at.to_rfc3339_opts(SecondsFormat::Secs, true)]);
+            }
+            for (key, value) in [
+                ("parameters", &details.parameters),
+                ("template", &details.template),
+                ("license", &details.license),
+            ] {
+                if let Some(value) = value {
+                    rows.push(vec![key.to_string(), one_line_summary(value)]);
+                }
+            }
+            let table = TableOutput { headers: vec!["FIELD", "VALUE"], rows };
+            print_output(format, &details, table)
+        }
+        Command::Rm { model } => {
+            client.delete_model(&model).await?;
+            let table = TableOutput {
+                headers: vec!["DELETED"],
+                rows: vec![vec![model.clone()]],
+            };
+            print_output(format, &serde_json::json!({ "deleted": model }), table)
+        }
+        Command::Pull { model } => pull(&client, &model, format).await,
+        Command::Ps => {
+            let running = client.list_running().await?;
+            let table = TableOutput {
+                headers: vec!["NAME", "SIZE", "VRAM", "PROCESSOR", "CONTEXT", "EXPIRES_AT"],
+                rows: running
+                    .iter()
+                    .map(|m| {
+                        vec![
+                            m.name.clone(),
+                            m.size_formatted(),
+                            format_size(m.size_vram, BINARY),
+                            m.processor_split(),
+                            m.context_length.map(|c| c.to_string()).unwrap_or_default(),
+                            m.expires_at.clone().unwrap_or_default(),
+                        ]
+                    })
+                    .collect(),
+            };
+            print_output(format, &running, table)
+        }
+        Command::Registry { command: RegistryCommand::Search { query } } => {
+            let registry = RegistryClient::new(
+                registry_api::get_registry_url(config.registry_url.as_deref()),
+                &config.timeouts,
+            );
+            let query_lower = query.to_lowercase();
+            let matches: Vec<String> = registry
+                .fetch_models()
+                .await?
+                .into_iter()
+                .filter(|m| m.to_lowercase().contains(&query_lower))
+                .collect();
+            let table = TableOutput {
+                headers: vec!["NAME"],
+                rows: matches.iter().map(|m| vec![m.clone()]).collect(),
+            };
+            print_output(format, &matches, table)
+        }
+    }
+}
+
+/// Streams a pull. JSON output emits one progress object per line on stdout;
+/// other formats report progress on stderr so stdout stays clean.
+async fn pull(client: &OllamaClient, model: &str, format: OutputFormat) -> Result<()> {
+    let mut stream = client.pull_model(model).await?;
+    let mut stderr = std::io::stderr();
+    let mut last_status = String::new();
+
+    while let Some(progress) = stream.next().await {
+        let progress = progress?;
+        if format == OutputFormat::Json {
+            let line = serde_json::to_string(&progress)
+                .map_err(|e| AppError::Api(ApiError::Deserialization(e)))?;
+            println!("{}", line);
+            continue;
+        }
+
+        // Keep a finished layer's progress line and start a new one for the next status
+        if progress.status != last_status && !last_status.is_empty() {
+            let _ = writeln!(stderr);
+        }
+        match (progress.total, progress.completed) {
+            (Some(total), Some(completed)) if total > 0 => {
+                let _ = write!(
+                    stderr,
+                    "\r{}: {} / {} ({:.0}%)\x1b[K",
+                    progress.status,
+                    format_size(completed, BINARY),
+                    format_size(total, BINARY),
+                    completed as f64 / total as f64 * 100.0
+                );
+            }
+            _ => {
+                let _ = write!(stderr, "\r{}\x1b[K", progress.status);
+            }
+        }
+        last_status = progress.status;
+    }
+
+    if format != OutputFormat::Json {
+        let _ = writeln!(stderr);
+        let table = TableOutput {
+            headers: vec!["PULLED"],
+            rows: vec![vec![model.to_string()]],
+        };
+        print_output(format, &(), table)?;
+    }
+    Ok(())
+}
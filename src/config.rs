@@ -0,0 +1,465 @@
+// src/config.rs
+// Loads the user configuration file (`$XDG_CONFIG_HOME/lazyollama/config.toml`, default `~/.config`).
+// Precedence for overlapping settings: environment, then `.env`, then this file.
+
+use crate::{
+    error::{AppError, Result},
+    fuzzy::MatchMode,
+    keymap::Keymap,
+    model_table::ModelColumn,
+    ollama_api,
+    theme::Theme,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A named Ollama server the app can switch to.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HostProfile {
+    pub name: String,
+    pub url: String,
+}
+
+/// HTTP timeouts. Streaming requests (pull, chat) and model loads only use the connect timeout.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(default, deny_unknown_fields)]
+pub struct Timeouts {
+    pub connect_secs: u64,
+    pub request_secs: u64,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self { connect_secs: 5, request_secs: 30 }
+    }
+}
+
+impl Timeouts {
+    pub fn connect(&self) -> Duration {
+        Duration::from_secs(self.connect_secs)
+    }
+
+    pub fn request(&self) -> Duration {
+        Duration::from_secs(self.request_secs)
+    }
+}
+
+/// Which actions ask for confirmation first.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(default, deny_unknown_fields)]
+pub struct Confirmations {
+    pub delete: bool,
+    pub install: bool,
+}
+
+impl Default for Confirmations {
+    fn default() -> Self {
+        Self { delete: true, install: true }
+    }
+}
+
+/// Color overrides; see `Theme` for the defaults.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub highlight_bg: Option<String>,
+    pub dialog_bg: Option<String>,
+    pub status_bar_bg: Option<String>,
+    pub accent: Option<String>,
+    pub success: Option<String>,
+    pub warning: Option<String>,
+    pub error: Option<String>,
+    pub muted: Option<String>,
+}
+
+/// Built-in key layouts that `[keys.*]` overrides start from.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyPreset {
+    /// Vim-style letters plus arrow keys.
+    #[default]
+    Default,
+    /// Letters only, with `dd` to delete.
+    Vim,
+    /// Arrow keys, Home/End, Delete and F1 instead of vim letters.
+    Arrows,
+}
+
+/// One key (`"ctrl+n"`, `"g g"`) or a list of them.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum KeySpecs {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeySpecs {
+    pub fn as_slice(&self) -> &[String] {
+        match self {
+            KeySpecs::One(spec) => std::slice::from_ref(spec),
+            KeySpecs::Many(specs) => specs,
+        }
+    }
+}
+
+/// Key bindings: a preset plus per-context `action = keys` overrides.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeysConfig {
+    pub preset: KeyPreset,
+    pub global: HashMap<String, KeySpecs>,
+    pub models: HashMap<String, KeySpecs>,
+    pub filter: HashMap<String, KeySpecs>,
+    pub install: HashMap<String, KeySpecs>,
+    pub confirm: HashMap<String, KeySpecs>,
+    pub hosts: HashMap<String, KeySpecs>,
+    pub inventory: HashMap<String, KeySpecs>,
+    pub running: HashMap<String, KeySpecs>,
+    pub downloads: HashMap<String, KeySpecs>,
+    pub chat: HashMap<String, KeySpecs>,
+    pub help: HashMap<String, KeySpecs>,
+    pub details: HashMap<String, KeySpecs>,
+    pub tensors: HashMap<String, KeySpecs>,
+    pub editor: HashMap<String, KeySpecs>,
+    pub form: HashMap<String, KeySpecs>,
+    pub quantize: HashMap<String, KeySpecs>,
+}
+
+/// Columns and initial sort of the installed-models table.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModelListConfig {
+    pub columns: Vec<ModelColumn>,
+    pub sort: ModelColumn,
+    /// Sort direction; defaults to the column's natural direction (biggest/newest first).
+    pub descending: Option<bool>,
+}
+
+impl Default for ModelListConfig {
+    fn default() -> Self {
+        Self {
+            columns: ModelColumn::ALL.to_vec(),
+            sort: ModelColumn::Name,
+            descending: None,
+        }
+    }
+}
+
+/// Matching used by the model and registry filters.
+#[derive(Deserialize, Debug, Clone, Copy, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterConfig {
+    /// `fuzzy` (default) or `substring`.
+    pub mode: MatchMode,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Name of the profile to start with when `OLLAMA_HOST` is not set.
+    pub default_host: Option<String>,
+    pub hosts: Vec<HostProfile>,
+    /// Used when `OLLAMA_REGISTRY_URL` is not set.
+    pub registry_url: Option<String>,
+    pub max_concurrent_downloads: usize,
+    /// How often the loaded-models list (`/api/ps`) is refreshed.
+    pub running_refresh_secs: u64,
+    /// Capture the mouse for clicking and scrolling. Off keeps the terminal's own text selection.
+    pub mouse: bool,
+    pub timeouts: Timeouts,
+    pub confirm: Confirmations,
+    pub theme: ThemeConfig,
+    pub model_list: ModelListConfig,
+    pub filter: FilterConfig,
+    pub keys: KeysConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            default_host: None,
+            hosts: Vec::new(),
+            registry_url: None,
+            max_concurrent_downloads: crate::downloads::DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            running_refresh_secs: crate::app::DEFAULT_RUNNING_MODELS_REFRESH_INTERVAL.as_secs(),
+            mouse: true,
+            timeouts: Timeouts::default(),
+            confirm: Confirmations::default(),
+            theme: ThemeConfig::default(),
+            model_list: ModelListConfig::default(),
+            filter: FilterConfig::default(),
+            keys: KeysConfig::default(),
+        }
+    }
+}
+
+/// Location of the config file: `$XDG_CONFIG_HOME/lazyollama/config.toml`, or under
+/// `~/.config` if that isn't set. `None` if there is no home directory either.
+pub fn config_path() -> Option<PathBuf> {
+    config_path_with(std::env::var_os("XDG_CONFIG_HOME"), dirs::home_dir())
+}
+
+fn config_path_with(xdg_config_home: Option<OsString>, home: Option<PathBuf>) -> Option<PathBuf> {
+    // Relative values are invalid per the XDG spec and ignored
+    let dir = xdg_config_home
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home.map(|home| home.join(".config")))?;
+    Some(dir.join("lazyollama").join("config.toml"))
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+impl Config {
+    /// Reads the config file. A missing file yields the default config.
+    pub fn load() -> Result<Self> {
+        let Some(path) = config_path() else {
+            return Ok(Self::default());
+        };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(AppError::Config(format!("{}: {}", path.display(), e))),
+        };
+        let config: Self = toml::from_str(&contents)
+            .map_err(|e| AppError::Config(format!("{}: {}", path.display(), e)))?;
+        config
+            .validate()
+            .map_err(|e| AppError::Config(format!("{}: {}", path.display(), e)))?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        for (i, host) in self.hosts.iter().enumerate() {
+            if host.name.trim().is_empty() {
+                return Err(format!("hosts[{}]: name must not be empty", i));
+            }
+            if !is_http_url(&host.url) {
+                return Err(format!(
+                    "hosts[{}] ({}): url must start with http:// or https://",
+                    i, host.name
+                ));
+            }
+            if self.hosts[..i].iter().any(|h| h.name == host.name) {
+                return Err(format!("duplicate host name '{}'", host.name));
+            }
+        }
+        if let Some(default) = &self.default_host
+            && !self.hosts.iter().any(|h| &h.name == default)
+        {
+            return Err(format!(
+                "default_host '{}' does not match any [[hosts]] entry",
+                default
+            ));
+        }
+        if let Some(url) = &self.registry_url
+            && !is_http_url(url)
+        {
+            return Err("registry_url must start with http:// or https://".to_string());
+        }
+        if self.max_concurrent_downloads == 0 {
+            return Err("max_concurrent_downloads must be at least 1".to_string());
+        }
+        if self.running_refresh_secs == 0 {
+            return Err("running_refresh_secs must be at least 1".to_string());
+        }
+        if self.timeouts.connect_secs == 0 || self.timeouts.request_secs == 0 {
+            return Err("timeouts.connect_secs and timeouts.request_secs must be at least 1".to_string());
+        }
+        let columns = &self.model_list.columns;
+        if !columns.contains(&ModelColumn::Name) {
+            return Err("model_list.columns must include \"name\"".to_string());
+        }
+        if let Some((i, column)) = columns.iter().enumerate().find(|(i, c)| columns[..*i].contains(c)) {
+            return Err(format!("model_list.columns[{}]: duplicate column '{}'", i, column.title()));
+        }
+        Theme::from_config(&self.theme)?;
+        Keymap::from_config(&self.keys)?;
+        Ok(())
+    }
+
+    /// Returns the host profiles to offer and the index of the one to start with.
+    /// `OLLAMA_HOST` takes precedence and is added as its own profile if no
+    /// configured host has the same URL.
+    pub fn resolve_hosts(&self) -> (Vec<HostProfile>, usize) {
+        self.resolve_hosts_with(ollama_api::env_ollama_host())
+    }
+
+    fn resolve_hosts_with(&self, env_host: Option<String>) -> (Vec<HostProfile>, usize) {
+        let mut hosts: Vec<HostProfile> = self
+            .hosts
+            .iter()
+            .map(|h| HostProfile {
+                name: h.name.clone(),
+                url: h.url.trim_end_matches('/').to_string(),
+            })
+            .collect();
+
+        if let Some(env_host) = env_host {
+            let index = match hosts.iter().position(|h| h.url == env_host) {
+                Some(index) => index,
+                None => {
+                    hosts.insert(0, HostProfile { name: "OLLAMA_HOST".to_string(), url: env_host });
+                    0
+                }
+            };
+            return (hosts, index);
+        }
+
+        if hosts.is_empty() {
+            hosts.push(HostProfile {
+                name: "default".to_string(),
+                url: ollama_api::get_ollama_host(),
+            });
+        }
+        let index = self
+            .default_host
+            .as_ref()
+            .and_then(|name| hosts.iter().position(|h| &h.name == name))
+            .unwrap_or(0);
+        (hosts, index)
+    }
+
+    pub fn running_refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.running_refresh_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejected(toml: &str) -> String {
+        let config: Config = toml::from_str(toml).expect("config should parse");
+        config.validate().expect_err("config should be rejected")
+    }
+
+    #[test]
+    fn an_empty_file_is_the_default_config() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.max_concurrent_downloads, crate::downloads::DEFAULT_MAX_CONCURRENT_DOWNLOADS);
+        assert!(config.confirm.delete && config.confirm.install);
+    }
+
+    #[test]
+    fn rejects_bad_hosts() {
+        assert_eq!(
+            rejected("[[hosts]]\nname = \" \"\nurl = \"http://a:11434\"\n"),
+            "hosts[0]: name must not be empty"
+        );
+        assert_eq!(
+            rejected("[[hosts]]\nname = \"gpu\"\nurl = \"gpu-box:11434\"\n"),
+            "hosts[0] (gpu): url must start with http:// or https://"
+        );
+        assert_eq!(
+            rejected(
+                "[[hosts]]\nname = \"gpu\"\nurl = \"http://a:11434\"\n\
+                 [[hosts]]\nname = \"gpu\"\nurl = \"http://b:11434\"\n"
+            ),
+            "duplicate host name 'gpu'"
+        );
+    }
+
+    #[test]
+    fn rejects_an_unknown_default_host() {
+        assert_eq!(
+            rejected("default_host = \"laptop\"\n[[hosts]]\nname = \"gpu\"\nurl = \"http://a:11434\"\n"),
+            "default_host 'laptop' does not match any [[hosts]] entry"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_numbers_and_urls() {
+        assert_eq!(
+            rejected("registry_url = \"registry.ollama.ai\""),
+            "registry_url must start with http:// or https://"
+        );
+        assert_eq!(rejected("max_concurrent_downloads = 0"), "max_concurrent_downloads must be at least 1");
+        assert_eq!(rejected("running_refresh_secs = 0"), "running_refresh_secs must be at least 1");
+        assert_eq!(
+            rejected("[timeouts]\nrequest_secs = 0"),
+            "timeouts.connect_secs and timeouts.request_secs must be at least 1"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_theme_colors_and_keys() {
+        assert_eq!(
+            rejected("[theme]\naccent = \"sparkly\""),
+            "theme.accent: unknown color 'sparkly' (use a name like \"lightblue\", \"#rrggbb\" or an index 0-255)"
+        );
+        assert_eq!(rejected("[keys.models]\nrun = \"f13\""), "keys.models.run: invalid key 'f13'");
+        assert_eq!(
+            rejected("[keys.models]\nchat = \"r\""),
+            "keys.models.chat: 'r' is the only key of 'run'; bind 'run' to another key too"
+        );
+    }
+
+    #[test]
+    fn unknown_fields_are_parse_errors() {
+        let err = toml::from_str::<Config>("max_downloads = 2").unwrap_err();
+        assert!(err.to_string().contains("unknown field `max_downloads`"), "{}", err);
+    }
+
+    fn two_hosts() -> Config {
+        toml::from_str(
+            "default_host = \"gpu\"\n\
+             [[hosts]]\nname = \"laptop\"\nurl = \"http://localhost:11434/\"\n\
+             [[hosts]]\nname = \"gpu\"\nurl = \"http://gpu-box:11434\"\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn default_host_picks_the_starting_profile() {
+        let (hosts, index) = two_hosts().resolve_hosts_with(None);
+        assert_eq!(hosts[0].url, "http://localhost:11434");
+        assert_eq!(hosts[index].name, "gpu");
+    }
+
+    #[test]
+    fn ollama_host_overrides_the_config() {
+        let (hosts, index) = two_hosts().resolve_hosts_with(Some("http://localhost:11434".to_string()));
+        assert_eq!((hosts.len(), hosts[index].name.as_str()), (2, "laptop"));
+
+        let (hosts, index) = two_hosts().resolve_hosts_with(Some("http://other:11434".to_string()));
+        assert_eq!(index, 0);
+        assert_eq!(hosts[0], HostProfile { name: "OLLAMA_HOST".to_string(), url: "http://other:11434".to_string() });
+        assert_eq!(hosts.len(), 3);
+    }
+
+    #[test]
+    fn config_path_follows_xdg_config_home() {
+        let home = Some(PathBuf::from("/home/me"));
+        assert_eq!(
+            config_path_with(Some("/tmp/cfg".into()), home.clone()),
+            Some(PathBuf::from("/tmp/cfg/lazyollama/config.toml"))
+        );
+        assert_eq!(
+            config_path_with(None, home.clone()),
+            Some(PathBuf::from("/home/me/.config/lazyollama/config.toml"))
+        );
+        assert_eq!(
+            config_path_with(Some("relative/cfg".into()), home),
+            Some(PathBuf::from("/home/me/.config/lazyollama/config.toml"))
+        );
+        assert_eq!(config_path_with(None, None), None);
+    }
+
+    #[test]
+    fn dotenv_does_not_override_the_environment() {
+        // Variable names are unique to this test so parallel tests are unaffected.
+        unsafe { std::env::set_var("LAZYOLLAMA_TEST_FROM_ENV", "environment") };
+        let dotenv = "LAZYOLLAMA_TEST_FROM_ENV=dotenv\nLAZYOLLAMA_TEST_FROM_DOTENV=dotenv\n";
+        dotenvy::from_read(dotenv.as_bytes()).unwrap();
+        assert_eq!(std::env::var("LAZYOLLAMA_TEST_FROM_ENV").unwrap(), "environment");
+        assert_eq!(std::env::var("LAZYOLLAMA_TEST_FROM_DOTENV").unwrap(), "dotenv");
+    }
+}
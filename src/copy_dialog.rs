@@ -0,0 +1,161 @@
+// src/copy_dialog.rs
+// Name input for copying or renaming a local model, with validation of the new name.
+
+use crate::ollama_api::ModelInfo;
+use crate::text_input::TextInput;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMode {
+    Copy,
+    /// Copy, check the copy's digest, then delete the original.
+    Rename,
+}
+
+impl CopyMode {
+    pub fn title(self) -> &'static str {
+        match self {
+            CopyMode::Copy => "Copy Model",
+            CopyMode::Rename => "Rename Model",
+        }
+    }
+
+    /// Progressive form for status messages, e.g. "Copying a to b...".
+    pub fn verb(self) -> &'static str {
+        match self {
+            CopyMode::Copy => "Copying",
+            CopyMode::Rename => "Renaming",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CopyDialog {
+    pub mode: CopyMode,
+    pub source: String,
+    pub input: TextInput,
+}
+
+impl CopyDialog {
+    /// Starts with the source name, so only the part that changes needs typing.
+    pub fn new(mode: CopyMode, source: String) -> Self {
+        let mut input = TextInput::default();
+        input.set_text(&source);
+        Self { mode, source, input }
+    }
+
+    /// The full target name (`:latest` added if no tag is given), or why it can't be used.
+    pub fn target(&self, models: &[ModelInfo]) -> Result<String, String> {
+        let target = normalize_model_name(self.input.text().trim())?;
+        if target.eq_ignore_ascii_case(&self.source) {
+            return Err("Same as the current name".to_string());
+        }
+        // Ollama compares model names case-insensitively
+        if models.iter().any(|m| m.name.eq_ignore_ascii_case(&target)) {
+            return Err(format!("{} already exists", target));
+        }
+        Ok(target)
+    }
+}
+
+/// Checks a `[host[:port]/][namespace/]model[:tag]` name and adds the default tag if missing.
+pub fn normalize_model_name(name: &str) -> Result<String, String> {
+    if name.is_empty() {
+        return Err("Enter a name".to_string());
+    }
+    let (path, tag) = match name.rsplit_once(':') {
+        // A colon before the last slash belongs to a host port
+        Some((path, tag)) if !tag.contains('/') => (path, tag),
+        _ => (name, "latest"),
+    };
+    let parts: Vec<&str> = path.split('/').collect();
+    if parts.len() > 3 {
+        return Err("Use at most host/namespace/model".to_string());
+    }
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 && parts.len() > 1 {
+            check_host(part)?;
+        } else {
+            check_name_part(part, "Name")?;
+        }
+    }
+    check_name_part(tag, "Tag")?;
+    if tag.len() > 80 {
+        return Err("Tag is longer than 80 characters".to_string());
+    }
+    Ok(format!("{}:{}", path, tag))
+}
+
+/// A registry host, optionally with a port: `registry.example.com` or `localhost:5000`.
+fn check_host(part: &str) -> Result<(), String> {
+    let (host, port) = match part.split_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (part, None),
+    };
+    check_name_part(host, "Host")?;
+    if let Some(port) = port
+        && (!port.chars().all(|c| c.is_ascii_digit()) || !matches!(port.parse::<u16>(), Ok(1..)))
+    {
+        return Err(format!("Port '{}' must be a number from 1 to 65535", port));
+    }
+    Ok(())
+}
+
+fn check_name_part(part: &str, what: &str) -> Result<(), String> {
+    let Some(first) = part.chars().next() else {
+        return Err(format!("{} has an empty part", what));
+    };
+    if !first.is_ascii_alphanumeric() && first != '_' {
+        return Err(format!("{} parts must start with a letter, digit or '_'", what));
+    }
+    if let Some(c) = part.chars().find(|c| !c.is_ascii_alphanumeric() && !matches!(c, '_' | '-' | '.')) {
+        return Err(format!("'{}' is not allowed in a model name", c));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_the_default_tag() {
+        assert_eq!(normalize_model_name("llama3").unwrap(), "llama3:latest");
+        assert_eq!(normalize_model_name("llama3:8b-instruct-q4_K_M").unwrap(), "llama3:8b-instruct-q4_K_M");
+        assert_eq!(normalize_model_name("team/my-model:v1.2").unwrap(), "team/my-model:v1.2");
+        assert_eq!(normalize_model_name("team/my-model").unwrap(), "team/my-model:latest");
+    }
+
+    #[test]
+    fn accepts_a_registry_host_with_a_port() {
+        assert_eq!(
+            normalize_model_name("localhost:5000/team/model:tag").unwrap(),
+            "localhost:5000/team/model:tag"
+        );
+        assert_eq!(normalize_model_name("host:5000/model").unwrap(), "host:5000/model:latest");
+        assert_eq!(
+            normalize_model_name("registry.example.com/team/model").unwrap(),
+            "registry.example.com/team/model:latest"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        assert_eq!(normalize_model_name("").unwrap_err(), "Enter a name");
+        assert_eq!(normalize_model_name("my model").unwrap_err(), "' ' is not allowed in a model name");
+        assert_eq!(normalize_model_name("-model").unwrap_err(), "Name parts must start with a letter, digit or '_'");
+        assert_eq!(normalize_model_name("team//model").unwrap_err(), "Name has an empty part");
+        assert_eq!(normalize_model_name("model:").unwrap_err(), "Tag has an empty part");
+        assert_eq!(normalize_model_name("model:a:b").unwrap_err(), "':' is not allowed in a model name");
+        assert_eq!(normalize_model_name(&format!("model:{}", "t".repeat(81))).unwrap_err(), "Tag is longer than 80 characters");
+    }
+
+    #[test]
+    fn rejects_ports_outside_the_host() {
+        assert_eq!(normalize_model_name("team/model:5000/x").unwrap_err(), "':' is not allowed in a model name");
+        assert_eq!(normalize_model_name("host:http/model").unwrap_err(), "Port 'http' must be a number from 1 to 65535");
+        assert_eq!(normalize_model_name("host:0/model").unwrap_err(), "Port '0' must be a number from 1 to 65535");
+        assert_eq!(normalize_model_name("host:/model").unwrap_err(), "Port '' must be a number from 1 to 65535");
+        assert_eq!(normalize_model_name(":5000/model").unwrap_err(), "Host has an empty part");
+        assert_eq!(normalize_model_name("a:1/b/c/d").unwrap_err(), "Use at most host/namespace/model");
+    }
+}
@@ -0,0 +1,144 @@
+// src/derive.rs
+// State of the Modelfile editor that derives a new model from the selected one via /api/create.
+
+use crate::copy_dialog::normalize_model_name;
+use crate::modelfile::{derived_modelfile, Modelfile, ModelfileError};
+use crate::ollama_api::{CreateModelRequest, ModelInfo};
+use crate::text_input::TextInput;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeriveFocus {
+    Name,
+    Modelfile,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeriveEditor {
+    pub source: String,
+    pub name: TextInput,
+    pub modelfile: TextInput,
+    pub focus: DeriveFocus,
+    /// First visible line of the Modelfile; kept around the cursor when drawn.
+    pub scroll: u16,
+    /// Visible Modelfile lines, recorded when the editor is drawn.
+    pub page_height: u16,
+    /// Problems found in the Modelfile, or reported by the server.
+    pub errors: Vec<ModelfileError>,
+    /// Status lines streamed by `/api/create`, oldest first.
+    pub progress: Vec<String>,
+    /// The `/api/create` request in flight, if any.
+    pub request_id: Option<u64>,
+}
+
+impl DeriveEditor {
+    /// Starts from the source model's Modelfile, built on the source model by name.
+    pub fn new(source: String, modelfile: Option<&str>) -> Self {
+        let mut editor_text = TextInput::default();
+        editor_text.set_text(&derived_modelfile(modelfile, &source));
+        editor_text.move_lines(-(editor_text.cursor_line() as isize));
+        Self {
+            source,
+            name: TextInput::default(),
+            modelfile: editor_text,
+            focus: DeriveFocus::Name,
+            scroll: 0,
+            page_height: 0,
+            errors: Vec::new(),
+            progress: Vec::new(),
+            request_id: None,
+        }
+    }
+
+    pub fn is_creating(&self) -> bool {
+        self.request_id.is_some()
+    }
+
+    /// The field that typing and editing keys go to.
+    pub fn focused_input(&mut self) -> &mut TextInput {
+        match self.focus {
+            DeriveFocus::Name => &mut self.name,
+            DeriveFocus::Modelfile => &mut self.modelfile,
+        }
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            DeriveFocus::Name => DeriveFocus::Modelfile,
+            DeriveFocus::Modelfile => DeriveFocus::Name,
+        };
+    }
+
+    /// The full name of the new model, and whether it replaces an existing one.
+    pub fn target(&self, models: &[ModelInfo]) -> Result<(String, bool), String> {
+        let target = normalize_model_name(self.name.text().trim())?;
+        let exists = models.iter().any(|m| m.name.eq_ignore_ascii_case(&target));
+        Ok((target, exists))
+    }
+
+    /// Checks the name and the Modelfile. Problems are kept for display and the cursor
+    /// moves to the first one.
+    pub fn build_request(&mut self, models: &[ModelInfo]) -> Option<CreateModelRequest> {
+        let target = match self.target(models) {
+            Ok((target, _)) => target,
+            Err(message) => {
+                self.errors = vec![ModelfileError { line: None, message: format!("Name: {}", message) }];
+                self.focus = DeriveFocus::Name;
+                return None;
+            }
+        };
+        match Modelfile::parse(self.modelfile.text()) {
+            Ok(modelfile) => {
+                self.errors.clear();
+                Some(modelfile.into_request(target))
+            }
+            Err(errors) => {
+                if let Some(line) = errors.iter().find_map(|e| e.line) {
+                    self.focus = DeriveFocus::Modelfile;
+                    self.modelfile.move_lines(line as isize - 1 - self.modelfile.cursor_line() as isize);
+                }
+                self.errors = errors;
+                None
+            }
+        }
+    }
+
+    pub fn begin_create(&mut self, request_id: u64) {
+        self.request_id = Some(request_id);
+        self.progress.clear();
+        self.errors.clear();
+    }
+
+    pub fn push_progress(&mut self, request_id: u64, status: String) {
+        if self.request_id == Some(request_id) && self.progress.last() != Some(&status) {
+            self.progress.push(status);
+        }
+    }
+
+    /// Ends a failed request and shows the server's error where the Modelfile's own problems go.
+    pub fn fail_create(&mut self, request_id: u64, message: String) {
+        if self.request_id != Some(request_id) {
+            return;
+        }
+        self.request_id = None;
+        self.errors = vec![ModelfileError { line: None, message }];
+    }
+
+    /// Shows an error that isn't about a particular line, e.g. from `$EDITOR`.
+    pub fn show_error(&mut self, message: String) {
+        self.errors = vec![ModelfileError { line: None, message }];
+    }
+
+    /// Lines with problems, counting from 1.
+    pub fn error_lines(&self) -> Vec<usize> {
+        self.errors.iter().filter_map(|e| e.line).collect()
+    }
+
+    /// Replaces the Modelfile, e.g. after editing it in `$EDITOR`, keeping the cursor's line.
+    pub fn replace_modelfile(&mut self, text: &str) {
+        let line = self.modelfile.cursor_line();
+        self.modelfile.set_text(text);
+        self.modelfile.move_lines(line as isize - self.modelfile.cursor_line() as isize);
+        self.errors.clear();
+        self.focus = DeriveFocus::Modelfile;
+    }
+}
@@ -0,0 +1,125 @@
+// src/details.rs
+// State of the tabbed details pane: the open tab, per-tab scrolling and the in-pane search.
+
+use crate::text_input::TextInput;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DetailsTab {
+    #[default]
+    Overview,
+    Parameters,
+    Template,
+    Modelfile,
+    License,
+    ModelInfo,
+}
+
+impl DetailsTab {
+    pub const ALL: [DetailsTab; 6] = [
+        DetailsTab::Overview,
+        DetailsTab::Parameters,
+        DetailsTab::Template,
+        DetailsTab::Modelfile,
+        DetailsTab::License,
+        DetailsTab::ModelInfo,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            DetailsTab::Overview => "Overview",
+            DetailsTab::Parameters => "Parameters",
+            DetailsTab::Template => "Template",
+            DetailsTab::Modelfile => "Modelfile",
+            DetailsTab::License => "License",
+            DetailsTab::ModelInfo => "Model Info",
+        }
+    }
+
+    /// Used when the full titles don't fit in the pane.
+    pub fn short_title(self) -> &'static str {
+        match self {
+            DetailsTab::Parameters => "Params",
+            DetailsTab::ModelInfo => "Info",
+            other => other.title(),
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&t| t == self).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DetailsPane {
+    pub tab: DetailsTab,
+    /// Lines scrolled down, per tab.
+    scroll: [u16; DetailsTab::ALL.len()],
+    /// Largest useful scroll of the open tab, recorded when the pane is drawn.
+    pub max_scroll: u16,
+    /// Visible lines of the open tab, recorded when the pane is drawn.
+    pub page_height: u16,
+    pub search: TextInput,
+    /// Matches of the search in the open tab, recorded when the pane is drawn.
+    pub match_count: usize,
+    pub current_match: usize,
+    /// Scroll the current match into view on the next draw.
+    pub reveal_match: bool,
+}
+
+impl DetailsPane {
+    pub fn scroll(&self) -> u16 {
+        self.scroll[self.tab.index()]
+    }
+
+    pub fn set_scroll(&mut self, scroll: u16) {
+        self.scroll[self.tab.index()] = scroll.min(self.max_scroll);
+    }
+
+    pub fn scroll_by(&mut self, lines: i32) {
+        let scroll = i32::from(self.scroll()) + lines;
+        self.set_scroll(scroll.clamp(0, i32::from(u16::MAX)) as u16);
+    }
+
+    pub fn scroll_page(&mut self, pages: i32) {
+        let page = i32::from(self.page_height.saturating_sub(1).max(1));
+        self.scroll_by(pages * page);
+    }
+
+    /// Moves `steps` tabs to the right (negative: left), wrapping around.
+    pub fn cycle_tab(&mut self, steps: i32) {
+        let count = DetailsTab::ALL.len() as i32;
+        let index = (self.tab.index() as i32 + steps).rem_euclid(count);
+        self.select_tab(DetailsTab::ALL[index as usize]);
+    }
+
+    pub fn select_tab(&mut self, tab: DetailsTab) {
+        self.tab = tab;
+        self.restart_search();
+    }
+
+    /// Goes back to the first match, e.g. after the search text changed.
+    pub fn restart_search(&mut self) {
+        self.current_match = 0;
+        self.reveal_match = true;
+    }
+
+    /// Back to the top of every tab, e.g. after another model was selected. The open tab is kept.
+    pub fn reset_scroll(&mut self) {
+        self.scroll = Default::default();
+        self.current_match = 0;
+    }
+
+    pub fn is_searching(&self) -> bool {
+        !self.search.is_empty()
+    }
+
+    /// Moves to the next match (negative: previous), wrapping around.
+    pub fn step_match(&mut self, steps: i32) {
+        if self.match_count == 0 {
+            return;
+        }
+        let index = (self.current_match as i64 + i64::from(steps)).rem_euclid(self.match_count as i64);
+        self.current_match = index as usize;
+        self.reveal_match = true;
+    }
+}
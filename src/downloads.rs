@@ -0,0 +1,447 @@
+// src/downloads.rs
+// Background download queue and progress tracking for model pulls.
+
+use crate::ollama_api::PullProgress;
+use ratatui::widgets::ListState;
+use std::time::{Duration, Instant};
+use tokio::task::AbortHandle;
+
+#[derive(Debug, Clone)]
+pub struct LayerProgress {
+    pub digest: String,
+    pub total: u64,
+    pub completed: u64,
+    /// Bytes already present when the layer was first reported (resumed downloads).
+    initial_completed: u64,
+}
+
+impl LayerProgress {
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (self.completed as f64 / self.total as f64).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Aggregated progress of a single `/api/pull` stream.
+#[derive(Debug, Clone)]
+pub struct PullProgressState {
+    pub model: String,
+    pub status: String,
+    pub layers: Vec<LayerProgress>,
+    pub started_at: Instant,
+}
+
+impl PullProgressState {
+    pub fn new(model: String) -> Self {
+        Self {
+            model,
+            status: "Starting pull...".to_string(),
+            layers: Vec::new(),
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Applies a progress line from the pull stream.
+    pub fn update(&mut self, progress: &PullProgress) {
+        self.status = progress.status.clone();
+
+        let (Some(digest), Some(total)) = (&progress.digest, progress.total) else {
+            return;
+        };
+        let completed = progress.completed.unwrap_or(0);
+
+        match self.layers.iter_mut().find(|l| &l.digest == digest) {
+            Some(layer) => {
+                layer.total = total;
+                layer.completed = completed.max(layer.completed);
+            }
+            None => self.layers.push(LayerProgress {
+                digest: digest.clone(),
+                total,
+                completed,
+                initial_completed: completed,
+            }),
+        }
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.layers.iter().map(|l| l.total).sum()
+    }
+
+    pub fn completed_bytes(&self) -> u64 {
+        self.layers.iter().map(|l| l.completed).sum()
+    }
+
+    pub fn ratio(&self) -> f64 {
+        let total = self.total_bytes();
+        if total == 0 {
+            0.0
+        } else {
+            (self.completed_bytes() as f64 / total as f64).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Download rate in bytes per second, ignoring bytes that were already on disk.
+    pub fn bytes_per_sec(&self) -> f64 {
+        let elapsed = self.started_at.elapsed().as_secs_f64();
+        if elapsed <= 0.0 {
+            return 0.0;
+        }
+        let downloaded: u64 = self
+            .layers
+            .iter()
+            .map(|l| l.completed.saturating_sub(l.initial_completed))
+            .sum();
+        downloaded as f64 / elapsed
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        let rate = self.bytes_per_sec();
+        if rate < 1.0 {
+            return None;
+        }
+        let remaining = self.total_bytes().saturating_sub(self.completed_bytes());
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+}
+
+/// Formats a duration as `h:mm:ss` or `m:ss`.
+pub fn format_eta(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    Pending,
+    Active,
+    Failed,
+    Cancelled,
+    Finished,
+}
+
+impl JobState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobState::Pending => "pending",
+            JobState::Active => "active",
+            JobState::Failed => "failed",
+            JobState::Cancelled => "cancelled",
+            JobState::Finished => "done",
+        }
+    }
+}
+
+/// A queued `name:tag` pull and its progress.
+#[derive(Debug, Clone)]
+pub struct DownloadJob {
+    pub id: u64,
+    pub model: String,
+    /// URL of the Ollama server the model is pulled onto.
+    pub host: String,
+    pub state: JobState,
+    pub progress: PullProgressState,
+    pub error: Option<String>,
+    /// Aborting the task drops the HTTP stream, which cancels the pull server-side.
+    pub abort_handle: Option<AbortHandle>,
+}
+
+pub const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 2;
+
+/// Pulls run in the background; at most `max_concurrent` jobs are active at once.
+#[derive(Debug, Clone)]
+pub struct DownloadQueue {
+    pub jobs: Vec<DownloadJob>,
+    pub max_concurrent: usize,
+    pub list_state: ListState,
+    next_id: u64,
+}
+
+impl DownloadQueue {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            jobs: Vec::new(),
+            max_concurrent: max_concurrent.max(1),
+            list_state: ListState::default(),
+            next_id: 1,
+        }
+    }
+
+    /// Adds a pull to the queue. Returns `None` if the model is already pending
+    /// or active on the same host.
+    pub fn enqueue(&mut self, model: String, host: String) -> Option<u64> {
+        let duplicate = self.jobs.iter().any(|j| {
+            j.model == model && j.host == host && matches!(j.state, JobState::Pending | JobState::Active)
+        });
+        if duplicate {
+            return None;
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.jobs.push(DownloadJob {
+            id,
+            progress: PullProgressState::new(model.clone()),
+            model,
+            host,
+            state: JobState::Pending,
+            error: None,
+            abort_handle: None,
+        });
+        if self.list_state.selected().is_none() {
+            self.list_state.select(Some(0));
+        }
+        Some(id)
+    }
+
+    pub fn job_mut(&mut self, id: u64) -> Option<&mut DownloadJob> {
+        self.jobs.iter_mut().find(|j| j.id == id)
+    }
+
+    pub fn count(&self, state: JobState) -> usize {
+        self.jobs.iter().filter(|j| j.state == state).count()
+    }
+
+    /// Marks as many pending jobs active as the concurrency limit allows and
+    /// returns them so the caller can spawn their pulls.
+    pub fn take_startable(&mut self) -> Vec<DownloadJob> {
+        let mut free = self.max_concurrent.saturating_sub(self.count(JobState::Active));
+        let mut started = Vec::new();
+        for job in self.jobs.iter_mut() {
+            if free == 0 {
+                break;
+            }
+            if job.state == JobState::Pending {
+                job.state = JobState::Active;
+                job.progress = PullProgressState::new(job.model.clone());
+                started.push(job.clone());
+                free -= 1;
+            }
+        }
+        started
+    }
+
+    pub fn update_progress(&mut self, id: u64, progress: &PullProgress) {
+        if let Some(job) = self.job_mut(id)
+            && job.state == JobState::Active
+        {
+            job.progress.update(progress);
+        }
+    }
+
+    pub fn complete(&mut self, id: u64, error: Option<String>) {
+        if let Some(job) = self.job_mut(id) {
+            // A cancelled job may still report back if it finished before the abort landed.
+            if job.state != JobState::Active {
+                return;
+            }
+            job.abort_handle = None;
+            job.state = if error.is_some() { JobState::Failed } else { JobState::Finished };
+            job.error = error;
+        }
+    }
+
+    pub fn selected(&self) -> Option<&DownloadJob> {
+        self.list_state.selected().and_then(|i| self.jobs.get(i))
+    }
+
+    pub fn cancel_selected(&mut self) -> Option<String> {
+        let index = self.list_state.selected()?;
+        let job = self.jobs.get_mut(index)?;
+        if !matches!(job.state, JobState::Pending | JobState::Active) {
+            return None;
+        }
+        if let Some(handle) = job.abort_handle.take() {
+            handle.abort();
+        }
+        job.state = JobState::Cancelled;
+        Some(job.model.clone())
+    }
+
+    pub fn retry_selected(&mut self) -> Option<String> {
+        let index = self.list_state.selected()?;
+        let job = self.jobs.get_mut(index)?;
+        if !matches!(job.state, JobState::Failed | JobState::Cancelled) {
+            return None;
+        }
+        job.state = JobState::Pending;
+        job.error = None;
+        job.progress = PullProgressState::new(job.model.clone());
+        Some(job.model.clone())
+    }
+
+    /// Removes finished, failed and cancelled jobs from the list.
+    pub fn clear_inactive(&mut self) {
+        self.jobs
+            .retain(|j| matches!(j.state, JobState::Pending | JobState::Active));
+        self.clamp_selection();
+    }
+
+    pub fn next(&mut self) {
+        if self.jobs.is_empty() {
+            return;
+        }
+        let i = self.list_state.selected().map_or(0, |i| (i + 1) % self.jobs.len());
+        self.list_state.select(Some(i));
+    }
+
+    pub fn previous(&mut self) {
+        if self.jobs.is_empty() {
+            return;
+        }
+        let len = self.jobs.len();
+        let i = self.list_state.selected().map_or(len - 1, |i| (i + len - 1) % len);
+        self.list_state.select(Some(i));
+    }
+
+    fn clamp_selection(&mut self) {
+        if self.jobs.is_empty() {
+            self.list_state.select(None);
+        } else {
+            let i = self.list_state.selected().unwrap_or(0).min(self.jobs.len() - 1);
+            self.list_state.select(Some(i));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCAL: &str = "http://localhost:11434";
+
+    fn queue(max_concurrent: usize, models: &[&str]) -> DownloadQueue {
+        let mut queue = DownloadQueue::new(max_concurrent);
+        for model in models {
+            queue.enqueue(model.to_string(), LOCAL.to_string()).unwrap();
+        }
+        queue
+    }
+
+    fn started(queue: &mut DownloadQueue) -> Vec<String> {
+        queue.take_startable().into_iter().map(|job| job.model).collect()
+    }
+
+    fn state(queue: &DownloadQueue, model: &str) -> JobState {
+        queue.jobs.iter().find(|j| j.model == model).unwrap().state
+    }
+
+    fn layer(digest: &str, total: u64, completed: u64) -> PullProgress {
+        PullProgress {
+            status: format!("pulling {}", digest),
+            digest: Some(digest.to_string()),
+            total: Some(total),
+            completed: Some(completed),
+        }
+    }
+
+    #[test]
+    fn enqueue_skips_models_already_queued() {
+        let mut queue = queue(1, &["llama3:8b"]);
+        assert_eq!(queue.enqueue("llama3:8b".to_string(), LOCAL.to_string()), None);
+        assert_eq!(queue.enqueue("mistral:7b".to_string(), LOCAL.to_string()), Some(2));
+        assert_eq!(queue.list_state.selected(), Some(0));
+        // The same model may be pulled onto another host
+        assert_eq!(queue.enqueue("llama3:8b".to_string(), "http://gpu:11434".to_string()), Some(3));
+
+        // Once the first pull is over it can be queued again
+        let id = queue.take_startable()[0].id;
+        queue.complete(id, None);
+        assert_eq!(queue.enqueue("llama3:8b".to_string(), LOCAL.to_string()), Some(4));
+    }
+
+    #[test]
+    fn starts_no_more_than_the_limit() {
+        let mut queue = queue(2, &["a", "b", "c"]);
+        assert_eq!(started(&mut queue), ["a", "b"]);
+        assert!(started(&mut queue).is_empty());
+        assert_eq!(queue.count(JobState::Active), 2);
+
+        // A finished pull frees its slot for the next pending one
+        queue.complete(1, Some("connection reset".to_string()));
+        assert_eq!(started(&mut queue), ["c"]);
+        assert_eq!(state(&queue, "a"), JobState::Failed);
+        assert_eq!(queue.jobs[0].error.as_deref(), Some("connection reset"));
+        assert_eq!(DownloadQueue::new(0).max_concurrent, 1);
+    }
+
+    #[test]
+    fn cancelled_jobs_ignore_late_results() {
+        let mut queue = queue(1, &["a", "b"]);
+        started(&mut queue);
+        assert_eq!(queue.cancel_selected().as_deref(), Some("a"));
+        assert_eq!(queue.cancel_selected(), None);
+
+        // The pull may still report before the abort lands
+        queue.update_progress(1, &layer("sha256:1", 10, 5));
+        queue.complete(1, None);
+        assert_eq!(state(&queue, "a"), JobState::Cancelled);
+        assert!(queue.jobs[0].progress.layers.is_empty());
+        assert_eq!(started(&mut queue), ["b"]);
+    }
+
+    #[test]
+    fn retry_queues_failed_and_cancelled_jobs_again() {
+        let mut queue = queue(1, &["a", "b"]);
+        started(&mut queue);
+        assert_eq!(queue.retry_selected(), None);
+        queue.complete(1, Some("disk full".to_string()));
+        assert_eq!(queue.retry_selected().as_deref(), Some("a"));
+        assert_eq!(state(&queue, "a"), JobState::Pending);
+        assert_eq!(queue.jobs[0].error, None);
+
+        queue.next();
+        assert_eq!(queue.cancel_selected().as_deref(), Some("b"));
+        assert_eq!(queue.retry_selected().as_deref(), Some("b"));
+        // Retried jobs start in list order
+        assert_eq!(started(&mut queue), ["a"]);
+    }
+
+    #[test]
+    fn clear_inactive_keeps_running_jobs_and_a_valid_selection() {
+        let mut queue = queue(1, &["a", "b", "c"]);
+        started(&mut queue);
+        queue.complete(1, None);
+        queue.previous();
+        assert_eq!(queue.selected().map(|j| j.model.as_str()), Some("c"));
+        queue.cancel_selected();
+
+        queue.clear_inactive();
+        let left: Vec<&str> = queue.jobs.iter().map(|j| j.model.as_str()).collect();
+        assert_eq!(left, ["b"]);
+        assert_eq!(queue.list_state.selected(), Some(0));
+
+        queue.cancel_selected();
+        queue.clear_inactive();
+        assert_eq!(queue.list_state.selected(), None);
+    }
+
+    #[test]
+    fn progress_sums_layers() {
+        let mut progress = PullProgressState::new("llama3:8b".to_string());
+        progress.update(&layer("sha256:1", 100, 40));
+        progress.update(&layer("sha256:2", 300, 0));
+        // Lines arriving out of order never move a layer backwards
+        progress.update(&layer("sha256:1", 100, 30));
+        progress.update(&PullProgress { status: "verifying sha256 digest".to_string(), digest: None, total: None, completed: None });
+        assert_eq!(progress.status, "verifying sha256 digest");
+        assert_eq!((progress.completed_bytes(), progress.total_bytes()), (40, 400));
+        assert_eq!(progress.ratio(), 0.1);
+        assert_eq!(progress.layers[0].ratio(), 0.4);
+    }
+
+    #[test]
+    fn eta_formats() {
+        assert_eq!(format_eta(Duration::from_secs(75)), "1:15");
+        assert_eq!(format_eta(Duration::from_secs(3600 + 5)), "1:00:05");
+        assert_eq!(PullProgressState::new("a".to_string()).eta(), None);
+    }
+}
@@ -16,6 +16,9 @@ pub enum AppError {
 
     #[error("External command error: {0}")]
     Command(String),
+
+    #[error("Config error: {0}")]
+    Config(String),
     // Add other application-specific errors here if needed
 }
 
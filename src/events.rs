@@ -1,15 +1,39 @@
 use crate::{
-    error::Result,
-    ollama_api::{ModelInfo, ShowModelResponse},
+    copy_dialog::CopyMode,
+    error::{AppError, Result},
+    ollama_api::{CreateProgress, ModelInfo, PullProgress, RunningModel, ShowModelResponse, TensorInfo},
+    registry_api::Manifest,
 };
 
-/// Define the types of events that can be sent from async tasks to the main loop
+/// Define the types of events that can be sent from async tasks to the main loop.
+/// Events that carry a host URL are dropped if the active host changed meanwhile.
 #[derive(Debug)]
 pub enum AppEvent {
-    ModelDetailsFetched(Result<ShowModelResponse>),
+    /// Host, request id, model name and its `/api/show` response.
+    ModelDetailsFetched(String, u64, String, Box<Result<ShowModelResponse>>),
+    /// Host, model name and the tensors of its verbose `/api/show` response.
+    TensorsFetched(String, String, Result<Vec<TensorInfo>>),
     RegistryModelsFetched(Result<Vec<String>>),
     RegistryTagsFetched(Result<Vec<String>>),
-    ModelPullCompleted(Result<()>),
-    LocalModelsRefreshed(Result<Vec<ModelInfo>>),
+    RegistryManifestFetched(String, String, Result<Manifest>),
+    UpdateChecked(String, Result<String>),
+    UpdateCheckFinished,
+    DownloadProgress(u64, PullProgress),
+    DownloadCompleted(u64, Result<()>),
+    ModelDeleteFailed(AppError),
+    /// Host, copy or rename, source and target name, and the outcome.
+    ModelCopied(String, CopyMode, String, String, Result<()>),
+    LocalModelsRefreshed(String, Result<Vec<ModelInfo>>),
+    RunningModelsFetched(String, Result<Vec<RunningModel>>),
+    InventoryFetched(u64, String, Result<Vec<ModelInfo>>),
+    KeepAliveUpdated(String, Option<i64>, Result<()>),
     OllamaRunCompleted(Result<()>),
+    /// The Modelfile as saved in `$EDITOR`.
+    ExternalEditClosed(Result<String>),
+    /// Request id and a status line of `/api/create`.
+    CreateProgress(u64, CreateProgress),
+    /// Host, request id, name of the new model and the outcome.
+    CreateCompleted(String, u64, String, Result<()>),
+    ChatToken(u64, String),
+    ChatCompleted(u64, Result<()>),
 }
\ No newline at end of file
@@ -0,0 +1,374 @@
+// src/filter_query.rs
+// Parses and evaluates model list queries like `family:llama size:>4GB -embed`.
+
+use crate::fuzzy::{self, Match, MatchMode};
+use crate::model_table::{modified_time, parse_parameter_count, split_name_tag};
+use crate::ollama_api::{ModelInfo, ShowModelResponse};
+use chrono::{DateTime, Duration, Utc};
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    /// Within 5%, since sizes are shown rounded.
+    About,
+}
+
+impl Comparison {
+    /// Splits a leading `<`, `<=`, `>`, `>=` or `=` off `value`.
+    fn split(value: &str) -> (Self, &str) {
+        for (prefix, comparison) in [
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+            ("=", Comparison::About),
+        ] {
+            if let Some(rest) = value.strip_prefix(prefix) {
+                return (comparison, rest);
+            }
+        }
+        (Comparison::About, value)
+    }
+
+    fn test(self, actual: f64, expected: f64) -> bool {
+        match self {
+            Comparison::Less => actual < expected,
+            Comparison::LessOrEqual => actual <= expected,
+            Comparison::Greater => actual > expected,
+            Comparison::GreaterOrEqual => actual >= expected,
+            Comparison::About => (actual - expected).abs() <= expected.abs() * 0.05,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    /// Bare word: matches the name (fuzzy), family or a known capability.
+    Text(String),
+    Name(String),
+    Tag(String),
+    Family(String),
+    Quantization(String),
+    Capability(String),
+    Size(Comparison, f64),
+    Parameters(Comparison, f64),
+    /// Age of the model, e.g. `modified:<30d` for models changed in the last 30 days.
+    Modified(Comparison, Duration),
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    negated: bool,
+    condition: Condition,
+}
+
+/// A term that could not be parsed, with its byte range in the query.
+#[derive(Debug, Clone)]
+pub struct QueryError {
+    pub range: Range<usize>,
+    pub message: String,
+}
+
+/// A parsed query. Terms are ANDed; invalid terms are reported and ignored.
+#[derive(Debug, Clone, Default)]
+pub struct FilterQuery {
+    terms: Vec<Term>,
+    mode: MatchMode,
+    pub errors: Vec<QueryError>,
+}
+
+/// Parses sizes like `4GB`, `512MiB` or `1.5g` into bytes.
+fn parse_size(value: &str) -> Option<f64> {
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let scale = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "k" | "kb" => 1e3,
+        "m" | "mb" => 1e6,
+        "g" | "gb" => 1e9,
+        "t" | "tb" => 1e12,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    number.parse::<f64>().ok().map(|n| n * scale)
+}
+
+/// Parses ages like `12h`, `30d`, `2w`, `6m` (months) or `1y`.
+fn parse_age(value: &str) -> Option<Duration> {
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: i64 = number.parse().ok()?;
+    match unit {
+        "h" => Some(Duration::hours(number)),
+        "d" | "" => Some(Duration::days(number)),
+        "w" => Some(Duration::weeks(number)),
+        "m" => Some(Duration::days(number * 30)),
+        "y" => Some(Duration::days(number * 365)),
+        _ => None,
+    }
+}
+
+fn parse_condition(word: &str) -> Result<Condition, String> {
+    // Unknown prefixes are plain text, since model names contain colons (`llama3:8b`)
+    let Some((field, value)) = word.split_once(':') else {
+        return Ok(Condition::Text(word.to_lowercase()));
+    };
+    let field = field.to_lowercase();
+    let text_field = |make: fn(String) -> Condition| {
+        if value.is_empty() {
+            Err(format!("{}: missing value", field))
+        } else {
+            Ok(make(value.to_lowercase()))
+        }
+    };
+    match field.as_str() {
+        "name" => text_field(Condition::Name),
+        "tag" => text_field(Condition::Tag),
+        "family" => text_field(Condition::Family),
+        "quant" | "quantization" => text_field(Condition::Quantization),
+        "cap" | "capability" => text_field(Condition::Capability),
+        "size" => {
+            let (comparison, rest) = Comparison::split(value);
+            parse_size(rest)
+                .map(|bytes| Condition::Size(comparison, bytes))
+                .ok_or_else(|| "size: expected a size like >4GB or <500MB".to_string())
+        }
+        "params" | "parameters" => {
+            let (comparison, rest) = Comparison::split(value);
+            parse_parameter_count(rest)
+                .map(|count| Condition::Parameters(comparison, count))
+                .ok_or_else(|| "params: expected a count like <10B or >500M".to_string())
+        }
+        "modified" | "age" => {
+            // A bare age means "within": `modified:7d` is `modified:<7d`
+            let (comparison, rest) = match Comparison::split(value) {
+                (Comparison::About, rest) if !value.starts_with('=') => (Comparison::LessOrEqual, rest),
+                split => split,
+            };
+            parse_age(rest)
+                .map(|age| Condition::Modified(comparison, age))
+                .ok_or_else(|| "modified: expected an age like <30d, >6m or <12h".to_string())
+        }
+        _ => Ok(Condition::Text(word.to_lowercase())),
+    }
+}
+
+impl FilterQuery {
+    pub fn parse(input: &str, mode: MatchMode) -> Self {
+        let mut query = Self { mode, ..Self::default() };
+        let mut offset = 0;
+        for word in input.split(' ') {
+            let range = offset..offset + word.len();
+            offset += word.len() + 1;
+
+            let (negated, body) = match word.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, word),
+            };
+            if body.is_empty() {
+                continue;
+            }
+            match parse_condition(body) {
+                Ok(condition) => query.terms.push(Term { negated, condition }),
+                Err(message) => query.errors.push(QueryError { range, message }),
+            }
+        }
+        query
+    }
+
+    /// True if some term can only be answered with `/api/show` data (capabilities).
+    pub fn needs_show_details(&self) -> bool {
+        self.terms
+            .iter()
+            .any(|t| matches!(t.condition, Condition::Capability(_) | Condition::Text(_)))
+    }
+
+    /// Checks every term against the model. `show` adds capabilities and fills in
+    /// details missing from the model list; without it capability terms don't match.
+    /// The result sums the name match scores and collects the matched name characters.
+    pub fn match_model(&self, model: &ModelInfo, show: Option<&ShowModelResponse>, now: DateTime<Utc>) -> Option<Match> {
+        let mut total = Match::default();
+        for term in &self.terms {
+            if term.negated {
+                // Exclusions match exactly, so `-embed` doesn't drop every name with an e, m, b...
+                if term.condition.evaluate(model, show, now, MatchMode::Substring).is_some() {
+                    return None;
+                }
+                continue;
+            }
+            let found = term.condition.evaluate(model, show, now, self.mode)?;
+            total.score += found.score;
+            total.positions.extend(found.positions);
+        }
+        total.positions.sort_unstable();
+        total.positions.dedup();
+        Some(total)
+    }
+}
+
+fn contains(haystack: Option<&str>, needle: &str) -> bool {
+    haystack.is_some_and(|h| h.to_lowercase().contains(needle))
+}
+
+impl Condition {
+    /// Name conditions return their match; the others an empty match when they hold.
+    fn evaluate(
+        &self,
+        model: &ModelInfo,
+        show: Option<&ShowModelResponse>,
+        now: DateTime<Utc>,
+        mode: MatchMode,
+    ) -> Option<Match> {
+        let details = model
+            .details
+            .as_ref()
+            .or_else(|| show.and_then(|s| s.details.as_ref()));
+        let capabilities = show.map(|s| s.capabilities.as_slice()).unwrap_or_default();
+        let has_capability = |needle: &str| capabilities.iter().any(|c| c.name().eq_ignore_ascii_case(needle));
+        let family_matches = |needle: &str| {
+            details.is_some_and(|d| {
+                contains(d.family.as_deref(), needle)
+                    || d.families.iter().flatten().any(|f| contains(Some(f), needle))
+            })
+        };
+
+        let holds = match self {
+            Condition::Text(text) => {
+                if let Some(found) = fuzzy::find(mode, text, &model.name) {
+                    return Some(found);
+                }
+                family_matches(text) || has_capability(text)
+            }
+            // The model part is a prefix of the name, so positions line up
+            Condition::Name(text) => return fuzzy::find(mode, text, split_name_tag(&model.name).0),
+            Condition::Tag(text) => contains(Some(split_name_tag(&model.name).1), text),
+            Condition::Family(text) => family_matches(text),
+            Condition::Quantization(text) => contains(details.and_then(|d| d.quantization_level.as_deref()), text),
+            Condition::Capability(text) => has_capability(text),
+            Condition::Size(comparison, bytes) => comparison.test(model.size as f64, *bytes),
+            Condition::Parameters(comparison, count) => details
+                .and_then(|d| parse_parameter_count(d.parameter_size.as_deref()?))
+                .is_some_and(|actual| comparison.test(actual, *count)),
+            Condition::Modified(comparison, age) => modified_time(model).is_some_and(|time| {
+                let actual = (now - time).num_seconds() as f64;
+                comparison.test(actual, age.num_seconds() as f64)
+            }),
+        };
+        holds.then(Match::default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ollama_api::ModelExtraDetails;
+
+    fn model(name: &str, size: u64, family: &str, parameters: &str) -> ModelInfo {
+        ModelInfo {
+            name: name.to_string(),
+            modified_at: "2024-05-01T10:00:00Z".to_string(),
+            size,
+            digest: "abc".to_string(),
+            details: Some(ModelExtraDetails {
+                format: Some("gguf".to_string()),
+                family: Some(family.to_string()),
+                families: None,
+                parameter_size: Some(parameters.to_string()),
+                quantization_level: Some("Q4_0".to_string()),
+            }),
+        }
+    }
+
+    /// Ten days after the fixtures were modified.
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-05-11T10:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn matches(query: &str, model: &ModelInfo) -> bool {
+        FilterQuery::parse(query, MatchMode::Fuzzy).match_model(model, None, now()).is_some()
+    }
+
+    #[test]
+    fn negated_terms_exclude() {
+        let llama = model("llama3:8b", 4_000_000_000, "llama", "8.0B");
+        let embed = model("nomic-embed-text:latest", 270_000_000, "nomic-bert", "137M");
+        assert!(!matches("-embed", &embed));
+        assert!(matches("-embed", &llama));
+        // Exclusions aren't fuzzy: "lm3" fuzzy-matches llama3 but isn't part of the name
+        assert!(matches("l3", &llama));
+        assert!(matches("-lm3", &llama));
+        assert!(!matches("-family:llama", &llama));
+        assert!(matches("family:llama -8b", &model("llama3:70b", 40_000_000_000, "llama", "70B")));
+        // A lone `-` is still being typed, not a term
+        assert!(matches("llama -", &llama));
+    }
+
+    #[test]
+    fn sizes_take_units_and_comparisons() {
+        assert_eq!(parse_size("4GB"), Some(4e9));
+        assert_eq!(parse_size("1.5g"), Some(1.5e9));
+        assert_eq!(parse_size("512MiB"), Some(512.0 * 1024.0 * 1024.0));
+        assert_eq!(parse_size("2tib"), Some(2.0 * 1024f64.powi(4)));
+        assert_eq!(parse_size("100"), Some(100.0));
+        assert_eq!(parse_size("4XB"), None);
+
+        let llama = model("llama3:8b", 4_000_000_000, "llama", "8.0B");
+        assert!(matches("size:>3GB", &llama));
+        assert!(matches("size:<=4GB", &llama));
+        assert!(!matches("size:<4GB", &llama));
+        // A bare size allows the 5% lost to rounding in the list
+        assert!(matches("size:3.9GB", &llama));
+        assert!(!matches("size:3.5GB", &llama));
+    }
+
+    #[test]
+    fn parameters_and_age() {
+        let llama = model("llama3:8b", 4_000_000_000, "llama", "8.0B");
+        assert!(matches("params:<10B", &llama));
+        assert!(!matches("params:>=70B", &llama));
+        assert!(matches("modified:<30d", &llama));
+        // A bare age means "within"
+        assert!(!matches("modified:7d", &llama));
+        assert!(matches("modified:>1w", &llama));
+    }
+
+    #[test]
+    fn errors_point_at_the_invalid_term() {
+        let input = "llama size:>4XB  -params:many family:";
+        let query = FilterQuery::parse(input, MatchMode::Fuzzy);
+        let spans: Vec<&str> = query.errors.iter().map(|e| &input[e.range.clone()]).collect();
+        assert_eq!(spans, ["size:>4XB", "-params:many", "family:"]);
+        assert_eq!(query.errors[0].message, "size: expected a size like >4GB or <500MB");
+        assert_eq!(query.errors[2].message, "family: missing value");
+
+        // The valid terms still filter
+        assert!(query.match_model(&model("llama3:8b", 1, "llama", "8B"), None, now()).is_some());
+        assert!(query.match_model(&model("mistral:7b", 1, "mistral", "7B"), None, now()).is_none());
+    }
+
+    #[test]
+    fn unknown_fields_are_plain_text() {
+        let query = FilterQuery::parse("llama3:8b", MatchMode::Substring);
+        assert!(query.errors.is_empty());
+        assert!(query.match_model(&model("llama3:8b", 1, "llama", "8B"), None, now()).is_some());
+        assert!(query.match_model(&model("llama3:70b", 1, "llama", "70B"), None, now()).is_none());
+    }
+
+    #[test]
+    fn name_matches_add_up_and_keep_positions() {
+        let query = FilterQuery::parse("lam name:3 family:llama", MatchMode::Fuzzy);
+        let found = query.match_model(&model("llama3:8b", 1, "llama", "8B"), None, now()).unwrap();
+        // `lam` and `3` both land in the name; the family term adds nothing
+        assert_eq!(found.positions, [1, 2, 3, 5]);
+        assert!(found.score > 0);
+    }
+}
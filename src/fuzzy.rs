@@ -0,0 +1,236 @@
+// src/fuzzy.rs
+// fzf-style fuzzy matching used by the model and registry filters.
+
+use serde::Deserialize;
+use std::ops::Range;
+
+/// How filter text is matched against names.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    /// Pattern characters in order, not necessarily adjacent (`l3i` finds `llama3:instruct`).
+    #[default]
+    Fuzzy,
+    /// Case-insensitive substring, as before fuzzy matching.
+    Substring,
+}
+
+/// A successful match: higher scores rank first. Positions are char indices into the text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Match {
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+const SCORE_MATCH: i64 = 16;
+const PENALTY_GAP_START: i64 = 3;
+const PENALTY_GAP_EXTENSION: i64 = 1;
+/// Matching right after a separator such as `/`, `:`, `-` or `_`.
+const BONUS_BOUNDARY: i64 = 8;
+const BONUS_CAMEL: i64 = 7;
+const BONUS_CONSECUTIVE: i64 = 4;
+/// The first pattern character's bonus counts this many times.
+const FIRST_CHAR_MULTIPLIER: i64 = 2;
+
+#[derive(Clone, Copy, PartialEq)]
+enum CharClass {
+    Separator,
+    Lower,
+    Upper,
+    Digit,
+    Other,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_lowercase() {
+        CharClass::Lower
+    } else if c.is_uppercase() {
+        CharClass::Upper
+    } else if c.is_numeric() {
+        CharClass::Digit
+    } else if c.is_whitespace() || "/:-_.".contains(c) {
+        CharClass::Separator
+    } else {
+        CharClass::Other
+    }
+}
+
+fn boundary_bonus(previous: CharClass, current: CharClass) -> i64 {
+    match (previous, current) {
+        (CharClass::Separator, c) if c != CharClass::Separator => BONUS_BOUNDARY,
+        (CharClass::Lower, CharClass::Upper) => BONUS_CAMEL,
+        (p, CharClass::Digit) if p != CharClass::Digit => BONUS_CAMEL,
+        _ => 0,
+    }
+}
+
+fn same_char(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Matches `pattern` against `text` in the given mode. An empty pattern matches everything.
+pub fn find(mode: MatchMode, pattern: &str, text: &str) -> Option<Match> {
+    match mode {
+        MatchMode::Fuzzy => fuzzy_match(pattern, text),
+        MatchMode::Substring => substring_match(pattern, text),
+    }
+}
+
+/// Case-insensitive fzf-style match. Finds the first occurrence of the pattern, then
+/// shrinks it to the shortest window ending there before scoring.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<Match> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    if pattern.is_empty() {
+        return Some(Match::default());
+    }
+
+    // Forward scan: where does the first full occurrence end?
+    let mut p = 0;
+    let mut end = None;
+    for (i, &c) in text.iter().enumerate() {
+        if same_char(c, pattern[p]) {
+            p += 1;
+            if p == pattern.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    // Backward scan: latest start that still contains the pattern
+    let mut p = pattern.len();
+    let mut start = end;
+    for i in (0..=end).rev() {
+        if same_char(text[i], pattern[p - 1]) {
+            p -= 1;
+            if p == 0 {
+                start = i;
+                break;
+            }
+        }
+    }
+
+    Some(score_window(&pattern, &text, start, end))
+}
+
+fn score_window(pattern: &[char], text: &[char], start: usize, end: usize) -> Match {
+    let mut score = 0;
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut p = 0;
+    let mut in_gap = false;
+    let mut consecutive = 0;
+    let mut first_bonus = 0;
+    let mut previous = if start == 0 { CharClass::Separator } else { char_class(text[start - 1]) };
+
+    for (i, &c) in text.iter().enumerate().take(end + 1).skip(start) {
+        let class = char_class(c);
+        if p < pattern.len() && same_char(c, pattern[p]) {
+            let mut bonus = boundary_bonus(previous, class);
+            if consecutive == 0 {
+                first_bonus = bonus;
+            } else {
+                // A run keeps the bonus of the boundary it started on
+                bonus = bonus.max(first_bonus).max(BONUS_CONSECUTIVE);
+            }
+            score += SCORE_MATCH + if p == 0 { bonus * FIRST_CHAR_MULTIPLIER } else { bonus };
+            positions.push(i);
+            consecutive += 1;
+            in_gap = false;
+            p += 1;
+        } else {
+            score -= if in_gap { PENALTY_GAP_EXTENSION } else { PENALTY_GAP_START };
+            in_gap = true;
+            consecutive = 0;
+            first_bonus = 0;
+        }
+        previous = class;
+    }
+    Match { score, positions }
+}
+
+/// Case-insensitive substring match. All matches score the same, so the list order is kept.
+pub fn substring_match(pattern: &str, text: &str) -> Option<Match> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    if pattern.is_empty() {
+        return Some(Match::default());
+    }
+    let start = text
+        .windows(pattern.len())
+        .position(|window| window.iter().zip(&pattern).all(|(&a, &b)| same_char(a, b)))?;
+    Some(Match { score: 0, positions: (start..start + pattern.len()).collect() })
+}
+
+/// Every non-overlapping case-insensitive occurrence of `pattern`, as char ranges.
+pub fn find_all(pattern: &str, text: &str) -> Vec<Range<usize>> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let mut found = Vec::new();
+    if pattern.is_empty() {
+        return found;
+    }
+    let mut start = 0;
+    while start + pattern.len() <= text.len() {
+        if text[start..start + pattern.len()].iter().zip(&pattern).all(|(&a, &b)| same_char(a, b)) {
+            found.push(start..start + pattern.len());
+            start += pattern.len();
+        } else {
+            start += 1;
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(pattern: &str, text: &str) -> Vec<usize> {
+        fuzzy_match(pattern, text).map(|m| m.positions).unwrap_or_default()
+    }
+
+    fn score(pattern: &str, text: &str) -> i64 {
+        fuzzy_match(pattern, text).unwrap().score
+    }
+
+    #[test]
+    fn pattern_characters_match_in_order() {
+        assert_eq!(positions("l3i", "llama3:instruct"), [1, 5, 7]);
+        assert_eq!(positions("LLA", "llama3"), [0, 1, 2]);
+        assert_eq!(fuzzy_match("3l", "llama3"), None);
+        assert_eq!(fuzzy_match("", "llama3"), Some(Match::default()));
+    }
+
+    #[test]
+    fn the_window_is_shrunk_from_the_end() {
+        // The first `m` is dropped for the one closer to the `3`
+        assert_eq!(positions("m3", "mistral-llama3"), [11, 13]);
+        assert_eq!(positions("ab", "a-a-ab"), [4, 5]);
+    }
+
+    #[test]
+    fn boundaries_and_runs_score_higher() {
+        assert!(score("code", "codellama") > score("code", "decode"));
+        assert!(score("ins", "llama3:instruct") > score("ins", "mixins"));
+        assert!(score("ll", "llama") > score("lm", "llama"));
+        assert!(score("q4", "qwen:q4_0") > score("q4", "qwen4"));
+        assert!(score("qI", "qwenInstruct") > score("qi", "qwenistruct"));
+    }
+
+    #[test]
+    fn substring_mode_needs_adjacent_characters() {
+        assert_eq!(substring_match("AMA", "llama3").unwrap().positions, [2, 3, 4]);
+        assert_eq!(substring_match("l3", "llama3"), None);
+        assert_eq!(find(MatchMode::Substring, "ma", "llama").unwrap().score, 0);
+        assert_eq!(find_all("aa", "aaaaa"), [0..2, 2..4]);
+        assert_eq!(find_all("LL", "llama llama"), [0..2, 6..8]);
+        assert!(find_all("", "llama").is_empty());
+    }
+
+    #[test]
+    fn positions_count_chars_not_bytes() {
+        assert_eq!(positions("é3", "modèle-é3"), [7, 8]);
+    }
+}
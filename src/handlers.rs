@@ -1,30 +1,353 @@
 use crate::{
-    app::{AppMode, AppState},
+    app::{AppMode, AppState, ManifestStatus, UpdateStatus},
+    copy_dialog::{CopyDialog, CopyMode},
+    derive::{DeriveEditor, DeriveFocus},
     error::Result,
     events::AppEvent,
-    ollama_api::OllamaClient,
+    inventory::{InventoryState, RowStatus},
+    keymap::{Action, KeyBinding, KeyContext, KeyLookup},
+    ollama_api::{OllamaClient, KEEP_ALIVE_FOREVER, KEEP_ALIVE_UNLOAD},
+    param_form::{FormFocus, ParamForm},
+    quantize::{self, QuantizeDialog},
+    registry_api::{self, RegistryClient},
     tasks,
+    tensors::TensorView,
     tui,
+    ui,
 };
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 type EventSender = mpsc::Sender<AppEvent>;
 
-/// Handles Ctrl+C clear functionality for filter modes
-fn handle_filter_clear(app: &mut AppState) {
-    match app.current_mode {
-        AppMode::Filter => {
-            app.filter_input.clear();
-            app.filter_cursor_pos = 0;
-            app.apply_filter();
+/// Fetches the manifest of the highlighted tag unless it is already loaded or loading.
+fn request_highlighted_manifest(app: &mut AppState, registry: &RegistryClient, tx: &EventSender) {
+    let (Some(model_name), Some(tag)) = (
+        app.selected_registry_model.clone(),
+        app.highlighted_registry_tag().cloned(),
+    ) else {
+        return;
+    };
+    if app.registry_manifests.contains_key(&tag) {
+        return;
+    }
+    app.registry_manifests.insert(tag.clone(), ManifestStatus::Loading);
+
+    let registry_clone = registry.clone();
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        tasks::fetch_registry_manifest(registry_clone, tx_clone, model_name, tag).await;
+    });
+}
+
+/// Starts an update check for every local model pulled from the Ollama registry.
+fn start_update_check(app: &mut AppState, registry: &RegistryClient, tx: &EventSender) {
+    if app.update_status.values().any(|s| matches!(s, UpdateStatus::Checking)) {
+        app.status_message = Some("Update check already running...".to_string());
+        return;
+    }
+
+    app.update_status.clear();
+    let mut names = Vec::new();
+    for model in &app.models {
+        if registry_api::split_model_name(&model.name).is_some() {
+            app.update_status.insert(model.name.clone(), UpdateStatus::Checking);
+            names.push(model.name.clone());
+        } else {
+            app.update_status.insert(
+                model.name.clone(),
+                UpdateStatus::Failed("not from the Ollama registry".to_string()),
+            );
+        }
+    }
+    app.status_message = Some(format!("Checking {} models for updates...", names.len()));
+
+    let registry_clone = registry.clone();
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        tasks::check_for_updates(registry_clone, tx_clone, names).await;
+    });
+}
+
+/// Spawns pull tasks for queued downloads while the concurrency limit allows.
+/// Each job pulls onto the host it was queued for, even if the active host changed since.
+fn start_pending_downloads(app: &mut AppState, client: &OllamaClient, tx: &EventSender) {
+    for job in app.downloads.take_startable() {
+        let tx_clone = tx.clone();
+        let job_client = client.for_host(job.host);
+        let (job_id, model_tag) = (job.id, job.model);
+        let handle = tokio::spawn(async move {
+            tasks::pull_model(job_client, tx_clone, job_id, model_tag).await;
+        });
+        if let Some(job) = app.downloads.job_mut(job_id) {
+            job.abort_handle = Some(handle.abort_handle());
+        }
+    }
+}
+
+/// Opens the multi-host inventory and lists the models of every host concurrently.
+fn start_inventory_refresh(app: &mut AppState, client: &OllamaClient, tx: &EventSender) {
+    let hosts = app.hosts.clone();
+    let inventory = app
+        .inventory
+        .get_or_insert_with(|| InventoryState::new(hosts.clone()));
+    let request_id = inventory.begin_refresh();
+    for host in hosts {
+        let tx_clone = tx.clone();
+        let host_client = client.for_host(host.url);
+        tokio::spawn(async move {
+            tasks::fetch_inventory_host(host_client, tx_clone, request_id).await;
+        });
+    }
+}
+
+/// Status message prefix of a failed details fetch, cleared once the details arrive.
+const DETAILS_ERROR: &str = "Error fetching details";
+
+/// Fetches `/api/show` for listed models that aren't cached yet, for capability badges and filters.
+pub fn start_details_prefetch(app: &mut AppState, client: &OllamaClient, tx: &EventSender) {
+    let requests: Vec<(u64, String)> = app
+        .models_needing_show_details()
+        .into_iter()
+        .map(|(name, digest)| (app.show_cache.begin_request(&name, &digest), name))
+        .collect();
+    if requests.is_empty() {
+        return;
+    }
+
+    let client_clone = client.clone();
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        tasks::prefetch_model_details(client_clone, tx_clone, requests).await;
+    });
+}
+
+/// Once the selection has rested on a model, fetches its details unless cached, then those of its neighbours.
+pub fn start_due_details_fetch(app: &mut AppState, client: &OllamaClient, tx: &EventSender) {
+    if app.details_fetch_due.is_none_or(|due| Instant::now() < due) {
+        return;
+    }
+    app.details_fetch_due = None;
+
+    let mut neighbourhood = app.selected_model_neighbourhood().into_iter();
+    // Supersedes a queued prefetch of the same model, which may be far behind
+    if let Some((name, digest)) = neighbourhood.next()
+        && !app.show_cache.contains(&name, &digest)
+    {
+        let request_id = app.show_cache.begin_request(&name, &digest);
+        let client_clone = client.clone();
+        let tx_clone = tx.clone();
+        tokio::spawn(async move {
+            tasks::fetch_model_details(client_clone, tx_clone, request_id, name).await;
+        });
+    }
+
+    let mut requests = Vec::new();
+    for (name, digest) in neighbourhood {
+        if !app.show_cache.contains(&name, &digest) && !app.show_cache.is_pending(&name) {
+            requests.push((app.show_cache.begin_request(&name, &digest), name));
+        }
+    }
+    if !requests.is_empty() {
+        let client_clone = client.clone();
+        let tx_clone = tx.clone();
+        tokio::spawn(async move {
+            tasks::prefetch_model_details(client_clone, tx_clone, requests).await;
+        });
+    }
+}
+
+/// Deletes the model selected in the model list.
+fn delete_selected_model(app: &mut AppState, client: &OllamaClient, tx: &EventSender) {
+    if let Some(name) = app.get_selected_model_name() {
+        app.status_message = Some(format!("Deleting {}...", name));
+
+        let client_clone = client.clone();
+        let tx_clone = tx.clone();
+        let model_name_clone = name.clone();
+
+        tokio::spawn(async move {
+            tasks::delete_model(client_clone, tx_clone, model_name_clone).await;
+        });
+    }
+    app.current_mode = AppMode::Normal;
+}
+
+/// Queues the selected registry `model:tag` and goes back to the tag list so more tags can be queued.
+fn install_selected_tag(app: &mut AppState, client: &OllamaClient, tx: &EventSender) {
+    if let (Some(model), Some(tag)) = (app.selected_registry_model.clone(), app.selected_registry_tag.clone()) {
+        let model_tag = format!("{}:{}", model, tag);
+        app.current_mode = AppMode::InstallSelectTag;
+        app.selected_registry_tag = None;
+        app.install_error = None;
+        app.install_status = match app.downloads.enqueue(model_tag.clone(), client.host().to_string()) {
+            Some(_) => Some(format!("Queued {} (D: Downloads)", model_tag)),
+            None => Some(format!("{} is already queued", model_tag)),
+        };
+        start_pending_downloads(app, client, tx);
+    } else {
+        app.install_error = Some("Model or tag not selected.".to_string());
+        app.current_mode = AppMode::InstallSelectTag;
+    }
+}
+
+/// Deletes the model of the selected inventory row from that row's host.
+fn delete_inventory_selection(app: &mut AppState, client: &OllamaClient, tx: &EventSender) {
+    let target = app.inventory.as_ref().and_then(|inv| {
+        Some((inv.selected_row()?.model.clone(), inv.selected_host()?.clone()))
+    });
+    if let Some((model, host)) = target {
+        app.status_message = Some(format!("Deleting {} on {}...", model, host.name));
+
+        let host_client = client.for_host(host.url);
+        let tx_clone = tx.clone();
+        tokio::spawn(async move {
+            tasks::delete_model(host_client, tx_clone, model).await;
+        });
+    }
+    app.current_mode = AppMode::Inventory;
+}
+
+/// Maps the load, unload and pin actions to a `keep_alive` value.
+fn keep_alive_for_action(action: Action) -> Option<i64> {
+    match action {
+        Action::UnloadModel => Some(KEEP_ALIVE_UNLOAD),
+        Action::PinModel => Some(KEEP_ALIVE_FOREVER),
+        _ => None,
+    }
+}
+
+/// Describes a keep-alive change for status messages.
+fn keep_alive_action(keep_alive: Option<i64>) -> (&'static str, &'static str) {
+    match keep_alive {
+        Some(KEEP_ALIVE_UNLOAD) => ("Unloading", "Unloaded"),
+        Some(k) if k < 0 => ("Pinning", "Pinned"),
+        _ => ("Loading", "Loaded"),
+    }
+}
+
+/// Sends a keep-alive request for a model: `None` warms it with the server default,
+/// `KEEP_ALIVE_UNLOAD` unloads it and `KEEP_ALIVE_FOREVER` pins it in memory.
+fn spawn_keep_alive(
+    app: &mut AppState,
+    client: &OllamaClient,
+    tx: &EventSender,
+    model_name: String,
+    keep_alive: Option<i64>,
+) {
+    app.status_message = Some(format!("{} {}...", keep_alive_action(keep_alive).0, model_name));
+    let client_clone = client.clone();
+    let tx_clone = tx.clone();
+    tokio::spawn(async move {
+        tasks::set_keep_alive(client_clone, tx_clone, model_name, keep_alive).await;
+    });
+}
+
+/// Index of the first or last of `len` entries for `MoveTop` / `MoveBottom`.
+fn edge_index(action: Action, len: usize) -> Option<usize> {
+    match action {
+        _ if len == 0 => None,
+        Action::MoveTop => Some(0),
+        _ => Some(len - 1),
+    }
+}
+
+/// Number of lines in the help screen, used to bound its scroll offset.
+fn help_line_count(app: &AppState) -> u16 {
+    let lines: usize = app
+        .keymap
+        .help_sections()
+        .iter()
+        .map(|(_, entries)| entries.len() + 2)
+        .sum();
+    u16::try_from(lines).unwrap_or(u16::MAX)
+}
+
+/// Handles key actions in the chat pane.
+fn handle_chat_action(action: Action, app: &mut AppState, client: &OllamaClient, tx: &EventSender) {
+    let Some(chat) = app.chat.as_mut() else {
+        app.current_mode = AppMode::Normal;
+        return;
+    };
+
+    match action {
+        Action::Back => {
+            // Chats opened from the inventory go back to it
+            app.current_mode = if chat.host.is_some() && app.inventory.is_some() {
+                AppMode::Inventory
+            } else {
+                AppMode::Normal
+            };
+            app.status_message = None;
         }
-        AppMode::InstallSelectModelFilter => {
-            app.registry_filter_input.clear();
-            app.registry_filter_cursor_pos = 0;
-            app.apply_registry_filter();
+        Action::InsertNewline => chat.input.insert_char('\n'),
+        Action::SendMessage => {
+            app.status_message = None;
+            if let Some(messages) = chat.begin_request(app.chat_request_id + 1) {
+                app.chat_request_id += 1;
+                let request_id = app.chat_request_id;
+                let client_clone = match &chat.host {
+                    Some(host) => client.for_host(host.url.clone()),
+                    None => client.clone(),
+                };
+                let tx_clone = tx.clone();
+                let model = chat.model.clone();
+                let handle = tokio::spawn(async move {
+                    tasks::stream_chat(client_clone, tx_clone, request_id, model, messages).await;
+                });
+                chat.abort_handle = Some(handle.abort_handle());
+            }
+        }
+        Action::StopOrClear => {
+            // Stop the response if one is streaming, otherwise clear the input
+            if chat.is_streaming() {
+                chat.cancel();
+            } else {
+                chat.input.clear();
+            }
+        }
+        Action::ClearTranscript => chat.clear(),
+        Action::MoveUp => chat.scroll_up(1),
+        Action::MoveDown => chat.scroll_down(1),
+        Action::PageUp => chat.scroll_up(10),
+        Action::PageDown => chat.scroll_down(10),
+        _ => {
+            chat.input.apply(action, &mut app.kill_ring);
+        }
+    }
+}
+
+/// Resolves a key press to an action of the current mode's keymap.
+/// Returns `None` for unbound keys and while a key sequence is still incomplete.
+fn resolve_action(key: &KeyEvent, app: &mut AppState, context: KeyContext) -> Option<Action> {
+    let binding = KeyBinding::from_event(key);
+    if context.is_text_input() {
+        app.pending_keys.clear();
+        return match app.keymap.lookup(context, &[binding], false) {
+            KeyLookup::Action(action) => Some(action),
+            _ => None,
+        };
+    }
+
+    app.pending_keys.push(binding);
+    let mut lookup = app.keymap.lookup(context, &app.pending_keys, true);
+    if lookup == KeyLookup::Unbound && app.pending_keys.len() > 1 {
+        // An unfinished sequence followed by an unrelated key: start over from that key
+        app.pending_keys = vec![binding];
+        lookup = app.keymap.lookup(context, &app.pending_keys, true);
+    }
+    match lookup {
+        KeyLookup::Pending => None,
+        KeyLookup::Action(action) => {
+            app.pending_keys.clear();
+            Some(action)
+        }
+        KeyLookup::Unbound => {
+            app.pending_keys.clear();
+            None
         }
-        _ => {} // Should not happen, but handle gracefully
     }
 }
 
@@ -33,325 +356,1055 @@ fn handle_filter_clear(app: &mut AppState) {
 pub async fn handle_key_event(
     key: KeyEvent,
     app: &mut AppState,
-    client: &OllamaClient,
+    client: &mut OllamaClient,
+    registry: &RegistryClient,
     tx: &EventSender,
 ) -> Result<bool> {
-    if key.kind == KeyEventKind::Press || key.kind == KeyEventKind::Repeat {
-        let mut handled_globally = false;
-        if app.is_global_key_handling_enabled() {
-            match key.code {
-                KeyCode::Char('h') | KeyCode::Char('?') => {
-                    app.previous_mode = Some(app.current_mode.clone());
-                    app.current_mode = AppMode::Help;
-                    app.status_message = None;
-                    handled_globally = true;
+    if key.kind != KeyEventKind::Press && key.kind != KeyEventKind::Repeat {
+        return Ok(false);
+    }
+    let Some(context) = KeyContext::for_mode(&app.current_mode) else {
+        return Ok(false);
+    };
+
+    let Some(action) = resolve_action(&key, app, context) else {
+        // Unbound keys type into the focused text field
+        if let KeyCode::Char(c) = key.code
+            && !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            match app.current_mode {
+                AppMode::Filter => {
+                    app.filter_input.insert_char(c);
+                    app.apply_filter();
+                    start_details_prefetch(app, client, tx);
+                }
+                AppMode::InstallSelectModelFilter => {
+                    app.registry_filter_input.insert_char(c);
+                    app.apply_registry_filter();
+                }
+                AppMode::DetailsSearch => {
+                    app.details.search.insert_char(c);
+                    app.details.restart_search();
+                }
+                AppMode::CopyInput => {
+                    if let Some(dialog) = app.copy_dialog.as_mut() {
+                        dialog.input.insert_char(c);
+                    }
+                }
+                AppMode::Derive => {
+                    if let Some(editor) = app.derive.as_mut() {
+                        editor.focused_input().insert_char(c);
+                    }
+                }
+                AppMode::ParamForm => {
+                    if let Some(form) = app.param_form.as_mut() {
+                        form.focused_input().insert_char(c);
+                        form.error = None;
+                    }
+                }
+                AppMode::Quantize => {
+                    if let Some(dialog) = app.quantize.as_mut() {
+                        dialog.name.insert_char(c);
+                        dialog.error = None;
+                    }
+                }
+                AppMode::Chat => {
+                    if let Some(chat) = app.chat.as_mut() {
+                        chat.input.insert_char(c);
+                    }
                 }
                 _ => {}
             }
         }
+        return Ok(false);
+    };
 
-        if !handled_globally {
-            let current_mode = app.current_mode.clone();
-            match current_mode {
-                AppMode::Normal => match key.code {
-                    KeyCode::Char('q') => return Ok(true),
-                    KeyCode::Char('j') | KeyCode::Down => app.next_model(),
-                    KeyCode::Char('k') | KeyCode::Up => app.previous_model(),
-                    KeyCode::Char('/') => {
-                        // Enter filter mode
-                        app.current_mode = AppMode::Filter;
-                        app.filter_input.clear();
-                        app.filter_cursor_pos = 0;
-                        app.status_message = None;
+    if action == Action::ToggleHelp {
+        if app.current_mode == AppMode::Help {
+            app.current_mode = app.previous_mode.take().unwrap_or(AppMode::Normal);
+        } else {
+            app.previous_mode = Some(app.current_mode.clone());
+            app.current_mode = AppMode::Help;
+            app.help_scroll = 0;
+        }
+        app.status_message = None;
+        return Ok(false);
+    }
+
+    handle_action(action, app, client, registry, tx)
+}
+
+/// Performs `action` in the current mode, for key presses and mouse clicks alike.
+/// Returns `Ok(true)` if the application should quit.
+fn handle_action(
+    action: Action,
+    app: &mut AppState,
+    client: &mut OllamaClient,
+    registry: &RegistryClient,
+    tx: &EventSender,
+) -> Result<bool> {
+    let current_mode = app.current_mode.clone();
+    match current_mode {
+        AppMode::Normal => match action {
+            Action::Quit => return Ok(true),
+            Action::MoveDown => app.next_model(),
+            Action::MoveUp => app.previous_model(),
+            Action::MoveTop | Action::MoveBottom => {
+                if let Some(i) = edge_index(action, app.get_current_models().len()) {
+                    app.select_and_prepare_fetch(Some(i));
+                }
+            }
+            Action::Filter => {
+                // Enter filter mode
+                app.current_mode = AppMode::Filter;
+                app.filter_input.clear();
+                app.status_message = None;
+            }
+            Action::ClearFilter if app.is_filtered => app.clear_filter(),
+            Action::Delete if app.list_state.selected().is_some() => {
+                if app.confirm.delete {
+                    app.current_mode = AppMode::ConfirmDelete;
+                    app.status_message = None;
+                } else {
+                    delete_selected_model(app, client, tx);
+                }
+            }
+            Action::CopyModel | Action::RenameModel => {
+                if let Some(name) = app.get_selected_model_name() {
+                    let mode = if action == Action::CopyModel { CopyMode::Copy } else { CopyMode::Rename };
+                    app.copy_dialog = Some(CopyDialog::new(mode, name));
+                    app.current_mode = AppMode::CopyInput;
+                    app.status_message = None;
+                }
+            }
+            Action::DeriveModel => {
+                if let Some(name) = app.get_selected_model_name() {
+                    // The Modelfile comes from the selected model's `/api/show` response
+                    match app.selected_model_details() {
+                        Some(details) => {
+                            app.derive = Some(DeriveEditor::new(name, details.modelfile.as_deref()));
+                            app.current_mode = AppMode::Derive;
+                            app.status_message = None;
+                        }
+                        None => app.status_message = Some(format!("Details of {} are still loading", name)),
                     }
-                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        // Clear filter with Ctrl+C
-                        if app.is_filtered {
-                            app.clear_filter();
+                }
+            }
+            Action::EditParameters => {
+                if let Some(name) = app.get_selected_model_name() {
+                    // Current values come from the selected model's `/api/show` response
+                    match app.selected_model_details() {
+                        Some(details) => {
+                            app.param_form = Some(ParamForm::new(name, details.parameters.as_deref()));
+                            app.current_mode = AppMode::ParamForm;
+                            app.status_message = None;
                         }
+                        None => app.status_message = Some(format!("Details of {} are still loading", name)),
                     }
-                    KeyCode::Char('d') => {
-                        if app.list_state.selected().is_some() {
-                            app.current_mode = AppMode::ConfirmDelete;
+                }
+            }
+            Action::Quantize => {
+                let selected = app.get_selected_model_name().and_then(|name| app.models.iter().find(|m| m.name == name));
+                if let Some(model) = selected {
+                    match quantize::source_type(model) {
+                        Ok(source_type) => {
+                            app.quantize = Some(QuantizeDialog::new(model, source_type));
+                            app.current_mode = AppMode::Quantize;
                             app.status_message = None;
                         }
+                        Err(message) => app.status_message = Some(message),
                     }
-                    KeyCode::Char('i') => {
-                        app.current_mode = AppMode::InstallSelectModel;
-                        app.is_fetching_registry = true;
-                        app.install_error = None;
-                        app.registry_models.clear();
-                        app.registry_model_list_state.select(None);
-
+                }
+            }
+            Action::ShowDownloads => {
+                app.current_mode = AppMode::Downloads;
+                app.status_message = None;
+            }
+            Action::ShowRunning => {
+                app.current_mode = AppMode::RunningModels;
+                app.status_message = None;
+            }
+            Action::CheckUpdates => start_update_check(app, registry, tx),
+            Action::UpdateAll => {
+                // Re-pull every outdated model through the download queue
+                let outdated = app.outdated_model_names();
+                if outdated.is_empty() {
+                    let keys = app.keymap.keys_for(KeyContext::Models, Action::CheckUpdates);
+                    app.status_message = Some(format!(
+                        "No outdated models (press '{}' to check)",
+                        keys.first().map_or("u", String::as_str)
+                    ));
+                } else {
+                    let queued = outdated
+                        .into_iter()
+                        .filter_map(|name| app.downloads.enqueue(name, client.host().to_string()))
+                        .count();
+                    app.status_message = Some(format!("Queued {} updates (D: Downloads)", queued));
+                    start_pending_downloads(app, client, tx);
+                }
+            }
+            Action::LoadModel | Action::UnloadModel | Action::PinModel => {
+                if let Some(name) = app.get_selected_model_name() {
+                    spawn_keep_alive(app, client, tx, name, keep_alive_for_action(action));
+                }
+            }
+            Action::SortNext | Action::SortReverse => {
+                if action == Action::SortNext {
+                    app.cycle_sort_column();
+                } else {
+                    app.model_sort.descending = !app.model_sort.descending;
+                    app.sort_models();
+                }
+                app.status_message = Some(format!(
+                    "Sorted by {} {}",
+                    app.model_sort.column.title(),
+                    app.model_sort.indicator()
+                ));
+            }
+            Action::ShowInventory => {
+                app.current_mode = AppMode::Inventory;
+                app.status_message = None;
+                start_inventory_refresh(app, client, tx);
+            }
+            Action::ShowTensors => {
+                if let Some(name) = app.get_selected_model_name() {
+                    app.current_mode = AppMode::Tensors;
+                    // Reopening the same model keeps the loaded tensors
+                    let reuse = app.tensors.as_ref().is_some_and(|view| view.model == name && view.error.is_none());
+                    if !reuse {
+                        app.tensors = Some(TensorView::new(name.clone()));
+                        let client_clone = client.clone();
                         let tx_clone = tx.clone();
                         tokio::spawn(async move {
-                            tasks::fetch_registry_models(tx_clone).await;
+                            tasks::fetch_tensors(client_clone, tx_clone, name).await;
                         });
                     }
-                    KeyCode::Enter => {
-                        if let Some(name) = app.get_selected_model_name() {
-                            app.current_mode = AppMode::RunningOllama;
-                            app.status_message = None;
+                }
+            }
+            Action::SwitchHost => {
+                app.host_list_state.select(Some(app.active_host));
+                app.current_mode = AppMode::HostSelect;
+                app.status_message = None;
+            }
+            Action::Install => {
+                app.current_mode = AppMode::InstallSelectModel;
+                app.is_fetching_registry = true;
+                app.install_error = None;
+                app.registry_models.clear();
+                app.registry_model_list_state.select(None);
 
-                            let tx_clone = tx.clone();
-                            let model_name_clone = name.clone();
+                let registry_clone = registry.clone();
+                let tx_clone = tx.clone();
+                tokio::spawn(async move {
+                    tasks::fetch_registry_models(registry_clone, tx_clone).await;
+                });
+            }
+            Action::Chat => {
+                app.open_chat();
+            }
+            Action::SwitchPane if app.list_state.selected().is_some() => {
+                app.current_mode = AppMode::Details;
+                app.status_message = None;
+            }
+            // Checked before the terminal is handed off, which would leave nothing to run
+            Action::Run if !tasks::ollama_cli_available() && app.open_chat() => {
+                app.status_message = Some("ollama CLI not found; chatting in the built-in pane instead".to_string());
+            }
+            Action::Run => {
+                // Interactive session through the local `ollama` CLI
+                if let Some(name) = app.get_selected_model_name() {
+                    app.current_mode = AppMode::RunningOllama;
+                    app.status_message = None;
 
-                            tokio::spawn(async move {
-                                tasks::run_ollama(tx_clone, model_name_clone).await;
-                            });
-                        }
-                    }
-                    _ => {}
-                },
-                AppMode::Filter => match key.code {
-                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        // Clear filter input with Ctrl+C
-                        handle_filter_clear(app);
-                    }
-                    KeyCode::Char(c) => {
-                        // Add character to filter input
-                        app.filter_input_char(c);
-                    }
-                    KeyCode::Backspace => {
-                        // Remove character from filter input
-                        app.filter_input_backspace();
-                    }
-                    KeyCode::Left => {
-                        app.filter_cursor_left();
-                    }
-                    KeyCode::Right => {
-                        app.filter_cursor_right();
-                    }
-                    KeyCode::Enter => {
-                        // Confirm filter and return to normal mode
-                        app.current_mode = AppMode::Normal;
-                        app.status_message = if app.is_filtered {
-                            Some(format!("Filter: '{}' ({} models)", app.filter_input, app.get_current_models().len()))
-                        } else {
-                            None
-                        };
-                    }
-                    KeyCode::Esc => {
-                        // Cancel filter - clear it and return to normal mode
-                        app.clear_filter();
-                        app.current_mode = AppMode::Normal;
-                        app.status_message = Some("Filter cleared".to_string());
-                    }
-                    _ => {}
-                },
-                AppMode::ConfirmDelete => match key.code {
-                    KeyCode::Char('y') | KeyCode::Char('Y') => {
-                        if let Some(name) = app.get_selected_model_name() {
-                            app.status_message = Some(format!("Deleting {}...", name));
-
-                            let client_clone = client.clone();
-                            let tx_clone = tx.clone();
-                            let model_name_clone = name.clone();
-
-                            tokio::spawn(async move {
-                                tasks::delete_model(client_clone, tx_clone, model_name_clone).await;
-                            });
-                        }
-                        app.current_mode = AppMode::Normal;
-                    }
-                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
-                        app.current_mode = AppMode::Normal;
-                        app.status_message = None;
-                    }
-                    _ => {}
-                },
-                AppMode::InstallSelectModel => match key.code {
-                    KeyCode::Char('/') => {
-                        // Enter registry filter mode
-                        app.current_mode = AppMode::InstallSelectModelFilter;
-                        app.registry_filter_input.clear();
-                        app.registry_filter_cursor_pos = 0;
-                        app.install_error = None;
-                    }
-                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        // Clear registry filter with Ctrl+C
-                        if app.is_registry_filtered {
-                            app.clear_registry_filter();
-                        }
-                    }
-                    KeyCode::Char('j') | KeyCode::Down => {
-                        let len = app.get_current_registry_models().len();
-                        if len > 0 {
-                            let i = match app.registry_model_list_state.selected() {
-                                Some(i) => (i + 1) % len,
-                                None => 0,
-                            };
-                            app.registry_model_list_state.select(Some(i));
-                        }
-                    }
-                    KeyCode::Char('k') | KeyCode::Up => {
-                        let len = app.get_current_registry_models().len();
-                        if len > 0 {
-                            let i = match app.registry_model_list_state.selected() {
-                                Some(i) => (i + len - 1) % len,
-                                None => len - 1,
-                            };
-                            app.registry_model_list_state.select(Some(i));
-                        }
+                    let tx_clone = tx.clone();
+                    let host = client.host().to_string();
+                    let model_name_clone = name.clone();
+                    let mouse = app.mouse_enabled;
+
+                    tokio::spawn(async move {
+                        tasks::run_ollama(tx_clone, host, model_name_clone, mouse).await;
+                    });
+                }
+            }
+            _ => {}
+        },
+        AppMode::Filter => match action {
+            Action::Submit => {
+                // Confirm filter and return to normal mode
+                app.filter_input.push_history();
+                app.current_mode = AppMode::Normal;
+                app.status_message = if app.is_filtered {
+                    Some(format!("Filter: '{}' ({} models)", app.filter_input.text(), app.get_current_models().len()))
+                } else {
+                    None
+                };
+            }
+            Action::Cancel => {
+                // Cancel filter - clear it and return to normal mode
+                app.clear_filter();
+                app.current_mode = AppMode::Normal;
+                app.status_message = Some("Filter cleared".to_string());
+            }
+            _ => {
+                if app.filter_input.apply(action, &mut app.kill_ring) == Some(true) {
+                    app.apply_filter();
+                    start_details_prefetch(app, client, tx);
+                }
+            }
+        },
+        AppMode::Derive => {
+            let Some(editor) = app.derive.as_mut() else {
+                app.current_mode = AppMode::Normal;
+                return Ok(false);
+            };
+            let in_modelfile = editor.focus == DeriveFocus::Modelfile;
+            match action {
+                Action::CreateModel if !editor.is_creating() => {
+                    // Problems in the name or the Modelfile are shown in the editor
+                    if let Some(request) = editor.build_request(&app.models) {
+                        app.create_request_id += 1;
+                        let request_id = app.create_request_id;
+                        editor.begin_create(request_id);
+
+                        let client_clone = client.clone();
+                        let tx_clone = tx.clone();
+                        tokio::spawn(async move {
+                            tasks::create_model(client_clone, tx_clone, request_id, request).await;
+                        });
                     }
-                    KeyCode::Enter => {
-                        if let Some(selected_index) = app.registry_model_list_state.selected() {
-                            if let Some(model_name) = app.get_current_registry_models().get(selected_index).cloned() {
-                                app.selected_registry_model = Some(model_name.clone());
-                                app.current_mode = AppMode::InstallSelectTag;
-                                app.is_fetching_registry = true;
-                                app.install_error = None;
-                                app.registry_tags.clear();
-                                app.registry_tag_list_state.select(None);
-
-                                let tx_clone = tx.clone();
-                                let model_name_clone = model_name.clone();
-                                tokio::spawn(async move {
-                                    tasks::fetch_registry_tags(tx_clone, model_name_clone).await;
-                                });
-                            }
-                        }
+                }
+                // A create in flight carries on; its result is reported in the status bar
+                Action::Cancel => {
+                    app.derive = None;
+                    app.current_mode = AppMode::Normal;
+                }
+                Action::SwitchPane => editor.toggle_focus(),
+                Action::InsertNewline if in_modelfile => editor.modelfile.insert_char('\n'),
+                Action::InsertNewline | Action::MoveDown if !in_modelfile => editor.toggle_focus(),
+                Action::MoveDown | Action::MoveUp if in_modelfile => {
+                    editor.modelfile.move_lines(if action == Action::MoveDown { 1 } else { -1 });
+                }
+                Action::PageDown | Action::PageUp if in_modelfile => {
+                    let page = editor.page_height.saturating_sub(1).max(1) as isize;
+                    editor.modelfile.move_lines(if action == Action::PageDown { page } else { -page });
+                }
+                Action::ExternalEditor => {
+                    app.current_mode = AppMode::EditingExternally;
+                    let text = editor.modelfile.text().to_string();
+                    let tx_clone = tx.clone();
+                    let mouse = app.mouse_enabled;
+                    tokio::spawn(async move {
+                        tasks::edit_externally(tx_clone, text, mouse).await;
+                    });
+                }
+                _ => {
+                    editor.focused_input().apply(action, &mut app.kill_ring);
+                }
+            }
+        }
+        AppMode::ParamForm => {
+            let Some(form) = app.param_form.as_mut() else {
+                app.current_mode = AppMode::Normal;
+                return Ok(false);
+            };
+            match action {
+                Action::CreateModel if !form.is_creating() => {
+                    // Problems in the name or a field are shown in the form
+                    if let Some(request) = form.build_request(&app.models) {
+                        app.create_request_id += 1;
+                        let request_id = app.create_request_id;
+                        form.begin_create(request_id);
+
+                        let client_clone = client.clone();
+                        let tx_clone = tx.clone();
+                        tokio::spawn(async move {
+                            tasks::create_model(client_clone, tx_clone, request_id, request).await;
+                        });
                     }
-                    KeyCode::Char('q') | KeyCode::Esc => {
-                        app.current_mode = AppMode::Normal;
-                        app.install_error = None;
-                        app.is_fetching_registry = false;
-                        app.clear_registry_filter();
+                }
+                // A create in flight carries on; its result is reported in the status bar
+                Action::Cancel => {
+                    app.param_form = None;
+                    app.current_mode = AppMode::Normal;
+                }
+                Action::SwitchPane => form.toggle_focus(),
+                Action::MoveDown | Action::MoveUp => form.step(if action == Action::MoveDown { 1 } else { -1 }),
+                Action::PageDown | Action::PageUp => {
+                    let page = form.page_height.saturating_sub(1).max(1) as isize;
+                    form.step(if action == Action::PageDown { page } else { -page });
+                }
+                Action::ResetField if form.focus == FormFocus::Fields => {
+                    form.fields[form.selected].reset();
+                    form.error = None;
+                }
+                _ => {
+                    if form.focused_input().apply(action, &mut app.kill_ring) == Some(true) {
+                        form.error = None;
                     }
-                    _ => {}
-                },
-                AppMode::InstallSelectTag => match key.code {
-                     KeyCode::Char('j') | KeyCode::Down => {
-                        let len = app.registry_tags.len();
-                        if len > 0 {
-                            let i = match app.registry_tag_list_state.selected() {
-                                Some(i) => (i + 1) % len,
-                                None => 0,
-                            };
-                            app.registry_tag_list_state.select(Some(i));
-                        }
+                }
+            }
+        }
+        AppMode::Quantize => {
+            let Some(dialog) = app.quantize.as_mut() else {
+                app.current_mode = AppMode::Normal;
+                return Ok(false);
+            };
+            match action {
+                Action::Submit if !dialog.is_creating() => {
+                    if let Some(request) = dialog.build_request(&app.models) {
+                        app.create_request_id += 1;
+                        let request_id = app.create_request_id;
+                        dialog.begin_create(request_id);
+
+                        let client_clone = client.clone();
+                        let tx_clone = tx.clone();
+                        tokio::spawn(async move {
+                            tasks::create_model(client_clone, tx_clone, request_id, request).await;
+                        });
                     }
-                    KeyCode::Char('k') | KeyCode::Up => {
-                        let len = app.registry_tags.len();
-                        if len > 0 {
-                            let i = match app.registry_tag_list_state.selected() {
-                                Some(i) => (i + len - 1) % len,
-                                None => len - 1,
-                            };
-                            app.registry_tag_list_state.select(Some(i));
-                        }
+                }
+                // A quantization in flight carries on; its result is reported in the status bar
+                Action::Cancel => {
+                    app.quantize = None;
+                    app.current_mode = AppMode::Normal;
+                }
+                Action::MoveDown | Action::MoveUp => dialog.step(if action == Action::MoveDown { 1 } else { -1 }),
+                _ => {
+                    if dialog.name.apply(action, &mut app.kill_ring) == Some(true) {
+                        dialog.error = None;
                     }
-                    KeyCode::Enter => {
-                        if let Some(selected_index) = app.registry_tag_list_state.selected() {
-                             if let Some(tag_name) = app.registry_tags.get(selected_index).cloned() {
-                                app.selected_registry_tag = Some(tag_name);
-                                app.current_mode = AppMode::InstallConfirm;
-                                app.install_error = None;
-                             }
-                        }
+                }
+            }
+        }
+        AppMode::ConfirmDelete => match action {
+            Action::Confirm => delete_selected_model(app, client, tx),
+            Action::Cancel => {
+                app.current_mode = AppMode::Normal;
+                app.status_message = None;
+            }
+            _ => {}
+        },
+        AppMode::CopyInput => match action {
+            // Invalid names stay in the dialog, which shows why
+            Action::Submit => {
+                if let Some(dialog) = &app.copy_dialog
+                    && let Ok(target) = dialog.target(&app.models)
+                {
+                    let (mode, source) = (dialog.mode, dialog.source.clone());
+                    app.status_message = Some(format!("{} {} to {}...", mode.verb(), source, target));
+                    app.copy_dialog = None;
+                    app.current_mode = AppMode::Normal;
+
+                    let client_clone = client.clone();
+                    let tx_clone = tx.clone();
+                    tokio::spawn(async move {
+                        tasks::copy_model(client_clone, tx_clone, mode, source, target).await;
+                    });
+                }
+            }
+            Action::Cancel => {
+                app.copy_dialog = None;
+                app.current_mode = AppMode::Normal;
+            }
+            _ => {
+                if let Some(dialog) = app.copy_dialog.as_mut() {
+                    dialog.input.apply(action, &mut app.kill_ring);
+                }
+            }
+        },
+        AppMode::InstallSelectModel => match action {
+            Action::Filter => {
+                // Enter registry filter mode
+                app.current_mode = AppMode::InstallSelectModelFilter;
+                app.registry_filter_input.clear();
+                app.install_error = None;
+            }
+            Action::ClearFilter if app.is_registry_filtered => app.clear_registry_filter(),
+            Action::MoveDown => {
+                let len = app.get_current_registry_models().len();
+                if len > 0 {
+                    let i = match app.registry_model_list_state.selected() {
+                        Some(i) => (i + 1) % len,
+                        None => 0,
+                    };
+                    app.registry_model_list_state.select(Some(i));
+                }
+            }
+            Action::MoveUp => {
+                let len = app.get_current_registry_models().len();
+                if len > 0 {
+                    let i = match app.registry_model_list_state.selected() {
+                        Some(i) => (i + len - 1) % len,
+                        None => len - 1,
+                    };
+                    app.registry_model_list_state.select(Some(i));
+                }
+            }
+            Action::MoveTop | Action::MoveBottom => {
+                let i = edge_index(action, app.get_current_registry_models().len());
+                app.registry_model_list_state.select(i);
+            }
+            Action::Select => {
+                if let Some(selected_index) = app.registry_model_list_state.selected()
+                    && let Some(model_name) = app.get_current_registry_models().get(selected_index).cloned()
+                {
+                    app.selected_registry_model = Some(model_name.clone());
+                    app.current_mode = AppMode::InstallSelectTag;
+                    app.is_fetching_registry = true;
+                    app.install_error = None;
+                    app.registry_tags.clear();
+                    app.registry_manifests.clear();
+                    app.registry_tag_list_state.select(None);
+
+                    let registry_clone = registry.clone();
+                    let tx_clone = tx.clone();
+                    let model_name_clone = model_name.clone();
+                    tokio::spawn(async move {
+                        tasks::fetch_registry_tags(registry_clone, tx_clone, model_name_clone).await;
+                    });
+                }
+            }
+            Action::Back => {
+                app.current_mode = AppMode::Normal;
+                app.install_error = None;
+                app.install_status = None;
+                app.is_fetching_registry = false;
+                app.clear_registry_filter();
+            }
+            _ => {}
+        },
+        AppMode::InstallSelectTag => match action {
+            Action::MoveDown => {
+                let len = app.registry_tags.len();
+                if len > 0 {
+                    let i = match app.registry_tag_list_state.selected() {
+                        Some(i) => (i + 1) % len,
+                        None => 0,
+                    };
+                    app.registry_tag_list_state.select(Some(i));
+                    request_highlighted_manifest(app, registry, tx);
+                }
+            }
+            Action::MoveUp => {
+                let len = app.registry_tags.len();
+                if len > 0 {
+                    let i = match app.registry_tag_list_state.selected() {
+                        Some(i) => (i + len - 1) % len,
+                        None => len - 1,
+                    };
+                    app.registry_tag_list_state.select(Some(i));
+                    request_highlighted_manifest(app, registry, tx);
+                }
+            }
+            Action::MoveTop | Action::MoveBottom => {
+                if let Some(i) = edge_index(action, app.registry_tags.len()) {
+                    app.registry_tag_list_state.select(Some(i));
+                    request_highlighted_manifest(app, registry, tx);
+                }
+            }
+            Action::Select => {
+                if let Some(selected_index) = app.registry_tag_list_state.selected()
+                    && let Some(tag_name) = app.registry_tags.get(selected_index).cloned()
+                {
+                    app.selected_registry_tag = Some(tag_name);
+                    app.current_mode = AppMode::InstallConfirm;
+                    app.install_error = None;
+                    if !app.confirm.install {
+                        install_selected_tag(app, client, tx);
                     }
-                    KeyCode::Char('q') | KeyCode::Esc => {
-                        app.current_mode = AppMode::InstallSelectModel;
-                        app.selected_registry_model = None;
-                        app.registry_tags.clear();
-                        app.install_error = None;
-                        app.is_fetching_registry = false;
+                }
+            }
+            Action::Back => {
+                app.current_mode = AppMode::InstallSelectModel;
+                app.selected_registry_model = None;
+                app.registry_tags.clear();
+                app.registry_manifests.clear();
+                app.install_error = None;
+                app.is_fetching_registry = false;
+            }
+            _ => {}
+        },
+        AppMode::InstallConfirm => match action {
+            Action::Confirm => install_selected_tag(app, client, tx),
+            Action::Cancel => {
+                app.current_mode = AppMode::InstallSelectTag;
+                app.selected_registry_tag = None;
+                app.install_error = None;
+            }
+            _ => {}
+        },
+        AppMode::HostSelect => match action {
+            Action::MoveDown if !app.hosts.is_empty() => {
+                let i = app.host_list_state.selected().map_or(0, |i| (i + 1) % app.hosts.len());
+                app.host_list_state.select(Some(i));
+            }
+            Action::MoveUp if !app.hosts.is_empty() => {
+                let len = app.hosts.len();
+                let i = app.host_list_state.selected().map_or(len - 1, |i| (i + len - 1) % len);
+                app.host_list_state.select(Some(i));
+            }
+            Action::MoveTop | Action::MoveBottom => {
+                app.host_list_state.select(edge_index(action, app.hosts.len()));
+            }
+            Action::Select => {
+                app.current_mode = AppMode::Normal;
+                if let Some(index) = app.host_list_state.selected()
+                    && index != app.active_host
+                    && let Some(host) = app.hosts.get(index)
+                {
+                    *client = client.for_host(host.url.clone());
+                    app.switch_host(index);
+
+                    let client_clone = client.clone();
+                    let tx_clone = tx.clone();
+                    tokio::spawn(async move {
+                        tasks::fetch_local_models(client_clone, tx_clone).await;
+                    });
+                }
+            }
+            Action::Back => {
+                app.current_mode = AppMode::Normal;
+            }
+            _ => {}
+        },
+        AppMode::Inventory => match action {
+            Action::MoveDown => {
+                if let Some(inventory) = app.inventory.as_mut() {
+                    inventory.next();
+                }
+            }
+            Action::MoveUp => {
+                if let Some(inventory) = app.inventory.as_mut() {
+                    inventory.previous();
+                }
+            }
+            Action::MoveTop | Action::MoveBottom => {
+                if let Some(inventory) = app.inventory.as_mut() {
+                    inventory.table_state.select(edge_index(action, inventory.rows.len()));
+                }
+            }
+            Action::Refresh => start_inventory_refresh(app, client, tx),
+            Action::Pull => {
+                // Pull onto the selected host, e.g. to fill in a missing model
+                let target = app.inventory.as_ref().and_then(|inv| {
+                    Some((inv.selected_row()?.model.clone(), inv.selected_host()?.clone()))
+                });
+                if let Some((model, host)) = target {
+                    app.status_message = match app.downloads.enqueue(model.clone(), host.url) {
+                        Some(_) => Some(format!("Queued {} on {} (D: Downloads)", model, host.name)),
+                        None => Some(format!("{} is already queued on {}", model, host.name)),
+                    };
+                    start_pending_downloads(app, client, tx);
+                }
+            }
+            Action::Delete => {
+                let installed = app
+                    .inventory
+                    .as_ref()
+                    .and_then(|inv| inv.selected_row())
+                    .is_some_and(|row| row.status != RowStatus::Missing);
+                if installed && app.confirm.delete {
+                    app.current_mode = AppMode::InventoryConfirmDelete;
+                    app.status_message = None;
+                } else if installed {
+                    delete_inventory_selection(app, client, tx);
+                }
+            }
+            Action::Chat => {
+                let target = app.inventory.as_ref().and_then(|inv| {
+                    let row = inv.selected_row().filter(|row| row.status != RowStatus::Missing)?;
+                    Some((row.model.clone(), inv.selected_host()?.clone()))
+                });
+                if let Some((model, host)) = target {
+                    app.open_chat_on(model, Some(host));
+                }
+            }
+            Action::Back => {
+                app.current_mode = AppMode::Normal;
+                app.status_message = None;
+            }
+            _ => {}
+        },
+        AppMode::InventoryConfirmDelete => match action {
+            Action::Confirm => delete_inventory_selection(app, client, tx),
+            Action::Cancel => {
+                app.current_mode = AppMode::Inventory;
+                app.status_message = None;
+            }
+            _ => {}
+        },
+        AppMode::RunningModels => match action {
+            Action::MoveDown => app.next_running_model(),
+            Action::MoveUp => app.previous_running_model(),
+            Action::MoveTop | Action::MoveBottom => {
+                app.running_table_state.select(edge_index(action, app.running_models.len()));
+            }
+            Action::LoadModel | Action::UnloadModel | Action::PinModel => {
+                let selected = app
+                    .running_table_state
+                    .selected()
+                    .and_then(|i| app.running_models.get(i))
+                    .map(|m| m.name.clone());
+                if let Some(name) = selected {
+                    spawn_keep_alive(app, client, tx, name, keep_alive_for_action(action));
+                }
+            }
+            Action::Back => {
+                app.current_mode = AppMode::Normal;
+                app.status_message = None;
+            }
+            _ => {}
+        },
+        AppMode::Downloads => match action {
+            Action::MoveDown => app.downloads.next(),
+            Action::MoveUp => app.downloads.previous(),
+            Action::MoveTop | Action::MoveBottom => {
+                let i = edge_index(action, app.downloads.jobs.len());
+                app.downloads.list_state.select(i);
+            }
+            Action::CancelDownload => {
+                if let Some(model) = app.downloads.cancel_selected() {
+                    app.status_message = Some(format!("Cancelled {}", model));
+                    start_pending_downloads(app, client, tx);
+                }
+            }
+            Action::RetryDownload => {
+                if let Some(model) = app.downloads.retry_selected() {
+                    app.status_message = Some(format!("Retrying {}", model));
+                    start_pending_downloads(app, client, tx);
+                }
+            }
+            Action::ClearFinished => app.downloads.clear_inactive(),
+            Action::MoreConcurrent => {
+                app.downloads.max_concurrent += 1;
+                start_pending_downloads(app, client, tx);
+            }
+            Action::FewerConcurrent => {
+                app.downloads.max_concurrent = app.downloads.max_concurrent.saturating_sub(1).max(1);
+            }
+            Action::Back => {
+                app.current_mode = AppMode::Normal;
+                app.status_message = None;
+            }
+            _ => {}
+        },
+        // Keys are not read while the terminal belongs to another program
+        AppMode::RunningOllama | AppMode::EditingExternally => {}
+        AppMode::Chat => handle_chat_action(action, app, client, tx),
+        AppMode::InstallSelectModelFilter => match action {
+            Action::Submit => {
+                // Confirm filter and return to install select mode
+                app.registry_filter_input.push_history();
+                app.current_mode = AppMode::InstallSelectModel;
+                app.install_error = if app.is_registry_filtered {
+                    Some(format!(
+                        "Filter: '{}' ({} models)",
+                        app.registry_filter_input.text(),
+                        app.get_current_registry_models().len()
+                    ))
+                } else {
+                    None
+                };
+            }
+            Action::Cancel => {
+                // Cancel filter - clear it and return to install select mode
+                app.clear_registry_filter();
+                app.current_mode = AppMode::InstallSelectModel;
+                app.install_error = Some("Filter cleared".to_string());
+            }
+            _ => {
+                if app.registry_filter_input.apply(action, &mut app.kill_ring) == Some(true) {
+                    app.apply_registry_filter();
+                }
+            }
+        },
+        AppMode::Help => match action {
+            Action::MoveDown => {
+                app.help_scroll = (app.help_scroll + 1).min(help_line_count(app).saturating_sub(1));
+            }
+            Action::MoveUp => app.help_scroll = app.help_scroll.saturating_sub(1),
+            Action::MoveTop => app.help_scroll = 0,
+            Action::MoveBottom => app.help_scroll = help_line_count(app).saturating_sub(1),
+            Action::Back => {
+                app.current_mode = app.previous_mode.take().unwrap_or(AppMode::Normal);
+                app.status_message = None;
+            }
+            _ => {}
+        },
+        AppMode::Details => match action {
+            Action::MoveDown => app.details.scroll_by(1),
+            Action::MoveUp => app.details.scroll_by(-1),
+            Action::PageDown => app.details.scroll_page(1),
+            Action::PageUp => app.details.scroll_page(-1),
+            Action::MoveTop => app.details.set_scroll(0),
+            Action::MoveBottom => app.details.set_scroll(u16::MAX),
+            Action::NextTab => app.details.cycle_tab(1),
+            Action::PrevTab => app.details.cycle_tab(-1),
+            Action::Search => {
+                app.details.search.clear();
+                app.current_mode = AppMode::DetailsSearch;
+            }
+            Action::NextMatch => app.details.step_match(1),
+            Action::PrevMatch => app.details.step_match(-1),
+            // The first Back only drops the search highlights
+            Action::Back if app.details.is_searching() => app.details.search.clear(),
+            Action::SwitchPane | Action::Back => app.current_mode = AppMode::Normal,
+            _ => {}
+        },
+        AppMode::Tensors => {
+            let Some(view) = app.tensors.as_mut() else {
+                app.current_mode = AppMode::Normal;
+                return Ok(false);
+            };
+            let page = i64::from(view.page_height.saturating_sub(1).max(1));
+            match action {
+                Action::MoveDown => view.step(1),
+                Action::MoveUp => view.step(-1),
+                Action::PageDown => view.step(page),
+                Action::PageUp => view.step(-page),
+                Action::MoveTop | Action::MoveBottom => view.table_state.select(edge_index(action, view.rows.len())),
+                Action::Back => app.current_mode = AppMode::Normal,
+                _ => {}
+            }
+        }
+        AppMode::DetailsSearch => match action {
+            Action::Submit => {
+                app.details.search.push_history();
+                app.current_mode = AppMode::Details;
+            }
+            Action::Cancel => {
+                app.details.search.clear();
+                app.current_mode = AppMode::Details;
+            }
+            _ => {
+                if app.details.search.apply(action, &mut app.kill_ring) == Some(true) {
+                    app.details.restart_search();
+                }
+            }
+        },
+    }
+    Ok(false)
+}
+
+/// Lines scrolled per mouse wheel step in text panes.
+const WHEEL_LINES: i32 = 3;
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Moves a list selection by `delta` entries without wrapping around.
+fn step_index(selected: Option<usize>, len: usize, delta: i32) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    let index = selected.map_or(0, |i| i as i64 + i64::from(delta));
+    Some(index.clamp(0, len as i64 - 1) as usize)
+}
+
+fn contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.x + area.width && row >= area.y && row < area.y + area.height
+}
+
+/// Handles clicks and wheel scrolling. `area` is the terminal size the screen was drawn at.
+/// Returns `Ok(true)` if the application should quit.
+pub fn handle_mouse_event(
+    mouse: MouseEvent,
+    area: Rect,
+    app: &mut AppState,
+    client: &mut OllamaClient,
+    registry: &RegistryClient,
+    tx: &EventSender,
+) -> Result<bool> {
+    match mouse.kind {
+        MouseEventKind::ScrollDown => handle_wheel(app, registry, tx, area, &mouse, 1),
+        MouseEventKind::ScrollUp => handle_wheel(app, registry, tx, area, &mouse, -1),
+        MouseEventKind::Down(MouseButton::Left) => {
+            let double = app
+                .last_click
+                .is_some_and(|(time, row)| row == mouse.row && time.elapsed() <= DOUBLE_CLICK_INTERVAL);
+            // A third click starts a new double-click
+            app.last_click = (!double).then(|| (Instant::now(), mouse.row));
+            return handle_click(app, client, registry, tx, area, &mouse, double);
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+/// Clicking a panel focuses it; clicking an entry selects it and double-clicking opens it (a chat for models).
+fn handle_click(
+    app: &mut AppState,
+    client: &mut OllamaClient,
+    registry: &RegistryClient,
+    tx: &EventSender,
+    area: Rect,
+    mouse: &MouseEvent,
+    double: bool,
+) -> Result<bool> {
+    let (column, row) = (mouse.column, mouse.row);
+    match app.current_mode {
+        AppMode::Normal | AppMode::Details | AppMode::DetailsSearch | AppMode::Filter | AppMode::Chat => {
+            let layout = ui::screen_layout(area, app);
+            if contains(layout.model_list, column, row) {
+                if app.current_mode == AppMode::Filter {
+                    handle_action(Action::Submit, app, client, registry, tx)?;
+                }
+                app.current_mode = AppMode::Normal;
+                let len = app.get_current_models().len();
+                if let Some(index) = ui::list_row_at(layout.model_list, 1, row, app.list_state.selected(), len) {
+                    app.select_and_prepare_fetch(Some(index));
+                    // Same as Enter: chat in the app rather than suspending it for `ollama run`
+                    if double {
+                        return handle_action(Action::Chat, app, client, registry, tx);
                     }
-                    _ => {}
-                },
-                AppMode::InstallConfirm => match key.code {
-                    KeyCode::Char('y') | KeyCode::Char('Y') => {
-                        if let (Some(model), Some(tag)) = (app.selected_registry_model.clone(), app.selected_registry_tag.clone()) {
-                            app.current_mode = AppMode::Installing;
-                            app.install_status = Some(format!("Starting pull for {}:{}...", model, tag));
-                            app.install_error = None;
-
-                            let tx_clone = tx.clone();
-                            let client_clone_for_refresh = client.clone();
-                            let model_clone = model.clone();
-                            let tag_clone = tag.clone();
-                            tokio::spawn(async move {
-                                tasks::pull_model(client_clone_for_refresh, tx_clone, model_clone, tag_clone).await;
-                            });
+                }
+            } else if contains(layout.side_pane, column, row)
+                && matches!(app.current_mode, AppMode::Normal | AppMode::Details)
+            {
+                if let Some(tab) = ui::details_tab_at(layout.side_pane, column, row) {
+                    app.details.select_tab(tab);
+                }
+                if app.current_mode == AppMode::Normal {
+                    handle_action(Action::SwitchPane, app, client, registry, tx)?;
+                }
+            }
+        }
+        AppMode::InstallSelectModel | AppMode::InstallSelectModelFilter => {
+            let (list_area, _) = ui::registry_dialog_areas(area, app);
+            let len = app.get_current_registry_models().len();
+            if let Some(index) = ui::list_row_at(list_area, 0, row, app.registry_model_list_state.selected(), len) {
+                if app.current_mode == AppMode::InstallSelectModelFilter {
+                    handle_action(Action::Submit, app, client, registry, tx)?;
+                }
+                app.registry_model_list_state.select(Some(index));
+                if double {
+                    return handle_action(Action::Select, app, client, registry, tx);
+                }
+            }
+        }
+        AppMode::InstallSelectTag if !app.is_fetching_registry => {
+            let list_area = ui::tag_dialog_area(area);
+            let len = app.registry_tags.len();
+            if let Some(index) = ui::list_row_at(list_area, 0, row, app.registry_tag_list_state.selected(), len) {
+                app.registry_tag_list_state.select(Some(index));
+                request_highlighted_manifest(app, registry, tx);
+                if double {
+                    return handle_action(Action::Select, app, client, registry, tx);
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+/// Scrolls whatever is under the mouse: lists move their selection, text panes scroll.
+fn handle_wheel(
+    app: &mut AppState,
+    registry: &RegistryClient,
+    tx: &EventSender,
+    area: Rect,
+    mouse: &MouseEvent,
+    direction: i32,
+) {
+    let (column, row) = (mouse.column, mouse.row);
+    match app.current_mode {
+        AppMode::Normal | AppMode::Details | AppMode::DetailsSearch | AppMode::Filter | AppMode::Chat => {
+            let layout = ui::screen_layout(area, app);
+            if contains(layout.model_list, column, row) {
+                let len = app.get_current_models().len();
+                if let Some(index) = step_index(app.list_state.selected(), len, direction) {
+                    app.select_and_prepare_fetch(Some(index));
+                }
+            } else if contains(layout.side_pane, column, row) {
+                match app.chat.as_mut() {
+                    Some(chat) if app.current_mode == AppMode::Chat => {
+                        if direction > 0 {
+                            chat.scroll_down(WHEEL_LINES as u16);
                         } else {
-                             app.install_error = Some("Model or tag not selected.".to_string());
-                             app.current_mode = AppMode::InstallSelectTag;
+                            chat.scroll_up(WHEEL_LINES as u16);
                         }
                     }
-                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
-                        app.current_mode = AppMode::InstallSelectTag;
-                        app.selected_registry_tag = None;
-                        app.install_error = None;
-                    }
-                    _ => {}
-                },
-                AppMode::Installing => {
-                    // Input is ignored while installing.
-                }
-                AppMode::RunningOllama => unreachable!(),
-                AppMode::InstallSelectModelFilter => match key.code {
-                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        // Clear filter input with Ctrl+C
-                        handle_filter_clear(app);
-                    }
-                    KeyCode::Char(c) => {
-                        // Add character to registry filter input
-                        app.registry_filter_input_char(c);
-                    }
-                    KeyCode::Backspace => {
-                        // Remove character from registry filter input
-                        app.registry_filter_input_backspace();
-                    }
-                    KeyCode::Left => {
-                        app.registry_filter_cursor_left();
-                    }
-                    KeyCode::Right => {
-                        app.registry_filter_cursor_right();
-                    }
-                    KeyCode::Enter => {
-                        // Confirm filter and return to install select mode
-                        app.current_mode = AppMode::InstallSelectModel;
-                        app.install_error = if app.is_registry_filtered {
-                            Some(format!("Filter: '{}' ({} models)", app.registry_filter_input, app.get_current_registry_models().len()))
-                        } else {
-                            None
-                        };
-                    }
-                    KeyCode::Esc => {
-                        // Cancel filter - clear it and return to install select mode
-                        app.clear_registry_filter();
-                        app.current_mode = AppMode::InstallSelectModel;
-                        app.install_error = Some("Filter cleared".to_string());
-                    }
-                    _ => {}
-                },
-                AppMode::Help => match key.code {
-                    KeyCode::Char('h') | KeyCode::Char('?') | KeyCode::Char('q') | KeyCode::Esc => {
-                        app.current_mode = app.previous_mode.take().unwrap_or(AppMode::Normal);
-                        app.status_message = None;
-                    }
-                    _ => {}
+                    _ => app.details.scroll_by(direction * WHEEL_LINES),
                 }
             }
         }
+        AppMode::InstallSelectModel | AppMode::InstallSelectModelFilter => {
+            let len = app.get_current_registry_models().len();
+            let index = step_index(app.registry_model_list_state.selected(), len, direction);
+            app.registry_model_list_state.select(index);
+        }
+        AppMode::InstallSelectTag => {
+            let index = step_index(app.registry_tag_list_state.selected(), app.registry_tags.len(), direction);
+            if index.is_some() {
+                app.registry_tag_list_state.select(index);
+                request_highlighted_manifest(app, registry, tx);
+            }
+        }
+        AppMode::Tensors => {
+            if let Some(view) = app.tensors.as_mut() {
+                view.step(i64::from(direction * WHEEL_LINES));
+            }
+        }
+        AppMode::Help => {
+            let max = help_line_count(app).saturating_sub(1);
+            let scroll = i32::from(app.help_scroll) + direction * WHEEL_LINES;
+            app.help_scroll = scroll.clamp(0, i32::from(max)) as u16;
+        }
+        _ => {}
     }
-    Ok(false)
 }
 
 /// Handles asynchronous events received from tasks.
-pub fn handle_app_event(event: AppEvent, app: &mut AppState) {
+pub fn handle_app_event(
+    event: AppEvent,
+    app: &mut AppState,
+    client: &OllamaClient,
+    registry: &RegistryClient,
+    tx: &EventSender,
+) {
+    // Deletes and pulls report the new model list of their host; keep the inventory current
+    if let AppEvent::LocalModelsRefreshed(host, Ok(models)) = &event
+        && let Some(inventory) = app.inventory.as_mut()
+    {
+        inventory.set_host_models(host, Ok(models.clone()));
+    }
+
      match event {
-        AppEvent::ModelDetailsFetched(result) => {
-            app.is_fetching_details = false;
-            match result {
+        // Results from a host that is no longer active
+        AppEvent::ModelDetailsFetched(host, _, _, _)
+        | AppEvent::TensorsFetched(host, _, _)
+        | AppEvent::ModelCopied(host, _, _, _, _)
+        | AppEvent::CreateCompleted(host, _, _, _)
+        | AppEvent::LocalModelsRefreshed(host, _)
+        | AppEvent::RunningModelsFetched(host, _)
+            if host != client.host() => {}
+        AppEvent::ModelDetailsFetched(_, request_id, name, result) => {
+            // Superseded by a newer request, or the cache was cleared meanwhile
+            let Some(digest) = app.show_cache.finish_request(&name, request_id) else {
+                return;
+            };
+            // The details pane shows whatever the cache holds for the selected model
+            let is_selected = app.get_selected_model_name().as_ref() == Some(&name);
+            match *result {
                 Ok(details) => {
-                    app.selected_model_details = Some(details);
-                    app.status_message = None;
+                    if is_selected && app.status_message.as_deref().is_some_and(|s| s.starts_with(DETAILS_ERROR)) {
+                        app.status_message = None;
+                    }
+                    app.show_cache.insert(name, digest, details);
+                    if app.filter_query.needs_show_details() {
+                        app.refresh_filter();
+                    }
                 }
-                Err(e) => {
-                    app.selected_model_details = None;
-                    app.status_message = Some(format!("Error fetching details: {}", e));
+                Err(e) if is_selected => {
+                    app.status_message = Some(format!("{}: {}", DETAILS_ERROR, e));
                 }
+                Err(_) => {}
+            }
+        }
+        AppEvent::TensorsFetched(_, name, result) => {
+            if let Some(view) = app.tensors.as_mut()
+                && view.model == name
+            {
+                view.set_result(result.map_err(|e| e.to_string()));
             }
         }
         AppEvent::RegistryModelsFetched(result) => {
@@ -382,6 +1435,7 @@ pub fn handle_app_event(event: AppEvent, app: &mut AppState) {
                     app.registry_tags = tags;
                      if !app.registry_tags.is_empty() {
                         app.registry_tag_list_state.select(Some(0));
+                        request_highlighted_manifest(app, registry, tx);
                     } else {
                         app.registry_tag_list_state.select(None);
                         app.install_error = Some("No tags found for this model.".to_string());
@@ -395,25 +1449,127 @@ pub fn handle_app_event(event: AppEvent, app: &mut AppState) {
                 }
             }
         }
-        AppEvent::ModelPullCompleted(result) => {
-            app.install_status = None;
+        AppEvent::RegistryManifestFetched(model_name, tag, result) => {
+            // Ignore manifests for a model the user has already navigated away from
+            if app.selected_registry_model.as_deref() == Some(model_name.as_str()) {
+                let status = match result {
+                    Ok(manifest) => ManifestStatus::Loaded(manifest),
+                    Err(e) => ManifestStatus::Failed(e.to_string()),
+                };
+                app.registry_manifests.insert(tag, status);
+            }
+        }
+        AppEvent::UpdateChecked(model_name, result) => {
+            let status = match result {
+                Ok(remote_digest) => UpdateStatus::Checked(remote_digest),
+                Err(e) => UpdateStatus::Failed(e.to_string()),
+            };
+            app.update_status.insert(model_name, status);
+        }
+        AppEvent::UpdateCheckFinished => {
+            let outdated = app.outdated_model_names().len();
+            let failed = app
+                .update_status
+                .values()
+                .filter(|s| matches!(s, UpdateStatus::Failed(_)))
+                .count();
+            app.status_message = Some(match (outdated, failed) {
+                (0, 0) => "All models are up to date".to_string(),
+                (0, f) => format!("No updates found ({} models could not be checked)", f),
+                (o, 0) => format!("{} models have updates (a: Update All)", o),
+                (o, f) => format!("{} models have updates (a: Update All), {} could not be checked", o, f),
+            });
+        }
+        AppEvent::DownloadProgress(job_id, progress) => {
+            app.downloads.update_progress(job_id, &progress);
+        }
+        AppEvent::DownloadCompleted(job_id, result) => {
+            let model = app.downloads.job_mut(job_id).map(|j| j.model.clone()).unwrap_or_default();
             match result {
                 Ok(_) => {
-                    app.status_message = Some("Model pull successful! Refreshing list...".to_string());
+                    app.downloads.complete(job_id, None);
+                    app.status_message = Some(format!("Pulled {}", model));
                 }
                 Err(e) => {
-                    app.install_error = Some(format!("Model pull/delete failed: {}", e));
-                    app.current_mode = AppMode::Normal;
+                    app.downloads.complete(job_id, Some(e.to_string()));
+                    app.status_message = Some(format!("Pull of {} failed: {}", model, e));
                 }
             }
-            app.selected_registry_model = None;
-            app.selected_registry_tag = None;
+            start_pending_downloads(app, client, tx);
         }
-        AppEvent::LocalModelsRefreshed(result) => {
+        AppEvent::ModelDeleteFailed(e) => {
+            app.install_error = Some(format!("Model delete failed: {}", e));
+        }
+        AppEvent::CreateProgress(request_id, progress) => {
+            if let Some(editor) = app.derive.as_mut() {
+                editor.push_progress(request_id, progress.status);
+            } else if let Some(form) = app.param_form.as_mut() {
+                form.push_progress(request_id, progress.status);
+            } else if let Some(dialog) = app.quantize.as_mut() {
+                dialog.push_progress(request_id, progress);
+            }
+        }
+        AppEvent::CreateCompleted(_, request_id, model, result) => {
+            let from_open_editor = app.derive.as_ref().is_some_and(|e| e.request_id == Some(request_id));
+            let from_open_form = app.param_form.as_ref().is_some_and(|f| f.request_id == Some(request_id));
+            let from_open_quantize = app.quantize.as_ref().is_some_and(|q| q.request_id == Some(request_id));
+            match result {
+                Ok(()) => {
+                    if from_open_editor || from_open_form {
+                        app.derive = None;
+                        app.param_form = None;
+                        if matches!(app.current_mode, AppMode::Derive | AppMode::ParamForm) {
+                            app.current_mode = AppMode::Normal;
+                        }
+                    }
+                    app.status_message = Some(format!("Created {}", model));
+                    // The quantize dialog stays open, e.g. to make another quantization level
+                    if from_open_quantize && let Some(dialog) = app.quantize.as_mut() {
+                        app.status_message = Some(dialog.finish_create(&model, &app.models));
+                    }
+                    // The list was refreshed just before; show the new model
+                    if let Some(i) = app.get_current_models().iter().position(|m| m.name.eq_ignore_ascii_case(&model)) {
+                        app.select_and_prepare_fetch(Some(i));
+                    }
+                }
+                Err(e) => match (app.derive.as_mut(), app.param_form.as_mut(), app.quantize.as_mut()) {
+                    (Some(editor), _, _) if from_open_editor => editor.fail_create(request_id, e.to_string()),
+                    (_, Some(form), _) if from_open_form => form.fail_create(request_id, e.to_string()),
+                    (_, _, Some(dialog)) if from_open_quantize => dialog.fail_create(request_id, e.to_string()),
+                    _ => app.status_message = Some(format!("Creating {} failed: {}", model, e)),
+                },
+            }
+        }
+        AppEvent::ExternalEditClosed(_) => {}
+        AppEvent::ModelCopied(_, mode, source, target, result) => match result {
+            Ok(()) => {
+                let done = match mode {
+                    CopyMode::Copy => "Copied",
+                    CopyMode::Rename => "Renamed",
+                };
+                app.status_message = Some(format!("{} {} to {}", done, source, target));
+                // The list was refreshed just before; follow the model to its new name
+                if let Some(i) = app.get_current_models().iter().position(|m| m.name.eq_ignore_ascii_case(&target)) {
+                    app.select_and_prepare_fetch(Some(i));
+                }
+            }
+            Err(e) => {
+                let what = match mode {
+                    CopyMode::Copy => "Copy",
+                    CopyMode::Rename => "Rename",
+                };
+                app.status_message = Some(format!("{} of {} failed: {}", what, source, e));
+            }
+        },
+        AppEvent::LocalModelsRefreshed(_, result) => {
             match result {
                 Ok(models) => {
                     let old_selection_index = app.list_state.selected();
-                    app.models = models;
+                    app.show_cache.retain_models(&models);
+                    app.set_models(models);
+                    if app.models.is_empty() {
+                        app.status_message = None;
+                    }
                     
                     // Reapply filter if it was active
                     if app.is_filtered {
@@ -427,10 +1583,7 @@ pub fn handle_app_event(event: AppEvent, app: &mut AppState) {
                         Some(old_selection_index.unwrap_or(0).min(current_models.len().saturating_sub(1)))
                     };
                     app.select_and_prepare_fetch(new_selection);
-
-                    if app.status_message.as_deref() == Some("Model pull successful! Refreshing list...") {
-                         app.status_message = None;
-                    }
+                    start_details_prefetch(app, client, tx);
                 }
                 Err(e) => {
                     if app.install_error.is_none() {
@@ -438,8 +1591,55 @@ pub fn handle_app_event(event: AppEvent, app: &mut AppState) {
                     }
                 }
             }
-            app.current_mode = AppMode::Normal;
-            app.install_status = None;
+        }
+        AppEvent::InventoryFetched(request_id, host, result) => {
+            if let Some(inventory) = app.inventory.as_mut()
+                && inventory.request_id == request_id
+            {
+                inventory.set_host_models(&host, result.map_err(|e| e.to_string()));
+            }
+        }
+        AppEvent::RunningModelsFetched(_, result) => {
+            app.is_fetching_running = false;
+            app.last_running_refresh = Some(Instant::now());
+            match result {
+                Ok(models) => {
+                    app.running_models = models;
+                    app.running_models_error = None;
+                    let len = app.running_models.len();
+                    match app.running_table_state.selected() {
+                        _ if len == 0 => app.running_table_state.select(None),
+                        Some(i) if i >= len => app.running_table_state.select(Some(len - 1)),
+                        None => app.running_table_state.select(Some(0)),
+                        _ => {}
+                    }
+                }
+                Err(e) => {
+                    app.running_models_error = Some(e.to_string());
+                }
+            }
+        }
+        AppEvent::KeepAliveUpdated(model_name, keep_alive, result) => {
+            match result {
+                Ok(_) => {
+                    app.status_message = Some(format!("{} {}", keep_alive_action(keep_alive).1, model_name));
+                }
+                Err(e) => {
+                    app.status_message = Some(format!("{} {} failed: {}", keep_alive_action(keep_alive).0, model_name, e));
+                }
+            }
+            // Refresh the loaded-models list right away
+            app.last_running_refresh = None;
+        }
+        AppEvent::ChatToken(request_id, token) => {
+            if let Some(chat) = app.chat.as_mut() {
+                chat.append_token(request_id, &token);
+            }
+        }
+        AppEvent::ChatCompleted(request_id, result) => {
+            if let Some(chat) = app.chat.as_mut() {
+                chat.finish(request_id, result.err().map(|e| e.to_string()));
+            }
         }
         AppEvent::OllamaRunCompleted(_) => {
              eprintln!("Warning: OllamaRunCompleted event received outside of RunningOllama mode.");
@@ -448,6 +1648,20 @@ pub fn handle_app_event(event: AppEvent, app: &mut AppState) {
     }
 }
 
+/// Puts the Modelfile saved in `$EDITOR` back into the editor pane.
+pub fn handle_external_edit_closed(result: Result<String>, app: &mut AppState, terminal: &mut tui::Tui) -> Result<()> {
+    app.current_mode = if app.derive.is_some() { AppMode::Derive } else { AppMode::Normal };
+    if let Some(editor) = app.derive.as_mut() {
+        match result {
+            Ok(text) => editor.replace_modelfile(&text),
+            Err(e) => editor.show_error(format!("External editor failed: {}", e)),
+        }
+    }
+    // The screen was handed to the editor; repaint all of it
+    terminal.clear()?;
+    Ok(())
+}
+
 /// Handles the completion event specifically when in RunningOllama mode.
 /// Returns `Ok(true)` if the app should exit due to channel closure, `Ok(false)` otherwise.
 /// Forces a redraw on the passed terminal.
@@ -0,0 +1,180 @@
+// src/inventory.rs
+// Merged view of the models installed on every configured host.
+
+use crate::config::HostProfile;
+use crate::ollama_api::ModelInfo;
+use ratatui::widgets::TableState;
+use std::collections::BTreeMap;
+
+/// Models reported by one host. `None` while the request is in flight.
+#[derive(Debug, Clone)]
+pub struct HostInventory {
+    pub host: HostProfile,
+    pub models: Option<Result<Vec<ModelInfo>, String>>,
+}
+
+/// How a model on one host compares to the same model on the other reachable hosts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowStatus {
+    /// Same digest on every reachable host.
+    InSync,
+    /// Same digest wherever installed, but missing on some hosts.
+    Partial,
+    /// Installed hosts disagree on the digest.
+    Differs,
+    /// Not installed on this host.
+    Missing,
+}
+
+impl RowStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RowStatus::InSync => "in sync",
+            RowStatus::Partial => "not on all hosts",
+            RowStatus::Differs => "digest differs",
+            RowStatus::Missing => "missing",
+        }
+    }
+}
+
+/// One `(model, host)` pair of the merged table.
+#[derive(Debug, Clone)]
+pub struct InventoryRow {
+    pub model: String,
+    pub host_index: usize,
+    /// The installed model, or `None` for a missing row.
+    pub info: Option<ModelInfo>,
+    pub status: RowStatus,
+}
+
+#[derive(Debug, Clone)]
+pub struct InventoryState {
+    pub hosts: Vec<HostInventory>,
+    pub rows: Vec<InventoryRow>,
+    pub table_state: TableState,
+    /// Identifies the current refresh; results from older refreshes are dropped.
+    pub request_id: u64,
+}
+
+impl InventoryState {
+    pub fn new(hosts: Vec<HostProfile>) -> Self {
+        Self {
+            hosts: hosts
+                .into_iter()
+                .map(|host| HostInventory { host, models: None })
+                .collect(),
+            rows: Vec::new(),
+            table_state: TableState::default(),
+            request_id: 0,
+        }
+    }
+
+    /// Clears all host results and returns the id for the new round of requests.
+    pub fn begin_refresh(&mut self) -> u64 {
+        self.request_id += 1;
+        for host in self.hosts.iter_mut() {
+            host.models = None;
+        }
+        self.rebuild_rows();
+        self.request_id
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.hosts.iter().any(|h| h.models.is_none())
+    }
+
+    /// Stores the model list of the host at `url` and re-merges the table.
+    pub fn set_host_models(&mut self, url: &str, result: Result<Vec<ModelInfo>, String>) {
+        let Some(host) = self.hosts.iter_mut().find(|h| h.host.url == url) else {
+            return;
+        };
+        host.models = Some(result);
+        self.rebuild_rows();
+    }
+
+    fn rebuild_rows(&mut self) {
+        // Model name -> (host index, installed model) for every reachable host
+        let mut by_model: BTreeMap<&str, Vec<(usize, Option<&ModelInfo>)>> = BTreeMap::new();
+        let reachable: Vec<(usize, &Vec<ModelInfo>)> = self
+            .hosts
+            .iter()
+            .enumerate()
+            .filter_map(|(i, h)| match &h.models {
+                Some(Ok(models)) => Some((i, models)),
+                _ => None,
+            })
+            .collect();
+
+        for (_, models) in &reachable {
+            for model in models.iter() {
+                by_model.entry(model.name.as_str()).or_default();
+            }
+        }
+        for (name, entries) in by_model.iter_mut() {
+            for (host_index, models) in &reachable {
+                entries.push((*host_index, models.iter().find(|m| m.name == *name)));
+            }
+        }
+
+        let mut rows = Vec::new();
+        for (name, entries) in by_model {
+            let mut digests: Vec<&str> = entries
+                .iter()
+                .filter_map(|(_, info)| info.map(|m| m.digest.as_str()))
+                .collect();
+            digests.sort_unstable();
+            digests.dedup();
+            let missing_somewhere = entries.iter().any(|(_, info)| info.is_none());
+
+            for (host_index, info) in entries {
+                let status = match info {
+                    None => RowStatus::Missing,
+                    Some(_) if digests.len() > 1 => RowStatus::Differs,
+                    Some(_) if missing_somewhere => RowStatus::Partial,
+                    Some(_) => RowStatus::InSync,
+                };
+                rows.push(InventoryRow {
+                    model: name.to_string(),
+                    host_index,
+                    info: info.cloned(),
+                    status,
+                });
+            }
+        }
+        self.rows = rows;
+
+        match self.table_state.selected() {
+            _ if self.rows.is_empty() => self.table_state.select(None),
+            Some(i) if i >= self.rows.len() => self.table_state.select(Some(self.rows.len() - 1)),
+            None => self.table_state.select(Some(0)),
+            _ => {}
+        }
+    }
+
+    pub fn selected_row(&self) -> Option<&InventoryRow> {
+        self.table_state.selected().and_then(|i| self.rows.get(i))
+    }
+
+    /// Host profile of the selected row.
+    pub fn selected_host(&self) -> Option<&HostProfile> {
+        self.selected_row()
+            .and_then(|row| self.hosts.get(row.host_index))
+            .map(|h| &h.host)
+    }
+
+    pub fn next(&mut self) {
+        let len = self.rows.len();
+        if len > 0 {
+            let i = self.table_state.selected().map_or(0, |i| (i + 1) % len);
+            self.table_state.select(Some(i));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let len = self.rows.len();
+        if len > 0 {
+            let i = self.table_state.selected().map_or(len - 1, |i| (i + len - 1) % len);
+            self.table_state.select(Some(i));
+        }
+    }
+}
@@ -0,0 +1,1078 @@
+// src/keymap.rs
+// Maps keys (and key sequences like `g g`) to actions per input context.
+// Bindings come from a preset and the `[keys.*]` sections of the config.
+
+use crate::app::AppMode;
+use crate::config::{KeyPreset, KeySpecs, KeysConfig};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A key plus modifiers, e.g. `ctrl+c`, `X`, `enter` or `f5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        // The plus key itself is written as "+" or e.g. "ctrl++"
+        let (mods, key) = if spec == "+" {
+            ("", "+")
+        } else if let Some(mods) = spec.strip_suffix("++") {
+            (mods, "+")
+        } else {
+            spec.rsplit_once('+').unwrap_or(("", spec))
+        };
+        if key.is_empty() {
+            return Err(format!("invalid key '{}'", spec));
+        }
+
+        let mut modifiers = KeyModifiers::NONE;
+        for m in mods.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match m.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("invalid key '{}': unknown modifier '{}'", spec, m)),
+            };
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pgup" | "pageup" => KeyCode::PageUp,
+            "pgdn" | "pagedown" => KeyCode::PageDown,
+            lower if lower.len() > 1 && lower.starts_with('f') => match lower[1..].parse::<u8>() {
+                Ok(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(format!("invalid key '{}'", spec)),
+            },
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("invalid key '{}'", spec)),
+                }
+            }
+        };
+        Ok(Self::new(code, modifiers))
+    }
+
+    /// Shift is implied by the character itself (`X` vs `x`), so it is dropped for chars.
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+/// Formats the key the way it is written in the config.
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl+"),
+            (KeyModifiers::ALT, "alt+"),
+            (KeyModifiers::SHIFT, "shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Enter => f.write_str("enter"),
+            KeyCode::Esc => f.write_str("esc"),
+            KeyCode::Tab => f.write_str("tab"),
+            KeyCode::Backspace => f.write_str("backspace"),
+            KeyCode::Delete => f.write_str("delete"),
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Up => f.write_str("up"),
+            KeyCode::Down => f.write_str("down"),
+            KeyCode::Left => f.write_str("left"),
+            KeyCode::Right => f.write_str("right"),
+            KeyCode::Home => f.write_str("home"),
+            KeyCode::End => f.write_str("end"),
+            KeyCode::PageUp => f.write_str("pgup"),
+            KeyCode::PageDown => f.write_str("pgdn"),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// One or more keys pressed in order, written space-separated in the config (`"g g"`).
+pub type KeySequence = Vec<KeyBinding>;
+
+fn parse_sequence(spec: &str) -> Result<KeySequence, String> {
+    let keys = spec
+        .split_whitespace()
+        .map(KeyBinding::parse)
+        .collect::<Result<KeySequence, String>>()?;
+    if keys.is_empty() {
+        return Err("empty key".to_string());
+    }
+    Ok(keys)
+}
+
+/// Formats a sequence compactly: `gg`, `dd`, but `g enter`.
+pub fn format_sequence(keys: &[KeyBinding]) -> String {
+    let names: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+    if names.iter().all(|n| n.chars().count() == 1) {
+        names.concat()
+    } else {
+        names.join(" ")
+    }
+}
+
+/// Everything a key can be bound to. Each context handles a subset of these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    ToggleHelp,
+    MoveDown,
+    MoveUp,
+    MoveTop,
+    MoveBottom,
+    PageDown,
+    PageUp,
+    Select,
+    Back,
+    Confirm,
+    Cancel,
+    Filter,
+    ClearFilter,
+    Delete,
+    CopyModel,
+    RenameModel,
+    DeriveModel,
+    EditParameters,
+    Quantize,
+    Install,
+    Chat,
+    Run,
+    ShowDownloads,
+    ShowRunning,
+    ShowInventory,
+    ShowTensors,
+    SwitchHost,
+    SwitchPane,
+    NextTab,
+    PrevTab,
+    Search,
+    NextMatch,
+    PrevMatch,
+    SortNext,
+    SortReverse,
+    CheckUpdates,
+    UpdateAll,
+    LoadModel,
+    UnloadModel,
+    PinModel,
+    Pull,
+    Refresh,
+    CancelDownload,
+    RetryDownload,
+    ClearFinished,
+    MoreConcurrent,
+    FewerConcurrent,
+    Submit,
+    DeleteChar,
+    CursorLeft,
+    CursorRight,
+    WordLeft,
+    WordRight,
+    LineStart,
+    LineEnd,
+    DeleteForward,
+    DeleteWord,
+    KillToEnd,
+    KillToStart,
+    Yank,
+    YankPop,
+    HistoryPrev,
+    HistoryNext,
+    ClearInput,
+    SendMessage,
+    InsertNewline,
+    CreateModel,
+    ExternalEditor,
+    ResetField,
+    StopOrClear,
+    ClearTranscript,
+}
+
+impl Action {
+    const ALL: [Action; 72] = [
+        Action::Quit,
+        Action::ToggleHelp,
+        Action::MoveDown,
+        Action::MoveUp,
+        Action::MoveTop,
+        Action::MoveBottom,
+        Action::PageDown,
+        Action::PageUp,
+        Action::Select,
+        Action::Back,
+        Action::Confirm,
+        Action::Cancel,
+        Action::Filter,
+        Action::ClearFilter,
+        Action::Delete,
+        Action::CopyModel,
+        Action::RenameModel,
+        Action::DeriveModel,
+        Action::EditParameters,
+        Action::Quantize,
+        Action::Install,
+        Action::Chat,
+        Action::Run,
+        Action::ShowDownloads,
+        Action::ShowRunning,
+        Action::ShowInventory,
+        Action::ShowTensors,
+        Action::SwitchHost,
+        Action::SwitchPane,
+        Action::NextTab,
+        Action::PrevTab,
+        Action::Search,
+        Action::NextMatch,
+        Action::PrevMatch,
+        Action::SortNext,
+        Action::SortReverse,
+        Action::CheckUpdates,
+        Action::UpdateAll,
+        Action::LoadModel,
+        Action::UnloadModel,
+        Action::PinModel,
+        Action::Pull,
+        Action::Refresh,
+        Action::CancelDownload,
+        Action::RetryDownload,
+        Action::ClearFinished,
+        Action::MoreConcurrent,
+        Action::FewerConcurrent,
+        Action::Submit,
+        Action::DeleteChar,
+        Action::CursorLeft,
+        Action::CursorRight,
+        Action::WordLeft,
+        Action::WordRight,
+        Action::LineStart,
+        Action::LineEnd,
+        Action::DeleteForward,
+        Action::DeleteWord,
+        Action::KillToEnd,
+        Action::KillToStart,
+        Action::Yank,
+        Action::YankPop,
+        Action::HistoryPrev,
+        Action::HistoryNext,
+        Action::ClearInput,
+        Action::SendMessage,
+        Action::InsertNewline,
+        Action::CreateModel,
+        Action::ExternalEditor,
+        Action::ResetField,
+        Action::StopOrClear,
+        Action::ClearTranscript,
+    ];
+
+    /// Name used in the `[keys.*]` config sections.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::ToggleHelp => "help",
+            Action::MoveDown => "down",
+            Action::MoveUp => "up",
+            Action::MoveTop => "top",
+            Action::MoveBottom => "bottom",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::Select => "select",
+            Action::Back => "back",
+            Action::Confirm => "confirm",
+            Action::Cancel => "cancel",
+            Action::Filter => "filter",
+            Action::ClearFilter => "clear_filter",
+            Action::Delete => "delete",
+            Action::CopyModel => "copy",
+            Action::RenameModel => "rename",
+            Action::DeriveModel => "derive",
+            Action::EditParameters => "edit_parameters",
+            Action::Quantize => "quantize",
+            Action::Install => "install",
+            Action::Chat => "chat",
+            Action::Run => "run",
+            Action::ShowDownloads => "downloads",
+            Action::ShowRunning => "loaded_models",
+            Action::ShowInventory => "inventory",
+            Action::ShowTensors => "tensors",
+            Action::SwitchHost => "switch_host",
+            Action::SwitchPane => "switch_pane",
+            Action::NextTab => "next_tab",
+            Action::PrevTab => "prev_tab",
+            Action::Search => "search",
+            Action::NextMatch => "next_match",
+            Action::PrevMatch => "prev_match",
+            Action::SortNext => "sort",
+            Action::SortReverse => "reverse_sort",
+            Action::CheckUpdates => "check_updates",
+            Action::UpdateAll => "update_all",
+            Action::LoadModel => "load",
+            Action::UnloadModel => "unload",
+            Action::PinModel => "pin",
+            Action::Pull => "pull",
+            Action::Refresh => "refresh",
+            Action::CancelDownload => "cancel_download",
+            Action::RetryDownload => "retry_download",
+            Action::ClearFinished => "clear_finished",
+            Action::MoreConcurrent => "more_concurrent",
+            Action::FewerConcurrent => "fewer_concurrent",
+            Action::Submit => "submit",
+            Action::DeleteChar => "delete_char",
+            Action::CursorLeft => "cursor_left",
+            Action::CursorRight => "cursor_right",
+            Action::WordLeft => "word_left",
+            Action::WordRight => "word_right",
+            Action::LineStart => "line_start",
+            Action::LineEnd => "line_end",
+            Action::DeleteForward => "delete_forward",
+            Action::DeleteWord => "delete_word",
+            Action::KillToEnd => "kill_to_end",
+            Action::KillToStart => "kill_to_start",
+            Action::Yank => "yank",
+            Action::YankPop => "yank_pop",
+            Action::HistoryPrev => "history_prev",
+            Action::HistoryNext => "history_next",
+            Action::ClearInput => "clear_input",
+            Action::SendMessage => "send",
+            Action::InsertNewline => "newline",
+            Action::CreateModel => "create",
+            Action::ExternalEditor => "external_editor",
+            Action::ResetField => "reset_field",
+            Action::StopOrClear => "stop",
+            Action::ClearTranscript => "clear_transcript",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    /// Text shown in the help screen.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::ToggleHelp => "Show/Hide Help",
+            Action::MoveDown => "Move Down",
+            Action::MoveUp => "Move Up",
+            Action::MoveTop => "Jump to First Entry",
+            Action::MoveBottom => "Jump to Last Entry",
+            Action::PageDown => "Scroll Down a Page",
+            Action::PageUp => "Scroll Up a Page",
+            Action::Select => "Select Entry",
+            Action::Back => "Close / Go Back",
+            Action::Confirm => "Confirm Action",
+            Action::Cancel => "Cancel",
+            Action::Filter => "Filter List (Type to Search)",
+            Action::ClearFilter => "Clear Filter",
+            Action::Delete => "Delete Selected Model",
+            Action::CopyModel => "Copy Selected Model to a New Name",
+            Action::RenameModel => "Rename Selected Model",
+            Action::DeriveModel => "Derive a New Model From Its Modelfile",
+            Action::EditParameters => "Derive a New Model With Other Parameters",
+            Action::Quantize => "Quantize an F16/F32 Model",
+            Action::Install => "Install New Model",
+            Action::Chat => "Chat With Selected Model",
+            Action::Run => "Run Selected Model in ollama CLI (Suspends TUI)",
+            Action::ShowDownloads => "Show Download Queue",
+            Action::ShowRunning => "Show Loaded Models (● marks loaded models)",
+            Action::ShowInventory => "Inventory Across All Hosts",
+            Action::ShowTensors => "Inspect Tensors of Selected Model",
+            Action::SwitchHost => "Switch Ollama Host",
+            Action::SwitchPane => "Focus Details Pane / Model List",
+            Action::NextTab => "Next Tab",
+            Action::PrevTab => "Previous Tab",
+            Action::Search => "Search Text",
+            Action::NextMatch => "Next Match",
+            Action::PrevMatch => "Previous Match",
+            Action::SortNext => "Sort by Next Column",
+            Action::SortReverse => "Reverse Sort Order",
+            Action::CheckUpdates => "Check Models for Updates",
+            Action::UpdateAll => "Update All Outdated Models",
+            Action::LoadModel => "Load Selected Model Into Memory",
+            Action::UnloadModel => "Unload Selected Model",
+            Action::PinModel => "Pin Selected Model in Memory",
+            Action::Pull => "Pull Model Onto Selected Host",
+            Action::Refresh => "Refresh",
+            Action::CancelDownload => "Cancel Selected Download",
+            Action::RetryDownload => "Retry Selected Download",
+            Action::ClearFinished => "Clear Finished, Failed and Cancelled",
+            Action::MoreConcurrent => "Raise Concurrent Downloads",
+            Action::FewerConcurrent => "Lower Concurrent Downloads",
+            Action::Submit => "Confirm Input",
+            Action::DeleteChar => "Remove Character",
+            Action::CursorLeft => "Move Cursor Left",
+            Action::CursorRight => "Move Cursor Right",
+            Action::WordLeft => "Move Cursor One Word Left",
+            Action::WordRight => "Move Cursor One Word Right",
+            Action::LineStart => "Move Cursor to Line Start",
+            Action::LineEnd => "Move Cursor to Line End",
+            Action::DeleteForward => "Remove Character Under Cursor",
+            Action::DeleteWord => "Cut Word Before Cursor",
+            Action::KillToEnd => "Cut to End of Line",
+            Action::KillToStart => "Cut to Start of Line",
+            Action::Yank => "Paste Last Cut Text",
+            Action::YankPop => "Replace Paste With Older Cut Text",
+            Action::HistoryPrev => "Previous Input From History",
+            Action::HistoryNext => "Next Input From History",
+            Action::ClearInput => "Clear Input",
+            Action::SendMessage => "Send Message",
+            Action::InsertNewline => "Insert Newline",
+            Action::CreateModel => "Create the Model",
+            Action::ExternalEditor => "Edit in $EDITOR",
+            Action::ResetField => "Reset Field to the Current Value",
+            Action::StopOrClear => "Stop Response / Clear Input",
+            Action::ClearTranscript => "Clear Transcript",
+        }
+    }
+}
+
+/// A group of modes sharing one set of bindings, configured as `[keys.<name>]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyContext {
+    /// Checked after the mode's own bindings in every mode without text input.
+    Global,
+    Models,
+    Filter,
+    Install,
+    Confirm,
+    Hosts,
+    Inventory,
+    Running,
+    Downloads,
+    Chat,
+    Help,
+    Details,
+    Tensors,
+    Editor,
+    Form,
+    Quantize,
+}
+
+impl KeyContext {
+    pub const ALL: [KeyContext; 16] = [
+        KeyContext::Global,
+        KeyContext::Models,
+        KeyContext::Filter,
+        KeyContext::Install,
+        KeyContext::Confirm,
+        KeyContext::Hosts,
+        KeyContext::Inventory,
+        KeyContext::Running,
+        KeyContext::Downloads,
+        KeyContext::Chat,
+        KeyContext::Help,
+        KeyContext::Details,
+        KeyContext::Tensors,
+        KeyContext::Editor,
+        KeyContext::Form,
+        KeyContext::Quantize,
+    ];
+
+    /// The context whose bindings apply in `mode`, if keys are handled there at all.
+    pub fn for_mode(mode: &AppMode) -> Option<Self> {
+        match mode {
+            AppMode::Normal => Some(KeyContext::Models),
+            AppMode::Filter
+            | AppMode::InstallSelectModelFilter
+            | AppMode::DetailsSearch
+            | AppMode::CopyInput => {
+                Some(KeyContext::Filter)
+            }
+            AppMode::InstallSelectModel | AppMode::InstallSelectTag => Some(KeyContext::Install),
+            AppMode::ConfirmDelete | AppMode::InstallConfirm | AppMode::InventoryConfirmDelete => {
+                Some(KeyContext::Confirm)
+            }
+            AppMode::HostSelect => Some(KeyContext::Hosts),
+            AppMode::Inventory => Some(KeyContext::Inventory),
+            AppMode::RunningModels => Some(KeyContext::Running),
+            AppMode::Downloads => Some(KeyContext::Downloads),
+            AppMode::Chat => Some(KeyContext::Chat),
+            AppMode::Help => Some(KeyContext::Help),
+            AppMode::Details => Some(KeyContext::Details),
+            AppMode::Tensors => Some(KeyContext::Tensors),
+            AppMode::Derive => Some(KeyContext::Editor),
+            AppMode::ParamForm => Some(KeyContext::Form),
+            AppMode::Quantize => Some(KeyContext::Quantize),
+            AppMode::RunningOllama | AppMode::EditingExternally => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyContext::Global => "global",
+            KeyContext::Models => "models",
+            KeyContext::Filter => "filter",
+            KeyContext::Install => "install",
+            KeyContext::Confirm => "confirm",
+            KeyContext::Hosts => "hosts",
+            KeyContext::Inventory => "inventory",
+            KeyContext::Running => "running",
+            KeyContext::Downloads => "downloads",
+            KeyContext::Chat => "chat",
+            KeyContext::Help => "help",
+            KeyContext::Details => "details",
+            KeyContext::Tensors => "tensors",
+            KeyContext::Editor => "editor",
+            KeyContext::Form => "form",
+            KeyContext::Quantize => "quantize",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            KeyContext::Global => "General",
+            KeyContext::Models => "Model List",
+            KeyContext::Filter => "Filter Input",
+            KeyContext::Install => "Install Mode",
+            KeyContext::Confirm => "Dialogs",
+            KeyContext::Hosts => "Host Picker",
+            KeyContext::Inventory => "Inventory",
+            KeyContext::Running => "Loaded Models",
+            KeyContext::Downloads => "Downloads",
+            KeyContext::Chat => "Chat",
+            KeyContext::Help => "Help Dialog",
+            KeyContext::Details => "Details Pane",
+            KeyContext::Tensors => "Tensor Inspector",
+            KeyContext::Editor => "Modelfile Editor",
+            KeyContext::Form => "Parameter Form",
+            KeyContext::Quantize => "Quantize Dialog",
+        }
+    }
+
+    /// Actions handled in this context, in help screen order.
+    pub fn actions(self) -> Vec<Action> {
+        preset_bindings(KeyPreset::Default, self)
+            .into_iter()
+            .map(|(action, _)| action)
+            .collect()
+    }
+
+    /// Text contexts insert unbound characters instead of looking up sequences.
+    pub fn is_text_input(self) -> bool {
+        matches!(
+            self,
+            KeyContext::Filter | KeyContext::Chat | KeyContext::Editor | KeyContext::Form | KeyContext::Quantize
+        )
+    }
+
+    fn config_section(self, config: &KeysConfig) -> &HashMap<String, KeySpecs> {
+        match self {
+            KeyContext::Global => &config.global,
+            KeyContext::Models => &config.models,
+            KeyContext::Filter => &config.filter,
+            KeyContext::Install => &config.install,
+            KeyContext::Confirm => &config.confirm,
+            KeyContext::Hosts => &config.hosts,
+            KeyContext::Inventory => &config.inventory,
+            KeyContext::Running => &config.running,
+            KeyContext::Downloads => &config.downloads,
+            KeyContext::Chat => &config.chat,
+            KeyContext::Help => &config.help,
+            KeyContext::Details => &config.details,
+            KeyContext::Tensors => &config.tensors,
+            KeyContext::Editor => &config.editor,
+            KeyContext::Form => &config.form,
+            KeyContext::Quantize => &config.quantize,
+        }
+    }
+}
+
+/// Built-in bindings of a context. The `Default` preset lists every action the context handles.
+fn preset_bindings(preset: KeyPreset, context: KeyContext) -> Vec<(Action, Vec<&'static str>)> {
+    use Action::*;
+    let (down, up, top, bottom) = match preset {
+        KeyPreset::Default => (vec!["j", "down"], vec!["k", "up"], vec!["g g", "home"], vec!["G", "end"]),
+        KeyPreset::Vim => (vec!["j"], vec!["k"], vec!["g g"], vec!["G"]),
+        KeyPreset::Arrows => (vec!["down"], vec!["up"], vec!["home"], vec!["end"]),
+    };
+    let navigation = vec![(MoveDown, down), (MoveUp, up), (MoveTop, top), (MoveBottom, bottom)];
+    let delete = match preset {
+        KeyPreset::Default => vec!["d"],
+        KeyPreset::Vim => vec!["d d"],
+        KeyPreset::Arrows => vec!["delete"],
+    };
+    let (next_tab, prev_tab) = match preset {
+        KeyPreset::Arrows => (vec!["right"], vec!["left"]),
+        _ => (vec!["l", "right", "]"], vec!["h", "left", "["]),
+    };
+    // Panels opened with a letter close with the same letter
+    let back = |toggle: Option<&'static str>| {
+        let mut keys = match preset {
+            KeyPreset::Arrows => vec!["esc"],
+            _ => vec!["q", "esc"],
+        };
+        keys.extend(toggle);
+        (Back, keys)
+    };
+
+    let mut bindings = match context {
+        KeyContext::Global => vec![(
+            ToggleHelp,
+            match preset {
+                KeyPreset::Arrows => vec!["f1", "?"],
+                _ => vec!["h", "?"],
+            },
+        )],
+        KeyContext::Models => vec![
+            (Quit, vec!["q"]),
+            (Chat, vec!["enter"]),
+            (Run, vec!["r"]),
+            (Delete, delete),
+            (CopyModel, vec!["c"]),
+            (RenameModel, vec!["R"]),
+            (DeriveModel, vec!["e"]),
+            (EditParameters, vec!["P"]),
+            (Quantize, vec!["Q"]),
+            (Install, vec!["i"]),
+            (Filter, vec!["/"]),
+            (ClearFilter, vec!["ctrl+c"]),
+            (SortNext, vec!["s"]),
+            (SortReverse, vec!["S"]),
+            (ShowDownloads, vec!["D"]),
+            (ShowRunning, vec!["p"]),
+            (LoadModel, vec!["L"]),
+            (UnloadModel, vec!["U"]),
+            (PinModel, vec!["F"]),
+            (CheckUpdates, vec!["u"]),
+            (UpdateAll, vec!["a"]),
+            (SwitchHost, vec!["H"]),
+            (ShowInventory, vec!["I"]),
+            (ShowTensors, vec!["T"]),
+            (SwitchPane, vec!["tab"]),
+        ],
+        KeyContext::Filter => vec![
+            (Submit, vec!["enter"]),
+            (Cancel, vec!["esc"]),
+            (HistoryPrev, vec!["up", "ctrl+p"]),
+            (HistoryNext, vec!["down", "ctrl+n"]),
+            (ClearInput, vec!["ctrl+c"]),
+        ],
+        KeyContext::Install => vec![
+            (Select, vec!["enter"]),
+            (Filter, vec!["/"]),
+            (ClearFilter, vec!["ctrl+c"]),
+            back(None),
+        ],
+        KeyContext::Confirm => vec![(Confirm, vec!["y", "Y"]), (Cancel, vec!["n", "N", "esc"])],
+        KeyContext::Hosts => vec![(Select, vec!["enter"]), back(None)],
+        KeyContext::Inventory => vec![
+            (Pull, vec!["p"]),
+            (Delete, delete),
+            (Chat, vec!["enter", "r"]),
+            (Refresh, vec!["R"]),
+            back(Some("I")),
+        ],
+        KeyContext::Running => vec![
+            (LoadModel, vec!["L"]),
+            (UnloadModel, vec!["U"]),
+            (PinModel, vec!["F"]),
+            back(Some("p")),
+        ],
+        KeyContext::Downloads => vec![
+            (CancelDownload, vec!["c"]),
+            (RetryDownload, vec!["r"]),
+            (ClearFinished, vec!["x"]),
+            (MoreConcurrent, vec!["+"]),
+            (FewerConcurrent, vec!["-"]),
+            back(Some("D")),
+        ],
+        KeyContext::Chat => vec![
+            (SendMessage, vec!["enter"]),
+            (InsertNewline, vec!["alt+enter", "ctrl+j"]),
+            (HistoryPrev, vec!["ctrl+p"]),
+            (HistoryNext, vec!["ctrl+n"]),
+            (MoveUp, vec!["up"]),
+            (MoveDown, vec!["down"]),
+            (PageUp, vec!["pgup"]),
+            (PageDown, vec!["pgdn"]),
+            (StopOrClear, vec!["ctrl+c"]),
+            (ClearTranscript, vec!["ctrl+l"]),
+            (Back, vec!["esc"]),
+        ],
+        KeyContext::Help => vec![back(None)],
+        KeyContext::Details => vec![
+            (PageDown, vec!["pgdn"]),
+            (PageUp, vec!["pgup"]),
+            (NextTab, next_tab),
+            (PrevTab, prev_tab),
+            (Search, vec!["/"]),
+            (NextMatch, vec!["n"]),
+            (PrevMatch, vec!["N"]),
+            (SwitchPane, vec!["tab"]),
+            back(None),
+        ],
+        KeyContext::Tensors => vec![(PageDown, vec!["pgdn"]), (PageUp, vec!["pgup"]), back(Some("T"))],
+        KeyContext::Editor => vec![
+            (CreateModel, vec!["ctrl+s"]),
+            (Cancel, vec!["esc"]),
+            (InsertNewline, vec!["enter"]),
+            (SwitchPane, vec!["tab"]),
+            (MoveUp, vec!["up"]),
+            (MoveDown, vec!["down"]),
+            (PageUp, vec!["pgup"]),
+            (PageDown, vec!["pgdn"]),
+            (ExternalEditor, vec!["ctrl+o"]),
+        ],
+        KeyContext::Form => vec![
+            (CreateModel, vec!["ctrl+s"]),
+            (Cancel, vec!["esc"]),
+            (MoveDown, vec!["down", "enter"]),
+            (MoveUp, vec!["up"]),
+            (PageDown, vec!["pgdn"]),
+            (PageUp, vec!["pgup"]),
+            (SwitchPane, vec!["tab"]),
+            (ResetField, vec!["ctrl+r"]),
+        ],
+        KeyContext::Quantize => vec![
+            (Submit, vec!["enter"]),
+            (Cancel, vec!["esc"]),
+            (MoveUp, vec!["up"]),
+            (MoveDown, vec!["down"]),
+        ],
+    };
+
+    // List-like contexts share the navigation keys
+    if matches!(
+        context,
+        KeyContext::Models
+            | KeyContext::Install
+            | KeyContext::Hosts
+            | KeyContext::Inventory
+            | KeyContext::Running
+            | KeyContext::Downloads
+            | KeyContext::Help
+            | KeyContext::Details
+            | KeyContext::Tensors
+    ) {
+        bindings.splice(0..0, navigation);
+    }
+    // Text inputs share the line editing keys, after submit/cancel or send/newline
+    if context.is_text_input() {
+        let editing = vec![
+            (CursorLeft, vec!["left"]),
+            (CursorRight, vec!["right"]),
+            (WordLeft, vec!["alt+b", "ctrl+left"]),
+            (WordRight, vec!["alt+f", "ctrl+right"]),
+            (LineStart, vec!["home", "ctrl+a"]),
+            (LineEnd, vec!["end", "ctrl+e"]),
+            (DeleteChar, vec!["backspace"]),
+            (DeleteForward, vec!["delete", "ctrl+d"]),
+            (DeleteWord, vec!["ctrl+w", "alt+backspace"]),
+            (KillToEnd, vec!["ctrl+k"]),
+            (KillToStart, vec!["ctrl+u"]),
+            (Yank, vec!["ctrl+y"]),
+            (YankPop, vec!["alt+y"]),
+        ];
+        bindings.splice(2..2, editing);
+    }
+    bindings
+}
+
+/// Result of looking up the keys pressed so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyLookup {
+    Action(Action),
+    /// The keys start a longer sequence; wait for the next key.
+    Pending,
+    Unbound,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<KeyContext, Vec<(KeySequence, Action)>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_preset(KeyPreset::Default)
+    }
+}
+
+impl Keymap {
+    pub fn from_preset(preset: KeyPreset) -> Self {
+        let bindings = KeyContext::ALL
+            .into_iter()
+            .map(|context| {
+                let entries = preset_bindings(preset, context)
+                    .into_iter()
+                    .flat_map(|(action, specs)| {
+                        specs.into_iter().map(move |spec| {
+                            (parse_sequence(spec).expect("preset keys are valid"), action)
+                        })
+                    })
+                    .collect();
+                (context, entries)
+            })
+            .collect();
+        Self { bindings }
+    }
+
+    /// Starts from the configured preset and applies the per-context overrides.
+    /// An overridden action loses its preset keys, and keys bound in the config are
+    /// taken away from any other action of the same context, as long as that action
+    /// keeps at least one key.
+    pub fn from_config(config: &KeysConfig) -> Result<Self, String> {
+        let mut keymap = Self::from_preset(config.preset);
+        for context in KeyContext::ALL {
+            let section = context.config_section(config);
+            if section.is_empty() {
+                continue;
+            }
+            let allowed = context.actions();
+
+            let mut configured: Vec<(KeySequence, Action)> = Vec::new();
+            for (name, specs) in section {
+                let error = |e: String| format!("keys.{}.{}: {}", context.name(), name, e);
+                let action = Action::from_name(name)
+                    .filter(|a| allowed.contains(a))
+                    .ok_or_else(|| error("unknown action for this section".to_string()))?;
+                for spec in specs.as_slice() {
+                    let keys = parse_sequence(spec).map_err(error)?;
+                    if context.is_text_input() && keys.len() > 1 {
+                        return Err(error(format!("'{}': key sequences are not allowed here", spec)));
+                    }
+                    if let Some((_, other)) = configured.iter().find(|(seq, _)| conflicts(seq, &keys)) {
+                        return Err(error(format!("'{}' conflicts with the keys of '{}'", spec, other.name())));
+                    }
+                    configured.push((keys, action));
+                }
+            }
+
+            let entries = keymap.bindings.entry(context).or_default();
+            entries.retain(|(_, action)| !section.contains_key(action.name()));
+            for (keys, action) in &configured {
+                let taken: Vec<Action> = entries
+                    .iter()
+                    .filter(|(seq, _)| conflicts(seq, keys))
+                    .map(|(_, a)| *a)
+                    .collect();
+                entries.retain(|(seq, _)| !conflicts(seq, keys));
+                // Taking a key is fine while the other action keeps one of its own
+                if let Some(other) = taken.iter().find(|a| !entries.iter().any(|(_, b)| b == *a)) {
+                    return Err(format!(
+                        "keys.{}.{}: '{}' is the only key of '{}'; bind '{}' to another key too",
+                        context.name(),
+                        action.name(),
+                        format_sequence(keys),
+                        other.name(),
+                        other.name()
+                    ));
+                }
+            }
+            entries.extend(configured);
+        }
+        Ok(keymap)
+    }
+
+    fn lookup_in(&self, context: KeyContext, keys: &[KeyBinding]) -> KeyLookup {
+        let Some(entries) = self.bindings.get(&context) else {
+            return KeyLookup::Unbound;
+        };
+        if let Some((_, action)) = entries.iter().find(|(seq, _)| seq.as_slice() == keys) {
+            KeyLookup::Action(*action)
+        } else if entries.iter().any(|(seq, _)| seq.len() > keys.len() && seq.starts_with(keys)) {
+            KeyLookup::Pending
+        } else {
+            KeyLookup::Unbound
+        }
+    }
+
+    /// Looks up the keys pressed so far in `context`, then in the global bindings.
+    pub fn lookup(&self, context: KeyContext, keys: &[KeyBinding], include_global: bool) -> KeyLookup {
+        match self.lookup_in(context, keys) {
+            KeyLookup::Unbound if include_global => self.lookup_in(KeyContext::Global, keys),
+            found => found,
+        }
+    }
+
+    /// Keys bound to `action` in `context`, formatted for display.
+    pub fn keys_for(&self, context: KeyContext, action: Action) -> Vec<String> {
+        self.bindings
+            .get(&context)
+            .into_iter()
+            .flatten()
+            .filter(|(_, a)| *a == action)
+            .map(|(seq, _)| format_sequence(seq))
+            .collect()
+    }
+
+    /// Status bar hints like `j/down: Down | q: Quit`, skipping unbound actions.
+    pub fn hints(&self, context: KeyContext, actions: &[(Action, &str)]) -> String {
+        actions
+            .iter()
+            .filter_map(|(action, label)| {
+                let keys = self.keys_for(context, *action);
+                (!keys.is_empty()).then(|| format!("{}: {}", keys.join("/"), label))
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    /// Help screen entries per context: `(title, [(keys, description)])`.
+    pub fn help_sections(&self) -> Vec<(&'static str, Vec<(String, &'static str)>)> {
+        KeyContext::ALL
+            .into_iter()
+            .map(|context| {
+                let mut entries: Vec<(String, &'static str)> = context
+                    .actions()
+                    .into_iter()
+                    .filter_map(|action| {
+                        let keys = self.keys_for(context, action);
+                        (!keys.is_empty()).then(|| (keys.join(" / "), action.description()))
+                    })
+                    .collect();
+                if context.is_text_input() {
+                    entries.insert(0, ("Type".to_string(), "Enter Text"));
+                }
+                (context.title(), entries)
+            })
+            .collect()
+    }
+}
+
+/// Two sequences conflict if one is the same as or a prefix of the other.
+fn conflicts(a: &[KeyBinding], b: &[KeyBinding]) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keymap(toml: &str) -> Result<Keymap, String> {
+        Keymap::from_config(&toml::from_str::<KeysConfig>(toml).unwrap())
+    }
+
+    fn press(keymap: &Keymap, context: KeyContext, keys: &str) -> KeyLookup {
+        keymap.lookup(context, &parse_sequence(keys).unwrap(), true)
+    }
+
+    #[test]
+    fn parses_keys_and_sequences() {
+        assert_eq!(KeyBinding::parse("X").unwrap(), KeyBinding::new(KeyCode::Char('X'), KeyModifiers::SHIFT));
+        assert_eq!(KeyBinding::parse("ctrl++").unwrap(), KeyBinding::new(KeyCode::Char('+'), KeyModifiers::CONTROL));
+        assert_eq!(KeyBinding::parse("f13").unwrap_err(), "invalid key 'f13'");
+
+        let gg = parse_sequence("g  g").unwrap();
+        assert_eq!(gg, vec![KeyBinding::new(KeyCode::Char('g'), KeyModifiers::NONE); 2]);
+        assert_eq!(format_sequence(&gg), "gg");
+        assert_eq!(format_sequence(&parse_sequence("g enter").unwrap()), "g enter");
+        assert_eq!(parse_sequence(" ").unwrap_err(), "empty key");
+    }
+
+    #[test]
+    fn sequences_wait_for_the_next_key() {
+        let default = Keymap::default();
+        assert_eq!(press(&default, KeyContext::Models, "g"), KeyLookup::Pending);
+        assert_eq!(press(&default, KeyContext::Models, "g g"), KeyLookup::Action(Action::MoveTop));
+        assert_eq!(press(&default, KeyContext::Models, "g x"), KeyLookup::Unbound);
+        assert_eq!(press(&default, KeyContext::Models, "d"), KeyLookup::Action(Action::Delete));
+
+        let vim = Keymap::from_preset(KeyPreset::Vim);
+        assert_eq!(press(&vim, KeyContext::Models, "d"), KeyLookup::Pending);
+        assert_eq!(press(&vim, KeyContext::Models, "d d"), KeyLookup::Action(Action::Delete));
+        assert_eq!(press(&vim, KeyContext::Inventory, "d d"), KeyLookup::Action(Action::Delete));
+    }
+
+    #[test]
+    fn vim_preset_drops_the_arrow_keys() {
+        let vim = Keymap::from_preset(KeyPreset::Vim);
+        assert_eq!(press(&vim, KeyContext::Models, "j"), KeyLookup::Action(Action::MoveDown));
+        assert_eq!(press(&vim, KeyContext::Models, "down"), KeyLookup::Unbound);
+        assert_eq!(press(&vim, KeyContext::Models, "home"), KeyLookup::Unbound);
+        assert_eq!(press(&vim, KeyContext::Models, "h"), KeyLookup::Action(Action::ToggleHelp));
+        // Text input keeps its own keys in every preset
+        assert_eq!(press(&vim, KeyContext::Chat, "down"), KeyLookup::Action(Action::MoveDown));
+    }
+
+    #[test]
+    fn arrows_preset_drops_the_letters() {
+        let arrows = Keymap::from_preset(KeyPreset::Arrows);
+        assert_eq!(press(&arrows, KeyContext::Models, "down"), KeyLookup::Action(Action::MoveDown));
+        assert_eq!(press(&arrows, KeyContext::Models, "j"), KeyLookup::Unbound);
+        assert_eq!(press(&arrows, KeyContext::Models, "g"), KeyLookup::Unbound);
+        assert_eq!(press(&arrows, KeyContext::Models, "delete"), KeyLookup::Action(Action::Delete));
+        assert_eq!(press(&arrows, KeyContext::Models, "f1"), KeyLookup::Action(Action::ToggleHelp));
+        assert_eq!(press(&arrows, KeyContext::Help, "q"), KeyLookup::Unbound);
+        assert_eq!(arrows.keys_for(KeyContext::Downloads, Action::Back), ["esc", "D"]);
+    }
+
+    #[test]
+    fn binding_an_action_replaces_its_preset_keys() {
+        let keymap = keymap("[models]\nrun = [\"x\", \"ctrl+r\"]\n").unwrap();
+        assert_eq!(keymap.keys_for(KeyContext::Models, Action::Run), ["x", "ctrl+r"]);
+        assert_eq!(press(&keymap, KeyContext::Models, "r"), KeyLookup::Unbound);
+        // Other contexts keep their bindings
+        assert_eq!(keymap.keys_for(KeyContext::Downloads, Action::RetryDownload), ["r"]);
+    }
+
+    #[test]
+    fn a_taken_key_leaves_the_other_action_its_remaining_keys() {
+        let keymap = keymap("[models]\nchat = \"down\"\nrun = \"g\"\n").unwrap();
+        assert_eq!(press(&keymap, KeyContext::Models, "down"), KeyLookup::Action(Action::Chat));
+        assert_eq!(keymap.keys_for(KeyContext::Models, Action::MoveDown), ["j"]);
+        // "g" is a prefix of "g g", so top keeps only "home"
+        assert_eq!(press(&keymap, KeyContext::Models, "g"), KeyLookup::Action(Action::Run));
+        assert_eq!(keymap.keys_for(KeyContext::Models, Action::MoveTop), ["home"]);
+    }
+
+    #[test]
+    fn taking_the_last_key_of_an_action_is_an_error() {
+        assert_eq!(
+            keymap("[models]\nrun = \"L\"\n").unwrap_err(),
+            "keys.models.run: 'L' is the only key of 'load'; bind 'load' to another key too"
+        );
+        assert_eq!(
+            keymap("preset = \"vim\"\n[models]\nrun = \"g\"\n").unwrap_err(),
+            "keys.models.run: 'g' is the only key of 'top'; bind 'top' to another key too"
+        );
+        assert!(keymap("[models]\nrun = \"L\"\nload = \"l\"\n").is_ok());
+    }
+
+    #[test]
+    fn rejects_conflicting_and_invalid_bindings() {
+        let err = keymap("[models]\nrun = \"x\"\nchat = \"x y\"\n").unwrap_err();
+        assert!(err.contains("conflicts with the keys of"), "{}", err);
+        assert_eq!(
+            keymap("[models]\npull = \"x\"\n").unwrap_err(),
+            "keys.models.pull: unknown action for this section"
+        );
+        assert_eq!(
+            keymap("[filter]\nsubmit = \"a b\"\n").unwrap_err(),
+            "keys.filter.submit: 'a b': key sequences are not allowed here"
+        );
+        assert_eq!(
+            keymap("[global]\nhelp = \"hyper+h\"\n").unwrap_err(),
+            "keys.global.help: invalid key 'hyper+h': unknown modifier 'hyper'"
+        );
+    }
+}
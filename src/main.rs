@@ -1,19 +1,40 @@
 mod app;
+mod chat;
+mod cli;
+mod config;
+mod copy_dialog;
+mod derive;
+mod details;
+mod downloads;
 mod error;
 mod events;
+mod filter_query;
+mod fuzzy;
 mod handlers;
+mod inventory;
+mod keymap;
+mod model_table;
+mod modelfile;
 mod ollama_api;
+mod param_form;
+mod quantize;
 mod registry_api;
+mod show_cache;
 mod tasks;
+mod tensors;
+mod text_input;
+mod theme;
 mod tui;
 mod ui;
 
 use clap::Parser;
 use crate::{
-    app::{AppMode, AppState},
+    app::AppState,
+    config::Config,
     error::{AppError, Result},
     events::AppEvent,
     ollama_api::OllamaClient,
+    registry_api::RegistryClient,
 };
 
 use crossterm::{
@@ -27,34 +48,71 @@ use tokio::sync::mpsc;
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)] // Reads version from Cargo.toml
 struct CliArgs {
-    // No arguments needed yet, but the struct is required for clap
-    // The `version` attribute on `command` handles the --version flag
+    /// Run a non-interactive command instead of the TUI
+    #[command(subcommand)]
+    command: Option<cli::Command>,
+
+    /// Output format for subcommands
+    #[arg(long, value_enum, global = true, default_value_t = cli::OutputFormat::Table)]
+    format: cli::OutputFormat,
+
+    /// Shorthand for `--format json`
+    #[arg(long, global = true)]
+    json: bool,
 }
 
 // Synchronous main function
 fn main() -> Result<()> {
-    CliArgs::parse();
+    let args = CliArgs::parse();
+
+    // Variables from `.env` fill in what the real environment leaves unset
+    let _ = dotenvy::dotenv();
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
 
     let rt = tokio::runtime::Runtime::new().map_err(AppError::Io)?; // Map the std::io::Error to AppError::Io
-    rt.block_on(run_async_app())
+
+    if let Some(command) = args.command {
+        let format = if args.json { cli::OutputFormat::Json } else { args.format };
+        if let Err(err) = rt.block_on(cli::run(command, format, &config)) {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    rt.block_on(run_async_app(config))
 }
 
-async fn run_async_app() -> Result<()> {
-    let mut terminal = tui::init_terminal()?;
+async fn run_async_app(config: Config) -> Result<()> {
+    let mut terminal = tui::init_terminal(config.mouse)?;
 
     let result = async {
-        let ollama_host = ollama_api::get_ollama_host();
-        let client = OllamaClient::new(ollama_host.clone());
         let mut app_state = AppState::new();
-        run_app(&mut terminal, client, &mut app_state).await
+        app_state.apply_config(&config);
+        let client = OllamaClient::new(
+            app_state.hosts[app_state.active_host].url.clone(),
+            &config.timeouts,
+        );
+        let registry = RegistryClient::new(
+            registry_api::get_registry_url(config.registry_url.as_deref()),
+            &config.timeouts,
+        );
+        run_app(&mut terminal, client, registry, &mut app_state).await
     }.await;
 
     tui::restore_terminal(&mut terminal)?;
 
-    if let Err(err) = &result {
-         if !matches!(err, AppError::Io(_)) {
-            eprintln!("Error running app: {:?}", err);
-         }
+    if let Err(err) = &result
+        && !matches!(err, AppError::Io(_))
+    {
+        eprintln!("Error running app: {:?}", err);
     }
 
     result
@@ -63,19 +121,16 @@ async fn run_async_app() -> Result<()> {
 
 async fn run_app(
     terminal: &mut tui::Tui,
-    client: OllamaClient,
+    mut client: OllamaClient,
+    registry: RegistryClient,
     app: &mut AppState,
 ) -> Result<()> {
     let (tx, mut rx) = mpsc::channel::<AppEvent>(32);
 
     match client.list_models().await {
         Ok(models) => {
-            app.models = models;
-            if !app.models.is_empty() {
-                app.list_state.select(Some(0));
-                app.selected_model_details = None;
-                app.is_fetching_details = false;
-            }
+            app.set_models(models);
+            app.select_and_prepare_fetch(Some(0));
             // Initialize filtered_models to empty since no filter is active initially
             app.filtered_models.clear();
             app.is_filtered = false;
@@ -85,57 +140,68 @@ async fn run_app(
             app.status_message = Some(format!("Error loading models: {}", e));
         }
     }
+    handlers::start_details_prefetch(app, &client, &tx);
+
+    // Kept across iterations: dropping it when an app event wins the select
+    // would lose any key it has already read
+    let mut input_task: Option<tokio::task::JoinHandle<Result<Option<Event>>>> = None;
 
     loop {
         terminal.draw(|f| ui::draw(f, app))?;
 
         // Only trigger fetches if not running an external command
-        if app.current_mode != AppMode::RunningOllama {
-            if app.list_state.selected().is_some()
-                && app.selected_model_details.is_none()
-                && !app.is_fetching_details
-            {
-                if let Some(name) = app.get_selected_model_name() {
-                    app.is_fetching_details = true;
-                    app.status_message = Some("Fetching details...".to_string());
-
-                    let client_clone = client.clone();
-                    let tx_clone = tx.clone();
-                    let name_clone = name.clone();
-                    tokio::spawn(async move {
-                        tasks::fetch_model_details(client_clone, tx_clone, name_clone).await;
-                    });
-                }
-            }
+        if !app.current_mode.is_suspended() {
+            handlers::start_due_details_fetch(app, &client, &tx);
+        }
+
+        if !app.current_mode.is_suspended() && app.running_refresh_due() {
+            app.is_fetching_running = true;
+            let client_clone = client.clone();
+            let tx_clone = tx.clone();
+            tokio::spawn(async move {
+                tasks::fetch_running_models(client_clone, tx_clone).await;
+            });
         }
 
-        if app.current_mode == AppMode::RunningOllama {
+        if app.current_mode.is_suspended() {
             if let Some(event) = rx.recv().await {
                 match event {
                     AppEvent::OllamaRunCompleted(result) => {
                         if handlers::handle_ollama_run_completion(result, app, terminal)? {
-                             break Ok(());
+                            break Ok(());
                         }
                     }
-                    _ => {}
+                    AppEvent::ExternalEditClosed(result) => handlers::handle_external_edit_closed(result, app, terminal)?,
+                    // Background results keep arriving while the external program runs
+                    event => handlers::handle_app_event(event, app, &client, &registry, &tx),
                 }
             } else {
                 app.status_message = Some("Error: Event channel closed unexpectedly.".to_string());
                 break Ok(());
             }
         } else {
-            tokio::select! {
-                maybe_term_event_res = tokio::task::spawn_blocking(|| -> Result<Option<Event>> {
+            let input = input_task.get_or_insert_with(|| {
+                tokio::task::spawn_blocking(|| -> Result<Option<Event>> {
                     if crossterm::event::poll(Duration::from_millis(100)).map_err(AppError::Io)? {
                         let event = event::read().map_err(AppError::Io)?;
                         Ok(Some(event))
                     } else {
                         Ok(None)
                     }
-                }) => {
+                })
+            });
+            tokio::select! {
+                maybe_term_event_res = input => {
+                    input_task = None;
                     match maybe_term_event_res {
                         Ok(Ok(Some(Event::Key(key)))) => {
-                            if handlers::handle_key_event(key, app, &client, &tx).await? {
+                            if handlers::handle_key_event(key, app, &mut client, &registry, &tx).await? {
+                                app.should_quit = true;
+                            }
+                        }
+                        Ok(Ok(Some(Event::Mouse(mouse)))) => {
+                            let area = terminal.size()?;
+                            if handlers::handle_mouse_event(mouse, area, app, &mut client, &registry, &tx)? {
                                 app.should_quit = true;
                             }
                         }
@@ -153,7 +219,7 @@ async fn run_app(
 
                 maybe_app_event = rx.recv() => {
                     if let Some(event) = maybe_app_event {
-                        handlers::handle_app_event(event, app);
+                        handlers::handle_app_event(event, app, &client, &registry, &tx);
                     } else {
                         app.status_message = Some("Error: Event channel closed unexpectedly.".to_string());
                         break Ok(());
@@ -0,0 +1,177 @@
+// src/model_table.rs
+// Columns and sort order of the installed-models table.
+
+use crate::ollama_api::ModelInfo;
+use chrono::{DateTime, Local, Utc};
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelColumn {
+    Name,
+    Tag,
+    Size,
+    Parameters,
+    Quantization,
+    Family,
+    Modified,
+}
+
+impl ModelColumn {
+    pub const ALL: [ModelColumn; 7] = [
+        ModelColumn::Name,
+        ModelColumn::Tag,
+        ModelColumn::Size,
+        ModelColumn::Parameters,
+        ModelColumn::Quantization,
+        ModelColumn::Family,
+        ModelColumn::Modified,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            ModelColumn::Name => "Name",
+            ModelColumn::Tag => "Tag",
+            ModelColumn::Size => "Size",
+            ModelColumn::Parameters => "Params",
+            ModelColumn::Quantization => "Quant",
+            ModelColumn::Family => "Family",
+            ModelColumn::Modified => "Modified",
+        }
+    }
+
+    /// Sizes and dates start with the biggest / newest first; text columns A-Z.
+    pub fn default_descending(self) -> bool {
+        matches!(self, ModelColumn::Size | ModelColumn::Parameters | ModelColumn::Modified)
+    }
+
+    /// Cell text of this column for `model`. Unknown values are left empty.
+    pub fn cell(self, model: &ModelInfo, now: DateTime<Utc>) -> String {
+        let details = model.details.as_ref();
+        match self {
+            ModelColumn::Name => split_name_tag(&model.name).0.to_string(),
+            ModelColumn::Tag => split_name_tag(&model.name).1.to_string(),
+            ModelColumn::Size => model.size_formatted(),
+            ModelColumn::Parameters => details.and_then(|d| d.parameter_size.clone()).unwrap_or_default(),
+            ModelColumn::Quantization => details.and_then(|d| d.quantization_level.clone()).unwrap_or_default(),
+            ModelColumn::Family => details.and_then(|d| d.family.clone()).unwrap_or_default(),
+            ModelColumn::Modified => modified_time(model)
+                .map(|t| format_relative(t, now))
+                .unwrap_or_else(|| model.modified_at.clone()),
+        }
+    }
+
+    fn compare(self, a: &ModelInfo, b: &ModelInfo) -> Ordering {
+        match self {
+            ModelColumn::Name => split_name_tag(&a.name).0.cmp(split_name_tag(&b.name).0),
+            ModelColumn::Tag => split_name_tag(&a.name).1.cmp(split_name_tag(&b.name).1),
+            ModelColumn::Size => a.size.cmp(&b.size),
+            ModelColumn::Parameters => {
+                let count = |m: &ModelInfo| m.details.as_ref().and_then(|d| parse_parameter_count(d.parameter_size.as_deref()?));
+                count(a).partial_cmp(&count(b)).unwrap_or(Ordering::Equal)
+            }
+            ModelColumn::Quantization => {
+                let quant = |m: &ModelInfo| m.details.as_ref().and_then(|d| d.quantization_level.clone());
+                quant(a).cmp(&quant(b))
+            }
+            ModelColumn::Family => {
+                let family = |m: &ModelInfo| m.details.as_ref().and_then(|d| d.family.clone());
+                family(a).cmp(&family(b))
+            }
+            ModelColumn::Modified => modified_time(a).cmp(&modified_time(b)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelSort {
+    pub column: ModelColumn,
+    pub descending: bool,
+}
+
+impl Default for ModelSort {
+    fn default() -> Self {
+        Self { column: ModelColumn::Name, descending: false }
+    }
+}
+
+impl ModelSort {
+    /// Sorts by the column, then by full name so equal keys keep a stable order.
+    pub fn sort(&self, models: &mut [ModelInfo]) {
+        models.sort_by(|a, b| {
+            let primary = self.column.compare(a, b);
+            let primary = if self.descending { primary.reverse() } else { primary };
+            primary.then_with(|| a.name.cmp(&b.name))
+        });
+    }
+
+    /// Arrow shown next to the sorted column's header.
+    pub fn indicator(&self) -> &'static str {
+        if self.descending { "▼" } else { "▲" }
+    }
+}
+
+/// Splits `namespace/model:tag` into the model and the tag. A missing tag means `latest`.
+pub fn split_name_tag(name: &str) -> (&str, &str) {
+    match name.rsplit_once(':') {
+        // A colon before the last slash belongs to a registry port, not a tag
+        Some((model, tag)) if !tag.contains('/') => (model, tag),
+        _ => (name, "latest"),
+    }
+}
+
+/// Parses sizes like `8.0B`, `137M` or `1.5K` into a parameter count.
+pub fn parse_parameter_count(size: &str) -> Option<f64> {
+    let size = size.trim();
+    let (number, scale) = match size.chars().last()?.to_ascii_uppercase() {
+        'K' => (&size[..size.len() - 1], 1e3),
+        'M' => (&size[..size.len() - 1], 1e6),
+        'B' => (&size[..size.len() - 1], 1e9),
+        'T' => (&size[..size.len() - 1], 1e12),
+        _ => (size, 1.0),
+    };
+    number.parse::<f64>().ok().map(|n| n * scale)
+}
+
+/// Formats a parameter count the way Ollama does, e.g. `8.03B` or `494.03M`.
+pub fn format_parameter_count(count: u64) -> String {
+    let count = count as f64;
+    match count {
+        c if c >= 1e12 => format!("{:.2}T", c / 1e12),
+        c if c >= 1e9 => format!("{:.2}B", c / 1e9),
+        c if c >= 1e6 => format!("{:.2}M", c / 1e6),
+        c if c >= 1e3 => format!("{:.2}K", c / 1e3),
+        c => c.to_string(),
+    }
+}
+
+pub fn modified_time(model: &ModelInfo) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&model.modified_at)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Local date and time with the relative age, e.g. "2024-05-01 12:00 (3 days ago)".
+pub fn format_timestamp(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    format!("{} ({})", time.with_timezone(&Local).format("%Y-%m-%d %H:%M"), format_relative(time, now))
+}
+
+/// Formats a past time relative to `now`, e.g. "3 days ago".
+pub fn format_relative(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now - time;
+    let (count, unit) = if elapsed.num_minutes() < 1 {
+        return "just now".to_string();
+    } else if elapsed.num_hours() < 1 {
+        (elapsed.num_minutes(), "minute")
+    } else if elapsed.num_days() < 1 {
+        (elapsed.num_hours(), "hour")
+    } else if elapsed.num_days() < 30 {
+        (elapsed.num_days(), "day")
+    } else if elapsed.num_days() < 365 {
+        (elapsed.num_days() / 30, "month")
+    } else {
+        (elapsed.num_days() / 365, "year")
+    };
+    format!("{} {}{} ago", count, unit, if count == 1 { "" } else { "s" })
+}
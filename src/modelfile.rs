@@ -0,0 +1,307 @@
+// src/modelfile.rs
+// Parses a Modelfile into the fields of an `/api/create` request.
+
+use crate::ollama_api::{ChatMessage, CreateModelRequest};
+use serde_json::{Map, Value};
+
+/// A problem in a Modelfile; `line` counts from 1, `None` for the file as a whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelfileError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ModelfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "Line {}: {}", line, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Modelfile {
+    pub from: String,
+    pub template: Option<String>,
+    pub system: Option<String>,
+    pub license: Vec<String>,
+    pub parameters: Map<String, Value>,
+    pub messages: Vec<ChatMessage>,
+}
+
+impl Modelfile {
+    /// Parses every instruction, collecting all problems rather than stopping at the first.
+    pub fn parse(text: &str) -> Result<Self, Vec<ModelfileError>> {
+        let mut modelfile = Modelfile::default();
+        let mut errors = Vec::new();
+        let mut from = None;
+        let lines: Vec<&str> = text.lines().collect();
+        let mut index = 0;
+
+        while index < lines.len() {
+            let line_number = index + 1;
+            let line = lines[index].trim();
+            index += 1;
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim_start();
+            let mut error = |message: String| errors.push(ModelfileError { line: Some(line_number), message });
+
+            // Only these take a possibly quoted, multi-line argument
+            let command = command.to_ascii_uppercase();
+            let argument = match command.as_str() {
+                "TEMPLATE" | "SYSTEM" | "LICENSE" | "MESSAGE" | "PARAMETER" => {
+                    match read_argument(rest, &lines, &mut index) {
+                        Ok(argument) => argument,
+                        Err(message) => {
+                            error(message);
+                            continue;
+                        }
+                    }
+                }
+                _ => rest.to_string(),
+            };
+            if argument.is_empty() {
+                error(format!("{} needs an argument", command));
+                continue;
+            }
+
+            match command.as_str() {
+                "FROM" => {
+                    if is_file_path(&argument) {
+                        error("FROM must name a model; files would have to be uploaded first".to_string());
+                    } else {
+                        from = Some(argument);
+                    }
+                }
+                "TEMPLATE" => modelfile.template = Some(argument),
+                "SYSTEM" => modelfile.system = Some(argument),
+                "LICENSE" => modelfile.license.push(argument),
+                "PARAMETER" => {
+                    let (key, value) = argument.split_once(char::is_whitespace).unwrap_or((&argument, ""));
+                    let value = unquote(value.trim());
+                    if value.is_empty() {
+                        error(format!("PARAMETER {} needs a value", key));
+                        continue;
+                    }
+                    let key = key.to_ascii_lowercase();
+                    // `stop` may be given several times and is sent as a list
+                    if key == "stop" {
+                        let stops = modelfile.parameters.entry("stop").or_insert_with(|| Value::Array(Vec::new()));
+                        if let Value::Array(stops) = stops {
+                            stops.push(Value::String(value));
+                        }
+                    } else {
+                        modelfile.parameters.insert(key, parameter_value(&value));
+                    }
+                }
+                "MESSAGE" => {
+                    let (role, content) = argument.split_once(char::is_whitespace).unwrap_or((&argument, ""));
+                    let role = role.to_ascii_lowercase();
+                    if !matches!(role.as_str(), "system" | "user" | "assistant") {
+                        error(format!("MESSAGE role must be system, user or assistant, not '{}'", role));
+                        continue;
+                    }
+                    modelfile.messages.push(ChatMessage { role, content: unquote(content.trim()) });
+                }
+                "ADAPTER" => error("ADAPTER isn't supported: adapters have to be uploaded as files".to_string()),
+                _ => error(format!("Unknown instruction '{}'", command)),
+            }
+        }
+
+        match from {
+            Some(from) => modelfile.from = from,
+            None if errors.is_empty() => errors.push(ModelfileError {
+                line: None,
+                message: "FROM is missing: name the model to build on".to_string(),
+            }),
+            None => {}
+        }
+        if errors.is_empty() { Ok(modelfile) } else { Err(errors) }
+    }
+
+    pub fn into_request(self, model: String) -> CreateModelRequest {
+        CreateModelRequest {
+            model,
+            from: self.from,
+            template: self.template,
+            system: self.system,
+            license: self.license,
+            parameters: self.parameters,
+            messages: self.messages,
+            quantize: None,
+            stream: true,
+        }
+    }
+}
+
+/// The Modelfile from `/api/show` with its `FROM` blob path replaced by the model's name,
+/// so it can be submitted as is.
+pub fn derived_modelfile(modelfile: Option<&str>, source: &str) -> String {
+    let Some(modelfile) = modelfile else {
+        return format!("FROM {}\n", source);
+    };
+    let mut replaced = false;
+    let lines: Vec<String> = modelfile
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let is_from = trimmed.get(..4).is_some_and(|command| command.eq_ignore_ascii_case("FROM"))
+                && trimmed[4..].starts_with(char::is_whitespace);
+            if is_from && !replaced {
+                replaced = true;
+                format!("FROM {}", source)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    let mut text = lines.join("\n");
+    if !replaced {
+        text = format!("FROM {}\n{}", source, text);
+    }
+    text.push('\n');
+    text
+}
+
+/// Reads an argument that may be `"quoted"` or span lines in `"""triple quotes"""`.
+/// Advances `index` past any continuation lines.
+fn read_argument(rest: &str, lines: &[&str], index: &mut usize) -> Result<String, String> {
+    // `PARAMETER stop """..."""` or `MESSAGE user """..."""`: keep the leading word
+    let (prefix, value) = match rest.find("\"\"\"") {
+        Some(start) if !rest[..start].contains('"') => (&rest[..start], &rest[start + 3..]),
+        _ => return Ok(unquote(rest)),
+    };
+    if let Some(end) = value.find("\"\"\"") {
+        return Ok(format!("{}{}", prefix, &value[..end]));
+    }
+    let mut collected = value.to_string();
+    while *index < lines.len() {
+        let line = lines[*index];
+        *index += 1;
+        collected.push('\n');
+        if let Some(end) = line.find("\"\"\"") {
+            collected.push_str(&line[..end]);
+            return Ok(format!("{}{}", prefix, collected));
+        }
+        collected.push_str(line);
+    }
+    Err("Unterminated \"\"\"".to_string())
+}
+
+/// Strips one pair of surrounding double quotes, unescaping `\"` inside.
+pub fn unquote(value: &str) -> String {
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(inner) => inner.replace("\\\"", "\""),
+        None => value.to_string(),
+    }
+}
+
+/// Numbers and booleans are sent typed; everything else as a string.
+fn parameter_value(value: &str) -> Value {
+    if let Ok(int) = value.parse::<i64>() {
+        return Value::from(int);
+    }
+    if let Ok(float) = value.parse::<f64>() {
+        return Value::from(float);
+    }
+    match value {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::String(value.to_string()),
+    }
+}
+
+fn is_file_path(value: &str) -> bool {
+    value.starts_with('/') || value.starts_with("./") || value.starts_with("../") || value.starts_with('~')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Each error as it is shown in the editor.
+    fn errors(text: &str) -> Vec<String> {
+        Modelfile::parse(text).unwrap_err().iter().map(ToString::to_string).collect()
+    }
+
+    const DERIVED: &str = r#"# comment
+from llama3:8b
+PARAMETER temperature 0.7
+PARAMETER num_ctx 4096
+PARAMETER stop "<|eot|>"
+PARAMETER stop user:
+PARAMETER penalize_newline false
+SYSTEM "Be brief."
+TEMPLATE """{{ .System }}
+{{ .Prompt }}"""
+MESSAGE user """Hi
+there"""
+MESSAGE assistant Hello!
+LICENSE MIT
+"#;
+
+    #[test]
+    fn parses_every_instruction() {
+        let request = Modelfile::parse(DERIVED).unwrap().into_request("brief:latest".to_string());
+        assert_eq!(request.model, "brief:latest");
+        assert_eq!(request.from, "llama3:8b");
+        assert_eq!(request.system.as_deref(), Some("Be brief."));
+        assert_eq!(request.template.as_deref(), Some("{{ .System }}\n{{ .Prompt }}"));
+        assert_eq!(request.license, ["MIT"]);
+        let messages: Vec<(&str, &str)> = request.messages.iter().map(|m| (m.role.as_str(), m.content.as_str())).collect();
+        assert_eq!(messages, [("user", "Hi\nthere"), ("assistant", "Hello!")]);
+
+        // Numbers and booleans are typed, and `stop` collects every value
+        let parameters = Value::Object(request.parameters).to_string();
+        assert_eq!(
+            parameters,
+            r#"{"num_ctx":4096,"penalize_newline":false,"stop":["<|eot|>","user:"],"temperature":0.7}"#
+        );
+    }
+
+    #[test]
+    fn reports_every_error_with_its_line() {
+        let text = "FROM ./model.gguf\nPARAMETER temperature\nMESSAGE tool hi\nADAPTER ./lora\nRUN x\nSYSTEM\n";
+        assert_eq!(
+            errors(text),
+            [
+                "Line 1: FROM must name a model; files would have to be uploaded first",
+                "Line 2: PARAMETER temperature needs a value",
+                "Line 3: MESSAGE role must be system, user or assistant, not 'tool'",
+                "Line 4: ADAPTER isn't supported: adapters have to be uploaded as files",
+                "Line 5: Unknown instruction 'RUN'",
+                "Line 6: SYSTEM needs an argument",
+            ]
+        );
+    }
+
+    #[test]
+    fn line_numbers_count_multi_line_arguments() {
+        let text = "FROM llama3\nTEMPLATE \"\"\"a\nb\n\"\"\"\nBOGUS x\nSYSTEM \"\"\"never closed\nmore";
+        assert_eq!(errors(text), ["Line 5: Unknown instruction 'BOGUS'", "Line 6: Unterminated \"\"\""]);
+    }
+
+    #[test]
+    fn from_is_required() {
+        let missing = Modelfile::parse("# nothing yet\nSYSTEM hi\n").unwrap_err();
+        assert_eq!(missing, [ModelfileError { line: None, message: "FROM is missing: name the model to build on".into() }]);
+        // Not repeated when the file has other problems
+        assert_eq!(errors("SYSTEM\n"), ["Line 1: SYSTEM needs an argument"]);
+    }
+
+    #[test]
+    fn derived_modelfile_names_the_source() {
+        let shown = "# Modelfile generated by \"ollama show\"\nFROM /root/.ollama/blobs/sha256-abc\nPARAMETER stop x";
+        assert_eq!(
+            derived_modelfile(Some(shown), "llama3:8b"),
+            "# Modelfile generated by \"ollama show\"\nFROM llama3:8b\nPARAMETER stop x\n"
+        );
+        assert_eq!(derived_modelfile(Some("SYSTEM hi"), "llama3"), "FROM llama3\nSYSTEM hi\n");
+        assert_eq!(derived_modelfile(None, "llama3"), "FROM llama3\n");
+        assert_eq!(unquote(r#""say \"hi\"""#), r#"say "hi""#);
+    }
+}
@@ -1,13 +1,23 @@
 // src/ollama_api.rs
 // Handles interactions with the Ollama REST API.
 
+use crate::config::Timeouts;
 use crate::error::ApiError;
+use chrono::{DateTime, Utc};
 use humansize::{format_size, BINARY};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::env;
+use std::marker::PhantomData;
+use std::time::Duration;
 
 const DEFAULT_OLLAMA_HOST: &str = "http://localhost:11434";
 
+/// `keep_alive` value that unloads a model immediately.
+pub const KEEP_ALIVE_UNLOAD: i64 = 0;
+/// `keep_alive` value that keeps a model loaded until it is explicitly unloaded.
+pub const KEEP_ALIVE_FOREVER: i64 = -1;
+
 // Structs matching Ollama API responses
 
 #[derive(Deserialize, Debug, Clone)]
@@ -15,16 +25,15 @@ pub struct ListTagsResponse {
     pub models: Vec<ModelInfo>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ModelInfo {
     pub name: String,
     pub modified_at: String,
     pub size: u64,
     pub digest: String,
-    // family: Option<String>, // Not present in /api/tags according to docs
-    // format: Option<String>, // Not present in /api/tags
-    // parameter_size: Option<String>, // Not present in /api/tags
-    // quantization_level: Option<String>, // Not present in /api/tags
+    /// Family, parameter size and quantization; missing on very old servers.
+    #[serde(default)]
+    pub details: Option<ModelExtraDetails>,
 }
 
 impl ModelInfo {
@@ -35,51 +44,407 @@ impl ModelInfo {
 }
 
 
+#[derive(Deserialize, Debug, Clone)]
+pub struct ListRunningResponse {
+    pub models: Vec<RunningModel>,
+}
+
+/// A model currently loaded into memory, as reported by `/api/ps`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunningModel {
+    pub name: String,
+    pub size: u64,
+    #[serde(default)]
+    pub size_vram: u64,
+    pub expires_at: Option<String>,
+    pub context_length: Option<u64>,
+}
+
+impl RunningModel {
+    pub fn size_formatted(&self) -> String {
+        format_size(self.size, BINARY)
+    }
+
+    /// Describes how the model is split between CPU and GPU memory, like `ollama ps`.
+    pub fn processor_split(&self) -> String {
+        if self.size == 0 || self.size_vram == 0 {
+            return "100% CPU".to_string();
+        }
+        if self.size_vram >= self.size {
+            return "100% GPU".to_string();
+        }
+        let gpu = (self.size_vram as f64 / self.size as f64 * 100.0).round() as u64;
+        format!("{}%/{}% CPU/GPU", 100 - gpu, gpu)
+    }
+
+    /// Time left until the model is unloaded. `None` if unknown or already expired.
+    pub fn expires_in(&self) -> Option<chrono::Duration> {
+        let expires_at = chrono::DateTime::parse_from_rfc3339(self.expires_at.as_deref()?).ok()?;
+        let remaining = expires_at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+        (remaining > chrono::Duration::zero()).then_some(remaining)
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct ShowModelRequest {
     pub name: String,
+    /// Adds tensors and the full tokenizer arrays to the response.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub verbose: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ShowModelResponse {
     pub license: Option<String>,
     pub modelfile: Option<String>,
     pub parameters: Option<String>,
     pub template: Option<String>,
     pub details: Option<ModelExtraDetails>,
+    /// Empty on older servers.
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+    pub model_info: Option<ModelMetadata>,
+    pub modified_at: Option<DateTime<Utc>>,
+    /// Only filled in by verbose requests.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tensors: Vec<TensorInfo>,
+    /// Fields not known to this client, e.g. `projector_info`; kept so JSON output passes them on.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+/// One tensor of the model file, from a verbose `/api/show`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TensorInfo {
+    /// e.g. `blk.0.attn_q.weight`.
+    pub name: String,
+    /// GGML type, e.g. `F32`, `Q4_K` or `Q6_K`.
+    #[serde(rename = "type")]
+    pub tensor_type: String,
+    #[serde(default)]
+    pub shape: Vec<u64>,
+}
+
+impl TensorInfo {
+    /// Number of weights: the product of the dimensions.
+    pub fn elements(&self) -> u64 {
+        self.shape.iter().product()
+    }
+}
+
+/// A feature reported by `/api/show`. Names added by newer servers are kept as `Other`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Capability {
+    Completion,
+    Vision,
+    Tools,
+    Insert,
+    Embedding,
+    Thinking,
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl Capability {
+    pub fn name(&self) -> &str {
+        match self {
+            Capability::Completion => "completion",
+            Capability::Vision => "vision",
+            Capability::Tools => "tools",
+            Capability::Insert => "insert",
+            Capability::Embedding => "embedding",
+            Capability::Thinking => "thinking",
+            Capability::Other(name) => name,
+        }
+    }
+
+    /// Short label for the model list. Plain completion is the norm, so it gets none.
+    pub fn badge(&self) -> Option<&str> {
+        match self {
+            Capability::Completion => None,
+            Capability::Vision => Some("vision"),
+            Capability::Tools => Some("tools"),
+            Capability::Insert => Some("fim"),
+            Capability::Embedding => Some("embed"),
+            Capability::Thinking => Some("think"),
+            Capability::Other(name) => Some(name),
+        }
+    }
+}
+
+/// GGUF metadata (`model_info`). Keys are prefixed with the architecture, e.g. `llama.context_length`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(from = "Map<String, Value>", into = "Map<String, Value>")]
+pub struct ModelMetadata {
+    pub architecture: Option<String>,
+    pub parameter_count: Option<u64>,
+    pub context_length: Option<u64>,
+    pub embedding_length: Option<u64>,
+    /// Number of layers.
+    pub block_count: Option<u64>,
+    pub head_count: Option<u64>,
+    pub head_count_kv: Option<u64>,
+    pub vocab_size: Option<u64>,
+    /// Every other key, such as tokenizer and rope settings.
+    pub other: Map<String, Value>,
+}
+
+/// Architecture-specific keys of `ModelMetadata`, without the architecture prefix.
+const ARCHITECTURE_KEYS: [&str; 6] = [
+    "context_length",
+    "embedding_length",
+    "block_count",
+    "attention.head_count",
+    "attention.head_count_kv",
+    "vocab_size",
+];
+
+impl ModelMetadata {
+    fn architecture_fields(&mut self) -> [&mut Option<u64>; 6] {
+        [
+            &mut self.context_length,
+            &mut self.embedding_length,
+            &mut self.block_count,
+            &mut self.head_count,
+            &mut self.head_count_kv,
+            &mut self.vocab_size,
+        ]
+    }
+}
+
+impl From<Map<String, Value>> for ModelMetadata {
+    fn from(mut other: Map<String, Value>) -> Self {
+        // Only plain numbers are typed; e.g. per-layer head count arrays stay in `other`
+        let mut take_u64 = |key: &str| {
+            let number = other.get(key)?.as_u64()?;
+            other.remove(key);
+            Some(number)
+        };
+        let parameter_count = take_u64("general.parameter_count");
+        let architecture = other.get("general.architecture").and_then(Value::as_str).map(String::from);
+        if architecture.is_some() {
+            other.remove("general.architecture");
+        }
+        let mut metadata = Self { architecture, parameter_count, ..Self::default() };
+        if let Some(prefix) = metadata.architecture.clone() {
+            for (key, field) in ARCHITECTURE_KEYS.iter().zip(metadata.architecture_fields()) {
+                let key = format!("{}.{}", prefix, key);
+                if let Some(number) = other.get(&key).and_then(Value::as_u64) {
+                    other.remove(&key);
+                    *field = Some(number);
+                }
+            }
+        }
+        metadata.other = other;
+        metadata
+    }
+}
+
+impl From<ModelMetadata> for Map<String, Value> {
+    fn from(mut metadata: ModelMetadata) -> Self {
+        let mut map = std::mem::take(&mut metadata.other);
+        if let Some(count) = metadata.parameter_count {
+            map.insert("general.parameter_count".to_string(), count.into());
+        }
+        if let Some(prefix) = metadata.architecture.clone() {
+            for (key, field) in ARCHITECTURE_KEYS.iter().zip(metadata.architecture_fields()) {
+                if let Some(number) = *field {
+                    map.insert(format!("{}.{}", prefix, key), number.into());
+                }
+            }
+            map.insert("general.architecture".to_string(), prefix.into());
+        }
+        map
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ModelExtraDetails {
     pub format: Option<String>,
     pub family: Option<String>,
     pub families: Option<Vec<String>>,
     pub parameter_size: Option<String>,
     pub quantization_level: Option<String>,
-    // Added based on potential API output, adjust as needed
-    pub parent_model: Option<String>,
-    pub general: Option<GeneralDetails>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
-pub struct GeneralDetails {
-   pub architecture: Option<String>,
-   pub file_type: Option<u32>, // Example, adjust type if needed
-   pub quantization_version: Option<u32>, // Example, adjust type if needed
-   // Add other general fields if present
+
+#[derive(Serialize, Debug)]
+pub struct DeleteModelRequest {
+    pub name: String,
 }
 
+#[derive(Serialize, Debug)]
+pub struct CopyModelRequest {
+    pub source: String,
+    pub destination: String,
+}
 
+/// An empty-prompt generate request, used only to load or unload a model.
 #[derive(Serialize, Debug)]
-pub struct DeleteModelRequest {
+pub struct GenerateRequest {
+    pub model: String,
+    pub prompt: String,
+    /// Seconds to keep the model loaded; `None` uses the server default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<i64>,
+    pub stream: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PullModelRequest {
     pub name: String,
+    pub stream: bool,
+}
+
+/// A single progress line streamed by `/api/pull`.
+/// `digest`, `total` and `completed` are only present while a layer is downloading.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PullProgress {
+    pub status: String,
+    pub digest: Option<String>,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+}
+
+/// Body of `/api/create`: a new model built on `from`, with the other Modelfile instructions as fields.
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateModelRequest {
+    pub model: String,
+    pub from: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub license: Vec<String>,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub parameters: Map<String, Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<ChatMessage>,
+    /// Quantization type for an F16/F32 `from` model, e.g. `q4_K_M`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantize: Option<String>,
+    pub stream: bool,
+}
+
+/// A status line streamed by `/api/create`, e.g. "using existing layer sha256:...".
+/// Quantizing also reports how much of the weights are done.
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreateProgress {
+    pub status: String,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub stream: bool,
+}
+
+/// A single chunk streamed by `/api/chat`; `message.content` holds the next tokens.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ChatResponseChunk {
+    pub message: Option<ChatMessage>,
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// Error object Ollama emits in place of a regular line when a stream fails.
+#[derive(Deserialize, Debug)]
+struct StreamError {
+    error: String,
+}
+
+/// Reads newline-delimited JSON objects from a streaming Ollama response.
+pub struct NdjsonStream<T> {
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+    done: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> NdjsonStream<T> {
+    fn new(response: reqwest::Response) -> Self {
+        Self {
+            response,
+            buffer: Vec::new(),
+            done: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the next decoded object, or `None` once the stream is exhausted.
+    pub async fn next(&mut self) -> Option<Result<T, ApiError>> {
+        loop {
+            if let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+                return Some(parse_stream_line(&line));
+            }
+
+            if self.done {
+                if self.buffer.trim_ascii().is_empty() {
+                    return None;
+                }
+                let line = std::mem::take(&mut self.buffer);
+                return Some(parse_stream_line(&line));
+            }
+
+            match self.response.chunk().await {
+                Ok(Some(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Ok(None) => self.done = true,
+                Err(e) => {
+                    self.done = true;
+                    self.buffer.clear();
+                    return Some(Err(ApiError::Reqwest(e)));
+                }
+            }
+        }
+    }
+}
+
+fn parse_stream_line<T: DeserializeOwned>(line: &[u8]) -> Result<T, ApiError> {
+    if let Ok(err) = serde_json::from_slice::<StreamError>(line) {
+        return Err(ApiError::ResponseError(err.error));
+    }
+    Ok(serde_json::from_slice(line)?)
+}
+
+/// Turns a non-2xx response into an `ApiError` carrying the response body.
+async fn check_status(res: reqwest::Response) -> Result<reqwest::Response, ApiError> {
+    if res.status().is_success() {
+        return Ok(res);
+    }
+    Err(ApiError::ResponseError(format!(
+        "API Error: {} - {}",
+        res.status(),
+        res.text().await.unwrap_or_else(|_| "Unknown error".to_string())
+    )))
 }
 
 // --- API Client Functions ---
 
 pub fn get_ollama_host() -> String {
-    // Consider using dotenvy here if needed
-    env::var("OLLAMA_HOST").unwrap_or_else(|_| DEFAULT_OLLAMA_HOST.to_string())
+    env_ollama_host().unwrap_or_else(|| DEFAULT_OLLAMA_HOST.to_string())
+}
+
+/// The `OLLAMA_HOST` environment variable without a trailing slash, if set.
+pub fn env_ollama_host() -> Option<String> {
+    env::var("OLLAMA_HOST")
+        .ok()
+        .filter(|h| !h.trim().is_empty())
+        .map(|h| h.trim_end_matches('/').to_string())
 }
 
 // Placeholder for the actual client implementation
@@ -87,19 +452,39 @@ pub fn get_ollama_host() -> String {
 pub struct OllamaClient {
     client: reqwest::Client,
     host: String,
+    /// Applied to non-streaming requests only.
+    request_timeout: Duration,
 }
 
 impl OllamaClient {
-    pub fn new(host: String) -> Self {
+    pub fn new(host: String, timeouts: &Timeouts) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(timeouts.connect())
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            client,
+            host,
+            request_timeout: timeouts.request(),
+        }
+    }
+
+    /// A client for another host that shares this client's connection pool and timeouts.
+    pub fn for_host(&self, host: String) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: self.client.clone(),
             host,
+            request_timeout: self.request_timeout,
         }
     }
 
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
     pub async fn list_models(&self) -> Result<Vec<ModelInfo>, ApiError> {
         let url = format!("{}/api/tags", self.host);
-        let res = self.client.get(&url).send().await?;
+        let res = self.client.get(&url).timeout(self.request_timeout).send().await?;
 
         if !res.status().is_success() {
             return Err(ApiError::ResponseError(format!(
@@ -114,9 +499,18 @@ impl OllamaClient {
     }
 
     pub async fn show_model_details(&self, name: &str) -> Result<ShowModelResponse, ApiError> {
+        self.show(name, false).await
+    }
+
+    /// Like `show_model_details`, but includes the tensors. The response can be several megabytes.
+    pub async fn show_model_details_verbose(&self, name: &str) -> Result<ShowModelResponse, ApiError> {
+        self.show(name, true).await
+    }
+
+    async fn show(&self, name: &str, verbose: bool) -> Result<ShowModelResponse, ApiError> {
         let url = format!("{}/api/show", self.host);
-        let request_body = ShowModelRequest { name: name.to_string() };
-        let res = self.client.post(&url).json(&request_body).send().await?;
+        let request_body = ShowModelRequest { name: name.to_string(), verbose };
+        let res = self.client.post(&url).json(&request_body).timeout(self.request_timeout).send().await?;
 
         if !res.status().is_success() {
             return Err(ApiError::ResponseError(format!(
@@ -133,7 +527,7 @@ impl OllamaClient {
     pub async fn delete_model(&self, name: &str) -> Result<(), ApiError> {
         let url = format!("{}/api/delete", self.host);
          let request_body = DeleteModelRequest { name: name.to_string() };
-        let res = self.client.delete(&url).json(&request_body).send().await?; // Changed to DELETE
+        let res = self.client.delete(&url).json(&request_body).timeout(self.request_timeout).send().await?; // Changed to DELETE
 
         if !res.status().is_success() {
              return Err(ApiError::ResponseError(format!(
@@ -145,4 +539,128 @@ impl OllamaClient {
         // Check for specific success status if needed, otherwise assume 2xx is OK
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Creates `destination` as another name for `source`; the model's blobs are shared, not duplicated.
+    pub async fn copy_model(&self, source: &str, destination: &str) -> Result<(), ApiError> {
+        let url = format!("{}/api/copy", self.host);
+        let request_body = CopyModelRequest { source: source.to_string(), destination: destination.to_string() };
+        let res = self.client.post(&url).json(&request_body).timeout(self.request_timeout).send().await?;
+        check_status(res).await?;
+        Ok(())
+    }
+
+    /// Starts pulling a model and returns the stream of progress updates.
+    /// Dropping the stream aborts the download.
+    pub async fn pull_model(&self, name: &str) -> Result<NdjsonStream<PullProgress>, ApiError> {
+        let url = format!("{}/api/pull", self.host);
+        let request_body = PullModelRequest { name: name.to_string(), stream: true };
+        let res = self.client.post(&url).json(&request_body).send().await?;
+        let res = check_status(res).await?;
+        Ok(NdjsonStream::new(res))
+    }
+
+    /// Starts creating a model and returns the stream of status updates.
+    pub async fn create_model(&self, request: &CreateModelRequest) -> Result<NdjsonStream<CreateProgress>, ApiError> {
+        let url = format!("{}/api/create", self.host);
+        let res = self.client.post(&url).json(request).send().await?;
+        let res = check_status(res).await?;
+        Ok(NdjsonStream::new(res))
+    }
+
+    /// Sends the conversation to `/api/chat` and returns the stream of response chunks.
+    pub async fn chat(
+        &self,
+        model: &str,
+        messages: Vec<ChatMessage>,
+    ) -> Result<NdjsonStream<ChatResponseChunk>, ApiError> {
+        let url = format!("{}/api/chat", self.host);
+        let request_body = ChatRequest { model: model.to_string(), messages, stream: true };
+        let res = self.client.post(&url).json(&request_body).send().await?;
+        let res = check_status(res).await?;
+        Ok(NdjsonStream::new(res))
+    }
+
+    /// Lists the models currently loaded into memory (`/api/ps`).
+    pub async fn list_running(&self) -> Result<Vec<RunningModel>, ApiError> {
+        let url = format!("{}/api/ps", self.host);
+        let res = self.client.get(&url).timeout(self.request_timeout).send().await?;
+        let res = check_status(res).await?;
+        let body: ListRunningResponse = res.json().await?;
+        Ok(body.models)
+    }
+
+    /// Loads a model into memory (or unloads it with `KEEP_ALIVE_UNLOAD`) by sending
+    /// an empty prompt with the given `keep_alive`.
+    pub async fn set_keep_alive(&self, name: &str, keep_alive: Option<i64>) -> Result<(), ApiError> {
+        let url = format!("{}/api/generate", self.host);
+        let request_body = GenerateRequest {
+            model: name.to_string(),
+            prompt: String::new(),
+            keep_alive,
+            stream: false,
+        };
+        let res = self.client.post(&url).json(&request_body).send().await?;
+        check_status(res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn model_info_keeps_unknown_keys() {
+        let raw = json!({
+            "general.architecture": "llama",
+            "general.parameter_count": 8030261248u64,
+            "general.quantization_version": 2,
+            "llama.context_length": 8192,
+            "llama.attention.head_count": 32,
+            // Per-layer head counts are arrays, which stay untyped
+            "llama.attention.head_count_kv": [8, 8, 4],
+            "llama.rope.freq_base": 500000.0,
+            "tokenizer.ggml.model": "gpt2",
+        });
+        let metadata: ModelMetadata = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(metadata.architecture.as_deref(), Some("llama"));
+        assert_eq!(metadata.parameter_count, Some(8030261248));
+        assert_eq!(metadata.context_length, Some(8192));
+        assert_eq!(metadata.head_count, Some(32));
+        assert_eq!(metadata.head_count_kv, None);
+        assert_eq!(
+            metadata.other.keys().collect::<Vec<_>>(),
+            [
+                "general.quantization_version",
+                "llama.attention.head_count_kv",
+                "llama.rope.freq_base",
+                "tokenizer.ggml.model",
+            ]
+        );
+
+        assert_eq!(serde_json::to_value(&metadata).unwrap(), raw);
+    }
+
+    #[test]
+    fn show_response_types_modified_at_and_keeps_unknown_fields() {
+        let raw = json!({
+            "modelfile": "FROM llama3",
+            "capabilities": ["completion", "vision"],
+            "modified_at": "2024-05-01T03:00:00.123456789-07:00",
+            "projector_info": { "clip.has_vision_encoder": true },
+        });
+        let show: ShowModelResponse = serde_json::from_value(raw).unwrap();
+        let expected = Utc.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap() + chrono::Duration::nanoseconds(123456789);
+        assert_eq!(show.modified_at, Some(expected));
+        assert_eq!(show.other.keys().collect::<Vec<_>>(), ["projector_info"]);
+
+        let json = serde_json::to_value(&show).unwrap();
+        assert_eq!(json["modified_at"], "2024-05-01T10:00:00.123456789Z");
+        assert_eq!(json["projector_info"]["clip.has_vision_encoder"], true);
+
+        let show: ShowModelResponse = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(show.modified_at, None);
+    }
+}
@@ -0,0 +1,439 @@
+// src/param_form.rs
+// Form over the sampling and runtime parameters of a model, creating a derived model with the changes.
+
+use crate::copy_dialog::normalize_model_name;
+use crate::modelfile::unquote;
+use crate::ollama_api::{CreateModelRequest, ModelInfo};
+use crate::text_input::TextInput;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamKind {
+    Int { min: i64, max: i64 },
+    Float { min: f64, max: f64 },
+    /// Strings that end generation, each one sent as an item of a list.
+    StopList,
+}
+
+/// A parameter the form offers, with the range Ollama documents for it.
+#[derive(Debug, Clone, Copy)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub kind: ParamKind,
+    pub description: &'static str,
+}
+
+impl ParamSpec {
+    const fn int(name: &'static str, min: i64, max: i64, description: &'static str) -> Self {
+        Self { name, kind: ParamKind::Int { min, max }, description }
+    }
+
+    const fn float(name: &'static str, min: f64, max: f64, description: &'static str) -> Self {
+        Self { name, kind: ParamKind::Float { min, max }, description }
+    }
+
+    /// Accepted values, e.g. "0 to 2" or "-1 or more".
+    pub fn range(&self) -> String {
+        fn bounds(min: String, max: String, unbounded_min: bool, unbounded_max: bool) -> String {
+            match (unbounded_min, unbounded_max) {
+                (true, true) => "any".to_string(),
+                (false, true) => format!("{} or more", min),
+                (true, false) => format!("{} or less", max),
+                (false, false) => format!("{} to {}", min, max),
+            }
+        }
+        match self.kind {
+            ParamKind::Int { min, max } => bounds(min.to_string(), max.to_string(), min == i64::MIN, max == i64::MAX),
+            ParamKind::Float { min, max } => bounds(min.to_string(), max.to_string(), min.is_infinite(), max.is_infinite()),
+            ParamKind::StopList => "\"quoted\" strings".to_string(),
+        }
+    }
+
+    /// The value to send for `text`, `None` if it's empty, or why it isn't accepted.
+    pub fn parse(&self, text: &str) -> Result<Option<Value>, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let value = match self.kind {
+            ParamKind::Int { min, max } => {
+                let int: i64 = text.parse().map_err(|_| format!("{} takes a whole number", self.name))?;
+                if int < min || int > max {
+                    return Err(format!("{} must be {}", self.name, self.range()));
+                }
+                Value::from(int)
+            }
+            ParamKind::Float { min, max } => {
+                let float: f64 = text.parse().map_err(|_| format!("{} takes a number", self.name))?;
+                if !float.is_finite() || float < min || float > max {
+                    return Err(format!("{} must be {}", self.name, self.range()));
+                }
+                Value::from(float)
+            }
+            ParamKind::StopList => {
+                let stops = split_stops(text)?;
+                Value::Array(stops.into_iter().map(Value::String).collect())
+            }
+        };
+        Ok(Some(value))
+    }
+}
+
+/// The parameters in the form, in the order they're listed.
+pub const PARAMETERS: &[ParamSpec] = &[
+    ParamSpec::float("temperature", 0.0, 2.0, "Randomness of the output; higher is more creative."),
+    ParamSpec::float("top_p", 0.0, 1.0, "Samples from the smallest set of tokens whose probabilities add up to this."),
+    ParamSpec::int("top_k", 0, i64::MAX, "Samples from this many most likely tokens; 0 disables the limit."),
+    ParamSpec::float("min_p", 0.0, 1.0, "Drops tokens less likely than this share of the most likely one."),
+    ParamSpec::float("typical_p", 0.0, 1.0, "Locally typical sampling; 1 disables it."),
+    ParamSpec::int("num_ctx", 1, i64::MAX, "Context window in tokens."),
+    ParamSpec::int("num_predict", -2, i64::MAX, "Most tokens to generate; -1 is unlimited, -2 fills the context."),
+    ParamSpec::float("repeat_penalty", 0.0, 2.0, "Penalizes repeated tokens; 1 disables it."),
+    ParamSpec::int("repeat_last_n", -1, i64::MAX, "Tokens looked back at for repetition; 0 disables it, -1 uses num_ctx."),
+    ParamSpec::float("presence_penalty", -2.0, 2.0, "Penalizes tokens that already appeared at all."),
+    ParamSpec::float("frequency_penalty", -2.0, 2.0, "Penalizes tokens by how often they appeared."),
+    ParamSpec { name: "stop", kind: ParamKind::StopList, description: "Generation stops when one of these is produced." },
+    ParamSpec::int("seed", i64::MIN, i64::MAX, "Fixed random seed, for reproducible output."),
+    ParamSpec::int("mirostat", 0, 2, "Mirostat sampling: 0 off, 1 Mirostat, 2 Mirostat 2.0."),
+    ParamSpec::float("mirostat_tau", 0.0, f64::INFINITY, "Mirostat target entropy; lower is more focused."),
+    ParamSpec::float("mirostat_eta", 0.0, 1.0, "Mirostat learning rate."),
+    ParamSpec::int("num_keep", -1, i64::MAX, "Prompt tokens kept when the context is shifted; -1 keeps all."),
+    ParamSpec::int("num_batch", 1, i64::MAX, "Prompt tokens processed at once."),
+    ParamSpec::int("num_gpu", -1, i64::MAX, "Layers offloaded to the GPU; -1 lets Ollama decide."),
+    ParamSpec::int("num_thread", 0, i64::MAX, "CPU threads; 0 lets Ollama decide."),
+];
+
+/// Splits `"a" "b c" d` into its strings; quotes allow spaces and `\"`.
+fn split_stops(text: &str) -> Result<Vec<String>, String> {
+    let mut stops = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut stop = String::new();
+        if c == '"' {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('\\') if chars.peek() == Some(&'"') => stop.push(chars.next().unwrap_or('"')),
+                    Some('"') => break,
+                    Some(c) => stop.push(c),
+                    None => return Err("A stop sequence is missing its closing quote".to_string()),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek().filter(|c| !c.is_whitespace()) {
+                stop.push(c);
+                chars.next();
+            }
+        }
+        if stop.is_empty() {
+            return Err("Stop sequences can't be empty".to_string());
+        }
+        stops.push(stop);
+    }
+    Ok(stops)
+}
+
+/// Shows stop sequences the way they are typed: quoted, separated by spaces.
+fn join_stops(stops: &[String]) -> String {
+    let quoted: Vec<String> = stops.iter().map(|s| format!("\"{}\"", s.replace('"', "\\\""))).collect();
+    quoted.join(" ")
+}
+
+/// Values of the `parameters` text from `/api/show` (`name value` per line), keyed by name.
+fn current_parameters(text: &str) -> HashMap<String, Vec<String>> {
+    let mut values: HashMap<String, Vec<String>> = HashMap::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some((name, value)) = line.split_once(char::is_whitespace) {
+            values.entry(name.to_ascii_lowercase()).or_default().push(unquote(value.trim()));
+        }
+    }
+    values
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamField {
+    pub spec: &'static ParamSpec,
+    /// Value in the source model, as typed into the field; `None` if it isn't set there.
+    pub current: Option<String>,
+    pub input: TextInput,
+}
+
+impl ParamField {
+    /// The new value if it differs from the current one, or why it can't be used.
+    pub fn change(&self) -> Result<Option<Value>, String> {
+        let value = self.spec.parse(self.input.text())?;
+        let current = self.current.as_deref().and_then(|c| self.spec.parse(c).ok().flatten());
+        match (value, current) {
+            // `/api/create` merges parameters into the source's, so a value can't be taken away
+            (None, Some(_)) => {
+                Err(format!("{} is set in the source model; enter a value instead of removing it", self.spec.name))
+            }
+            (Some(value), current) if current.as_ref() != Some(&value) => Ok(Some(value)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.input.set_text(self.current.as_deref().unwrap_or(""));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormFocus {
+    Name,
+    Fields,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamForm {
+    pub source: String,
+    pub name: TextInput,
+    pub fields: Vec<ParamField>,
+    pub selected: usize,
+    pub focus: FormFocus,
+    /// Visible field rows, recorded when the form is drawn.
+    pub page_height: u16,
+    /// Why the last create didn't start or failed.
+    pub error: Option<String>,
+    /// Status lines streamed by `/api/create`, oldest first.
+    pub progress: Vec<String>,
+    /// The `/api/create` request in flight, if any.
+    pub request_id: Option<u64>,
+}
+
+impl ParamForm {
+    /// Fills every field with the source model's value from its `/api/show` parameters.
+    pub fn new(source: String, parameters: Option<&str>) -> Self {
+        let current = current_parameters(parameters.unwrap_or(""));
+        let fields = PARAMETERS
+            .iter()
+            .map(|spec| {
+                let values = current.get(spec.name);
+                let current = match (spec.kind, values) {
+                    (_, None) => None,
+                    (ParamKind::StopList, Some(stops)) => Some(join_stops(stops)),
+                    (_, Some(values)) => values.last().cloned(),
+                };
+                let mut field = ParamField { spec, current, input: TextInput::default() };
+                field.reset();
+                field
+            })
+            .collect();
+        Self {
+            source,
+            name: TextInput::default(),
+            fields,
+            selected: 0,
+            focus: FormFocus::Name,
+            page_height: 0,
+            error: None,
+            progress: Vec::new(),
+            request_id: None,
+        }
+    }
+
+    pub fn is_creating(&self) -> bool {
+        self.request_id.is_some()
+    }
+
+    /// The field that typing and editing keys go to.
+    pub fn focused_input(&mut self) -> &mut TextInput {
+        match self.focus {
+            FormFocus::Name => &mut self.name,
+            FormFocus::Fields => &mut self.fields[self.selected].input,
+        }
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            FormFocus::Name => FormFocus::Fields,
+            FormFocus::Fields => FormFocus::Name,
+        };
+    }
+
+    /// Moves between fields; moving up from the first one goes to the name.
+    pub fn step(&mut self, delta: isize) {
+        match self.focus {
+            FormFocus::Name if delta > 0 => self.focus = FormFocus::Fields,
+            FormFocus::Name => {}
+            FormFocus::Fields if delta < 0 && self.selected == 0 => self.focus = FormFocus::Name,
+            FormFocus::Fields => {
+                let last = self.fields.len() as isize - 1;
+                self.selected = (self.selected as isize + delta).clamp(0, last) as usize;
+            }
+        }
+    }
+
+    /// The full name of the new model, and whether it replaces an existing one.
+    pub fn target(&self, models: &[ModelInfo]) -> Result<(String, bool), String> {
+        let target = normalize_model_name(self.name.text().trim())?;
+        if target.eq_ignore_ascii_case(&self.source) {
+            return Err("Same as the source model".to_string());
+        }
+        let exists = models.iter().any(|m| m.name.eq_ignore_ascii_case(&target));
+        Ok((target, exists))
+    }
+
+    /// Changed parameters by field index, in form order.
+    pub fn changes(&self) -> Vec<(usize, Value)> {
+        self.fields
+            .iter()
+            .enumerate()
+            .filter_map(|(i, field)| field.change().ok().flatten().map(|value| (i, value)))
+            .collect()
+    }
+
+    /// Checks the name and every field; the first problem is kept for display and focused.
+    pub fn build_request(&mut self, models: &[ModelInfo]) -> Option<CreateModelRequest> {
+        let target = match self.target(models) {
+            Ok((target, _)) => target,
+            Err(message) => {
+                self.error = Some(format!("Name: {}", message));
+                self.focus = FormFocus::Name;
+                return None;
+            }
+        };
+        if let Some((i, message)) = self.fields.iter().enumerate().find_map(|(i, f)| f.change().err().map(|e| (i, e))) {
+            self.error = Some(message);
+            self.focus = FormFocus::Fields;
+            self.selected = i;
+            return None;
+        }
+        // Only the changes are sent; everything else is inherited from the source
+        let parameters: Map<String, Value> = self
+            .changes()
+            .into_iter()
+            .map(|(i, value)| (self.fields[i].spec.name.to_string(), value))
+            .collect();
+        if parameters.is_empty() {
+            self.error = Some("No parameter was changed".to_string());
+            return None;
+        }
+        self.error = None;
+        Some(CreateModelRequest {
+            model: target,
+            from: self.source.clone(),
+            template: None,
+            system: None,
+            license: Vec::new(),
+            parameters,
+            messages: Vec::new(),
+            quantize: None,
+            stream: true,
+        })
+    }
+
+    pub fn begin_create(&mut self, request_id: u64) {
+        self.request_id = Some(request_id);
+        self.progress.clear();
+        self.error = None;
+    }
+
+    pub fn push_progress(&mut self, request_id: u64, status: String) {
+        if self.request_id == Some(request_id) && self.progress.last() != Some(&status) {
+            self.progress.push(status);
+        }
+    }
+
+    pub fn fail_create(&mut self, request_id: u64, message: String) {
+        if self.request_id != Some(request_id) {
+            return;
+        }
+        self.request_id = None;
+        self.error = Some(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(name: &str) -> &'static ParamSpec {
+        PARAMETERS.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn numbers_are_checked_against_their_range() {
+        assert_eq!(spec("temperature").parse(" 0.7 ").unwrap(), Some(json!(0.7)));
+        assert_eq!(spec("temperature").parse("").unwrap(), None);
+        assert_eq!(spec("temperature").parse("2.5").unwrap_err(), "temperature must be 0 to 2");
+        assert_eq!(spec("temperature").parse("NaN").unwrap_err(), "temperature must be 0 to 2");
+        assert_eq!(spec("temperature").parse("warm").unwrap_err(), "temperature takes a number");
+
+        assert_eq!(spec("num_predict").parse("-2").unwrap(), Some(json!(-2)));
+        assert_eq!(spec("num_predict").parse("-3").unwrap_err(), "num_predict must be -2 or more");
+        assert_eq!(spec("num_ctx").parse("4096.5").unwrap_err(), "num_ctx takes a whole number");
+        assert_eq!(spec("mirostat").parse("3").unwrap_err(), "mirostat must be 0 to 2");
+        assert_eq!(spec("seed").range(), "any");
+        assert_eq!(spec("mirostat_tau").range(), "0 or more");
+    }
+
+    #[test]
+    fn stops_are_split_on_spaces_outside_quotes() {
+        assert_eq!(
+            spec("stop").parse(r#""<|im_end|>" "User:"  END"#).unwrap(),
+            Some(json!(["<|im_end|>", "User:", "END"]))
+        );
+        assert_eq!(split_stops(r#""say \"hi\"" "a b""#).unwrap(), ["say \"hi\"", "a b"]);
+        // A backslash before anything else is kept as typed
+        assert_eq!(split_stops(r#""C:\path""#).unwrap(), [r"C:\path"]);
+        assert_eq!(join_stops(&["say \"hi\"".to_string()]), r#""say \"hi\"""#);
+    }
+
+    #[test]
+    fn bad_stops_are_errors() {
+        assert_eq!(split_stops(r#""open"#).unwrap_err(), "A stop sequence is missing its closing quote");
+        assert_eq!(split_stops(r#""escaped end\""#).unwrap_err(), "A stop sequence is missing its closing quote");
+        assert_eq!(split_stops(r#"a """#).unwrap_err(), "Stop sequences can't be empty");
+    }
+
+    #[test]
+    fn fields_start_from_the_source_parameters() {
+        let form = ParamForm::new(
+            "llama3:latest".to_string(),
+            Some("stop                           \"<|eot_id|>\"\nstop \"say \\\"bye\\\"\"\ntemperature 0.6\n"),
+        );
+        let field = |name: &str| form.fields.iter().find(|f| f.spec.name == name).unwrap();
+        assert_eq!(field("temperature").input.text(), "0.6");
+        assert_eq!(field("stop").input.text(), r#""<|eot_id|>" "say \"bye\"""#);
+        assert_eq!(field("top_k").current, None);
+        assert!(form.changes().is_empty());
+    }
+
+    #[test]
+    fn change_compares_parsed_values() {
+        let mut form = ParamForm::new("llama3:latest".to_string(), Some("temperature 0.6\n"));
+        let index = form.fields.iter().position(|f| f.spec.name == "temperature").unwrap();
+        let field = &mut form.fields[index];
+
+        field.input.set_text("0.60");
+        assert_eq!(field.change().unwrap(), None);
+        field.input.set_text("1");
+        assert_eq!(field.change().unwrap(), Some(json!(1.0)));
+        assert_eq!(form.changes(), [(index, json!(1.0))]);
+    }
+
+    #[test]
+    fn an_inherited_value_cannot_be_removed() {
+        let mut form = ParamForm::new("llama3:latest".to_string(), Some("temperature 0.6\n"));
+        let field = form.fields.iter_mut().find(|f| f.spec.name == "temperature").unwrap();
+        field.input.set_text("  ");
+        assert_eq!(
+            field.change().unwrap_err(),
+            "temperature is set in the source model; enter a value instead of removing it"
+        );
+        field.reset();
+        assert_eq!(field.change().unwrap(), None);
+
+        // Clearing a value the source doesn't set is not a change
+        let top_k = form.fields.iter().find(|f| f.spec.name == "top_k").unwrap();
+        assert_eq!(top_k.change().unwrap(), None);
+    }
+}
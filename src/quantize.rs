@@ -0,0 +1,280 @@
+// src/quantize.rs
+// Dialog that quantizes an F16/F32 model into a smaller copy with the `quantize` option of /api/create.
+
+use crate::copy_dialog::normalize_model_name;
+use crate::model_table::split_name_tag;
+use crate::ollama_api::{CreateModelRequest, CreateProgress, ModelInfo};
+use crate::text_input::TextInput;
+use humansize::{format_size, BINARY};
+use ratatui::widgets::ListState;
+use serde_json::Map;
+
+/// Types `/api/create` can quantize to, most commonly used first.
+pub const QUANTIZATIONS: &[(&str, &str)] = &[
+    ("q4_K_M", "Recommended balance of size and quality"),
+    ("q4_K_S", "Slightly smaller than q4_K_M"),
+    ("q5_K_M", "Better quality, about a fifth larger than q4_K_M"),
+    ("q5_K_S", "Slightly smaller than q5_K_M"),
+    ("q6_K", "Close to q8_0 quality"),
+    ("q8_0", "Nearly lossless, about half of F16"),
+    ("q3_K_L", "Small, noticeable quality loss"),
+    ("q3_K_M", "Smaller, more quality loss"),
+    ("q3_K_S", "Smaller still"),
+    ("q2_K", "Smallest, large quality loss"),
+    ("q4_0", "Legacy 4-bit"),
+    ("q4_1", "Legacy 4-bit"),
+    ("q5_0", "Legacy 5-bit"),
+    ("q5_1", "Legacy 5-bit"),
+];
+
+/// Weight types that can be quantized from.
+const SOURCE_TYPES: &[&str] = &["F16", "F32"];
+
+/// Tag suffixes naming the source's weight type, replaced by the new type in suggested names.
+const TYPE_SUFFIXES: &[&str] = &["-fp16", "-f16", "-fp32", "-f32"];
+
+/// The weight type of `model` if it can be quantized, or why it can't.
+pub fn source_type(model: &ModelInfo) -> Result<String, String> {
+    let level = model.details.as_ref().and_then(|d| d.quantization_level.as_deref());
+    match level {
+        Some(level) if SOURCE_TYPES.iter().any(|t| t.eq_ignore_ascii_case(level)) => Ok(level.to_ascii_uppercase()),
+        Some(level) => Err(format!("{} is already quantized ({}); quantize from an F16 or F32 model", model.name, level)),
+        None => Err(format!("The weight type of {} is unknown; quantize from an F16 or F32 model", model.name)),
+    }
+}
+
+/// "14.96 GiB → 4.58 GiB (31% of the source)".
+pub fn size_comparison(source_size: u64, size: u64) -> String {
+    let share = if source_size == 0 { 0.0 } else { size as f64 * 100.0 / source_size as f64 };
+    format!(
+        "{} → {} ({:.0}% of the source)",
+        format_size(source_size, BINARY),
+        format_size(size, BINARY),
+        share
+    )
+}
+
+/// A model made in the dialog; `size` is `None` if it didn't show up in the list.
+#[derive(Debug, Clone)]
+pub struct QuantizeResult {
+    pub model: String,
+    pub quantization: &'static str,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QuantizeDialog {
+    pub source: String,
+    pub source_size: u64,
+    /// `F16` or `F32`.
+    pub source_type: String,
+    pub list_state: ListState,
+    pub name: TextInput,
+    /// The `/api/create` request in flight, if any.
+    pub request_id: Option<u64>,
+    /// Latest status line of the request in flight.
+    pub status: Option<String>,
+    /// Share of the weights quantized so far, once the server reports it.
+    pub ratio: Option<f64>,
+    pub error: Option<String>,
+    /// Models made since the dialog was opened, oldest first.
+    pub results: Vec<QuantizeResult>,
+    /// Type the request in flight quantizes to.
+    quantization: &'static str,
+}
+
+impl QuantizeDialog {
+    pub fn new(model: &ModelInfo, source_type: String) -> Self {
+        let mut dialog = Self {
+            source: model.name.clone(),
+            source_size: model.size,
+            source_type,
+            list_state: ListState::default().with_selected(Some(0)),
+            name: TextInput::default(),
+            request_id: None,
+            status: None,
+            ratio: None,
+            error: None,
+            results: Vec::new(),
+            quantization: QUANTIZATIONS[0].0,
+        };
+        dialog.name.set_text(&dialog.suggested_name());
+        dialog
+    }
+
+    pub fn is_creating(&self) -> bool {
+        self.request_id.is_some()
+    }
+
+    pub fn selected_type(&self) -> &'static str {
+        QUANTIZATIONS[self.list_state.selected().unwrap_or(0)].0
+    }
+
+    /// The source's name with the selected type as tag, e.g. `llama3:8b-fp16` -> `llama3:8b-q4_K_M`.
+    pub fn suggested_name(&self) -> String {
+        let (model, tag) = split_name_tag(&self.source);
+        let quantization = self.selected_type();
+        let lower = tag.to_ascii_lowercase();
+        match TYPE_SUFFIXES.iter().find(|s| lower.ends_with(*s)) {
+            Some(suffix) => format!("{}:{}-{}", model, &tag[..tag.len() - suffix.len()], quantization),
+            None if tag == "latest" || SOURCE_TYPES.iter().any(|t| t.eq_ignore_ascii_case(tag)) => {
+                format!("{}:{}", model, quantization)
+            }
+            None => format!("{}:{}-{}", model, tag, quantization),
+        }
+    }
+
+    /// Moves through the types without wrapping. A name that wasn't edited follows the selection.
+    pub fn step(&mut self, delta: isize) {
+        let follows = self.name.text() == self.suggested_name();
+        let index = self.list_state.selected().unwrap_or(0) as isize + delta;
+        self.list_state.select(Some(index.clamp(0, QUANTIZATIONS.len() as isize - 1) as usize));
+        if follows {
+            self.name.set_text(&self.suggested_name());
+        }
+    }
+
+    /// The full name of the new model, and whether it replaces an existing one.
+    pub fn target(&self, models: &[ModelInfo]) -> Result<(String, bool), String> {
+        let target = normalize_model_name(self.name.text().trim())?;
+        if target.eq_ignore_ascii_case(&self.source) {
+            return Err("Same as the source model".to_string());
+        }
+        let exists = models.iter().any(|m| m.name.eq_ignore_ascii_case(&target));
+        Ok((target, exists))
+    }
+
+    pub fn build_request(&mut self, models: &[ModelInfo]) -> Option<CreateModelRequest> {
+        let target = match self.target(models) {
+            Ok((target, _)) => target,
+            Err(message) => {
+                self.error = Some(format!("Name: {}", message));
+                return None;
+            }
+        };
+        self.error = None;
+        Some(CreateModelRequest {
+            model: target,
+            from: self.source.clone(),
+            template: None,
+            system: None,
+            license: Vec::new(),
+            parameters: Map::new(),
+            messages: Vec::new(),
+            quantize: Some(self.selected_type().to_string()),
+            stream: true,
+        })
+    }
+
+    pub fn begin_create(&mut self, request_id: u64) {
+        self.request_id = Some(request_id);
+        self.quantization = self.selected_type();
+        self.status = None;
+        self.ratio = None;
+        self.error = None;
+    }
+
+    pub fn push_progress(&mut self, request_id: u64, progress: CreateProgress) {
+        if self.request_id != Some(request_id) {
+            return;
+        }
+        self.ratio = match (progress.completed, progress.total) {
+            (Some(completed), Some(total)) if total > 0 => Some((completed as f64 / total as f64).min(1.0)),
+            _ => None,
+        };
+        self.status = Some(progress.status);
+    }
+
+    pub fn fail_create(&mut self, request_id: u64, message: String) {
+        if self.request_id != Some(request_id) {
+            return;
+        }
+        self.request_id = None;
+        self.status = None;
+        self.ratio = None;
+        self.error = Some(message);
+    }
+
+    /// Records the new model with its size from the refreshed list and describes the saving.
+    pub fn finish_create(&mut self, model: &str, models: &[ModelInfo]) -> String {
+        self.request_id = None;
+        self.status = None;
+        self.ratio = None;
+        let size = models.iter().find(|m| m.name.eq_ignore_ascii_case(model)).map(|m| m.size);
+        self.results.push(QuantizeResult { model: model.to_string(), quantization: self.quantization, size });
+        match size {
+            Some(size) => format!("Quantized {}: {}", model, size_comparison(self.source_size, size)),
+            None => format!("Quantized {}", model),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn model(name: &str, quantization_level: Option<&str>) -> ModelInfo {
+        serde_json::from_value(json!({
+            "name": name,
+            "modified_at": "2024-05-01T10:00:00Z",
+            "size": 16_068_891_681u64,
+            "digest": "sha256:5f6e",
+            "details": { "quantization_level": quantization_level },
+        }))
+        .unwrap()
+    }
+
+    fn suggested(source: &str) -> String {
+        QuantizeDialog::new(&model(source, Some("F16")), "F16".to_string()).suggested_name()
+    }
+
+    #[test]
+    fn suggested_name_replaces_the_type_suffix() {
+        assert_eq!(suggested("llama3:8b-instruct-fp16"), "llama3:8b-instruct-q4_K_M");
+        assert_eq!(suggested("llama3:8b-F16"), "llama3:8b-q4_K_M");
+        assert_eq!(suggested("qwen2:7b-fp32"), "qwen2:7b-q4_K_M");
+        assert_eq!(suggested("me/model:v2"), "me/model:v2-q4_K_M");
+    }
+
+    #[test]
+    fn suggested_name_replaces_latest_and_bare_type_tags() {
+        assert_eq!(suggested("tinyllama:latest"), "tinyllama:q4_K_M");
+        assert_eq!(suggested("tinyllama"), "tinyllama:q4_K_M");
+        assert_eq!(suggested("tinyllama:F16"), "tinyllama:q4_K_M");
+        assert_eq!(suggested("localhost:5000/tinyllama"), "localhost:5000/tinyllama:q4_K_M");
+    }
+
+    #[test]
+    fn unedited_names_follow_the_selected_type() {
+        let mut dialog = QuantizeDialog::new(&model("llama3:8b-fp16", Some("F16")), "F16".to_string());
+        dialog.step(5);
+        assert_eq!(dialog.name.text(), "llama3:8b-q8_0");
+        dialog.step(100);
+        assert_eq!(dialog.selected_type(), "q5_1");
+
+        dialog.name.set_text("mine");
+        dialog.step(-1);
+        assert_eq!(dialog.name.text(), "mine");
+    }
+
+    #[test]
+    fn only_f16_and_f32_models_can_be_quantized() {
+        assert_eq!(source_type(&model("llama3:8b-fp16", Some("F16"))).unwrap(), "F16");
+        assert_eq!(source_type(&model("llama3:8b-fp32", Some("f32"))).unwrap(), "F32");
+        assert_eq!(
+            source_type(&model("llama3:8b", Some("Q4_K_M"))).unwrap_err(),
+            "llama3:8b is already quantized (Q4_K_M); quantize from an F16 or F32 model"
+        );
+        assert_eq!(
+            source_type(&model("custom:latest", None)).unwrap_err(),
+            "The weight type of custom:latest is unknown; quantize from an F16 or F32 model"
+        );
+    }
+
+    #[test]
+    fn compares_sizes() {
+        assert_eq!(size_comparison(100 << 20, 31 << 20), "100 MiB → 31 MiB (31% of the source)");
+        assert_eq!(size_comparison(0, 1024), "0 B → 1 KiB (0% of the source)");
+    }
+}
@@ -1,15 +1,200 @@
 // src/registry_api.rs
-// Functions for interacting with the Ollama registry website (scraping)
+// Interacts with the Ollama registry: the OCI distribution API (/v2) for tags and
+// manifests, and the library web pages (scraping) as a fallback.
 
+use crate::config::Timeouts;
 use crate::error::{ApiError, AppError, Result}; // Result is the alias for std::result::Result<T, AppError>
+use humansize::{format_size, BINARY};
 use scraper::{Html, Selector};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::env;
 
-const REGISTRY_BASE_URL: &str = "https://registry.ollama.ai";
+const DEFAULT_REGISTRY_URL: &str = "https://registry.ollama.ai";
+const MANIFEST_ACCEPT: &str = "application/vnd.docker.distribution.manifest.v2+json";
+
+/// Returns the registry base URL: `OLLAMA_REGISTRY_URL` (e.g. to point at a local
+/// stand-in registry), then the configured URL, then the public registry.
+pub fn get_registry_url(configured: Option<&str>) -> String {
+    env::var("OLLAMA_REGISTRY_URL")
+        .ok()
+        .or_else(|| configured.map(str::to_string))
+        .unwrap_or_else(|| DEFAULT_REGISTRY_URL.to_string())
+}
+
+/// A content descriptor (config or layer) referenced by a manifest.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Descriptor {
+    pub media_type: String,
+    pub digest: String,
+    pub size: u64,
+}
+
+/// An image manifest as served by `/v2/<name>/manifests/<tag>`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub config: Descriptor,
+    pub layers: Vec<Descriptor>,
+}
+
+impl Manifest {
+    /// Total bytes a pull of this tag downloads (config plus all layers).
+    pub fn download_size(&self) -> u64 {
+        self.config.size + self.layers.iter().map(|l| l.size).sum::<u64>()
+    }
+
+    pub fn download_size_formatted(&self) -> String {
+        format_size(self.download_size(), BINARY)
+    }
+
+    /// Short layer kinds, e.g. `model`, `template`, `params`, `license`.
+    pub fn layer_kinds(&self) -> Vec<&str> {
+        self.layers
+            .iter()
+            .map(|l| l.media_type.rsplit('.').next().unwrap_or(&l.media_type))
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct TagList {
+    tags: Vec<String>,
+}
+
+/// Maps a model name to its repository path; official models live under `library/`.
+fn repository_path(model_name: &str) -> String {
+    if model_name.contains('/') {
+        model_name.to_string()
+    } else {
+        format!("library/{}", model_name)
+    }
+}
+
+/// Puts `latest` first and sorts the remaining tags alphabetically.
+fn sort_tags(tags: &mut Vec<String>) {
+    tags.sort();
+    if let Some(pos) = tags.iter().position(|t| t == "latest")
+        && pos > 0
+    {
+        let latest_tag = tags.remove(pos);
+        tags.insert(0, latest_tag);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RegistryClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl RegistryClient {
+    pub fn new(base_url: String, timeouts: &Timeouts) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(timeouts.connect())
+            .timeout(timeouts.request())
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Fetches the list of available models from the registry library page.
+    pub async fn fetch_models(&self) -> Result<Vec<String>> {
+        fetch_registry_models(&self.client, &self.base_url).await
+    }
+
+    /// Fetches a model's tags from the `/v2` tags endpoint, falling back to
+    /// scraping the library page if the endpoint is unavailable.
+    pub async fn fetch_tags(&self, model_name: &str) -> Result<Vec<String>> {
+        match self.fetch_tags_v2(model_name).await {
+            Ok(tags) if !tags.is_empty() => Ok(tags),
+            _ => fetch_registry_tags(&self.client, &self.base_url, model_name).await,
+        }
+    }
+
+    async fn fetch_tags_v2(&self, model_name: &str) -> Result<Vec<String>> {
+        let url = format!("{}/v2/{}/tags/list", self.base_url, repository_path(model_name));
+        let res = self.client.get(&url).send().await.map_err(ApiError::Reqwest)?;
+        if !res.status().is_success() {
+            return Err(AppError::Api(ApiError::ResponseError(format!(
+                "Registry returned {} for {}",
+                res.status(),
+                url
+            ))));
+        }
+        let body: TagList = res.json().await.map_err(ApiError::Reqwest)?;
+        let mut tags = body.tags;
+        sort_tags(&mut tags);
+        Ok(tags)
+    }
+
+    /// Fetches and parses the manifest for `model:tag`.
+    pub async fn fetch_manifest(&self, model_name: &str, tag: &str) -> Result<Manifest> {
+        let body = self.fetch_manifest_bytes(model_name, tag).await?;
+        let manifest = serde_json::from_slice(&body).map_err(ApiError::Deserialization)?;
+        Ok(manifest)
+    }
+
+    /// Returns the hex sha256 of the manifest for `model:tag`. Ollama stores the
+    /// manifest as pulled, so this matches the local `digest` while the model is current.
+    pub async fn fetch_manifest_digest(&self, model_name: &str, tag: &str) -> Result<String> {
+        let body = self.fetch_manifest_bytes(model_name, tag).await?;
+        let digest = Sha256::digest(&body);
+        Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    async fn fetch_manifest_bytes(&self, model_name: &str, tag: &str) -> Result<Vec<u8>> {
+        let url = format!(
+            "{}/v2/{}/manifests/{}",
+            self.base_url,
+            repository_path(model_name),
+            tag
+        );
+        let res = self
+            .client
+            .get(&url)
+            .header(reqwest::header::ACCEPT, MANIFEST_ACCEPT)
+            .send()
+            .await
+            .map_err(ApiError::Reqwest)?;
+        if !res.status().is_success() {
+            return Err(AppError::Api(ApiError::ResponseError(format!(
+                "Registry returned {} for {}:{}",
+                res.status(),
+                model_name,
+                tag
+            ))));
+        }
+        let body = res.bytes().await.map_err(ApiError::Reqwest)?;
+        Ok(body.to_vec())
+    }
+}
+
+/// Splits a local model name like `llama3:latest` or `user/model:q4` into
+/// repository and tag. Returns `None` for models pulled from other registries.
+pub fn split_model_name(name: &str) -> Option<(&str, &str)> {
+    let (repository, tag) = match name.rsplit_once(':') {
+        Some((repository, tag)) if !tag.contains('/') => (repository, tag),
+        _ => (name, "latest"),
+    };
+    // A host prefix such as `hf.co/` or `localhost:5000/` means a different registry
+    let first_segment = repository.split('/').next().unwrap_or_default();
+    if repository.contains('/') && (first_segment.contains('.') || first_segment.contains(':')) {
+        return None;
+    }
+    Some((repository, tag))
+}
 
 /// Fetches the list of available models from the Ollama registry library page.
-pub async fn fetch_registry_models() -> Result<Vec<String>> { // Use Result alias
-    let url = format!("{}/library", REGISTRY_BASE_URL);
-    let html_content = reqwest::get(&url)
+async fn fetch_registry_models(client: &reqwest::Client, base_url: &str) -> Result<Vec<String>> { // Use Result alias
+    let url = format!("{}/library", base_url);
+    let html_content = client
+        .get(&url)
+        .send()
         .await
         .map_err(|e| AppError::Api(ApiError::Reqwest(e)))? // Map Reqwest error
         .text()
@@ -47,10 +232,13 @@ pub async fn fetch_registry_models() -> Result<Vec<String>> { // Use Result alia
     }
 }
 
-/// Fetches the list of available tags for a specific model from the Ollama registry.
-pub async fn fetch_registry_tags(model_name: &str) -> Result<Vec<String>> { // Use Result alias
-    let url = format!("{}/library/{}/tags", REGISTRY_BASE_URL, model_name);
-     let html_content = reqwest::get(&url)
+/// Scrapes the list of available tags for a specific model from the library page.
+/// Only used when the `/v2` tags endpoint is unavailable.
+async fn fetch_registry_tags(client: &reqwest::Client, base_url: &str, model_name: &str) -> Result<Vec<String>> { // Use Result alias
+    let url = format!("{}/library/{}/tags", base_url, model_name);
+     let html_content = client
+        .get(&url)
+        .send()
         .await
         .map_err(|e| AppError::Api(ApiError::Reqwest(e)))? // Map Reqwest error
         .text()
@@ -89,19 +277,153 @@ pub async fn fetch_registry_tags(model_name: &str) -> Result<Vec<String>> { // U
             tags.push("latest".to_string());
         }
 
-        // Sort all tags alphabetically
-        tags.sort();
+        sort_tags(&mut tags);
 
-        // Move "latest" to the front
-        if let Some(pos) = tags.iter().position(|t| t == "latest") {
-            // Only move if it's not already at the front
-            if pos > 0 {
-            let latest_tag = tags.remove(pos);
-            tags.insert(0, latest_tag);
+        Ok(tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Starts a stand-in registry answering `GET <path>` from `routes`; other paths get a 404.
+    async fn stand_in(routes: &[(&'static str, u16, &str)]) -> RegistryClient {
+        let routes: Vec<(&str, u16, String)> = routes.iter().map(|&(p, s, b)| (p, s, b.to_string())).collect();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match socket.read(&mut buffer).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buffer[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = match routes.iter().find(|(route, _, _)| *route == path) {
+                    Some((_, status, body)) => (*status, body.as_str()),
+                    None => (404, ""),
+                };
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
             }
-        }
-        // If "latest" wasn't found (shouldn't happen here) or was already at pos 0, do nothing.
+        });
+        RegistryClient::new(base_url, &Timeouts::default())
+    }
 
-        Ok(tags)
+    const MANIFEST: &str = r#"{
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.docker.distribution.manifest.v2+json",
+        "config": { "mediaType": "application/vnd.docker.container.image.v1+json", "digest": "sha256:c0", "size": 485 },
+        "layers": [
+            { "mediaType": "application/vnd.ollama.image.model", "digest": "sha256:m0", "size": 4661211424 },
+            { "mediaType": "application/vnd.ollama.image.template", "digest": "sha256:t0", "size": 1480 },
+            { "mediaType": "application/vnd.ollama.image.license", "digest": "sha256:l0", "size": 12403 }
+        ]
+    }"#;
+
+    /// Tag page markup as the scraping selector expects it.
+    fn tag_page(tags: &[&str]) -> String {
+        let rows: String = tags
+            .iter()
+            .map(|t| format!("<a href=\"/library/{t}\"><div><div><span>{t}</span><span>4.7GB</span></div></div></a>"))
+            .collect();
+        format!("<html><body><section>{}</section></body></html>", rows)
+    }
+
+    #[test]
+    fn official_models_live_under_library() {
+        assert_eq!(repository_path("llama3"), "library/llama3");
+        assert_eq!(repository_path("user/model"), "user/model");
+
+        let mut tags = vec!["8b".to_string(), "latest".to_string(), "70b".to_string()];
+        sort_tags(&mut tags);
+        assert_eq!(tags, ["latest", "70b", "8b"]);
+    }
+
+    #[tokio::test]
+    async fn reads_manifests() {
+        let registry = stand_in(&[("/v2/library/llama3/manifests/8b", 200, MANIFEST)]).await;
+        let manifest = registry.fetch_manifest("llama3", "8b").await.unwrap();
+        assert_eq!(manifest.download_size(), 485 + 4661211424 + 1480 + 12403);
+        assert_eq!(manifest.layer_kinds(), ["model", "template", "license"]);
+
+        let missing = registry.fetch_manifest("llama3", "70b").await.unwrap_err();
+        assert!(missing.to_string().contains("404 Not Found for llama3:70b"), "{}", missing);
+    }
+
+    #[tokio::test]
+    async fn lists_tags_from_the_v2_api() {
+        let registry = stand_in(&[
+            ("/v2/library/llama3/tags/list", 200, r#"{"name":"library/llama3","tags":["8b","latest","70b"]}"#),
+            ("/v2/user/model/tags/list", 200, r#"{"name":"user/model","tags":["q8","q4"]}"#),
+        ])
+        .await;
+        assert_eq!(registry.fetch_tags("llama3").await.unwrap(), ["latest", "70b", "8b"]);
+        // No `latest` is made up when the API lists the tags
+        assert_eq!(registry.fetch_tags("user/model").await.unwrap(), ["q4", "q8"]);
+    }
+
+    #[tokio::test]
+    async fn scrapes_tags_when_the_api_has_none() {
+        let llama = tag_page(&["llama3:8b", "llama3:70b", "llama3:8b", "llama3"]);
+        let mistral = tag_page(&["mistral:7b"]);
+        let registry = stand_in(&[
+            ("/v2/library/llama3/tags/list", 200, r#"{"tags":[]}"#),
+            ("/library/llama3/tags", 200, &llama),
+            ("/library/mistral/tags", 200, &mistral),
+        ])
+        .await;
+        // Duplicates and the bare model name are dropped, and `latest` is always offered
+        assert_eq!(registry.fetch_tags("llama3").await.unwrap(), ["latest", "70b", "8b"]);
+        assert_eq!(registry.fetch_tags("mistral").await.unwrap(), ["latest", "7b"]);
+
+        let error = registry.fetch_tags("phi3").await.unwrap_err();
+        assert!(matches!(error, AppError::Scraping(_)), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn scrapes_the_model_library() {
+        let page = r#"<html><body>
+            <a href="/library/mistral">mistral</a>
+            <a href="/library/llama3">llama3</a>
+            <a href="/library/llama3">Llama 3</a>
+            <a href="/library/llama3/tags">tags</a>
+            <a href="/search">search</a>
+        </body></html>"#;
+        let registry = stand_in(&[("/library", 200, page)]).await;
+        assert_eq!(registry.fetch_models().await.unwrap(), ["llama3", "mistral"]);
+
+        let empty = stand_in(&[("/library", 200, "<html></html>")]).await;
+        assert!(matches!(empty.fetch_models().await, Err(AppError::Scraping(_))));
+    }
+
+    #[test]
+    fn splits_local_names() {
+        assert_eq!(split_model_name("llama3"), Some(("llama3", "latest")));
+        assert_eq!(split_model_name("llama3:8b"), Some(("llama3", "8b")));
+        assert_eq!(split_model_name("user/model:q4"), Some(("user/model", "q4")));
+        // Pulled from elsewhere, so there is nothing to compare against
+        assert_eq!(split_model_name("hf.co/org/model:Q4_K_M"), None);
+        assert_eq!(split_model_name("localhost:5000/model"), None);
+    }
+
+    #[tokio::test]
+    async fn digest_covers_the_manifest_as_served() {
+        let registry = stand_in(&[("/v2/library/llama3/manifests/8b", 200, MANIFEST)]).await;
+        let digest = registry.fetch_manifest_digest("llama3", "8b").await.unwrap();
+        assert_eq!(digest.len(), 64);
+        assert_eq!(digest, format!("{:x}", Sha256::digest(MANIFEST.as_bytes())));
     }
 }
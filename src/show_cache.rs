@@ -0,0 +1,202 @@
+// src/show_cache.rs
+// Least-recently-used cache of `/api/show` responses, keyed by model name and digest.
+
+use crate::ollama_api::{ModelInfo, ShowModelResponse};
+use std::collections::HashMap;
+
+/// Responses kept before the least recently used one is dropped.
+pub const SHOW_CACHE_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone)]
+struct Entry {
+    digest: String,
+    response: ShowModelResponse,
+    last_used: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShowCache {
+    entries: HashMap<String, Entry>,
+    capacity: usize,
+    /// Bumped on every use, to find the least recently used entry.
+    clock: u64,
+    /// Latest request per model in flight, with the digest it was made for.
+    pending: HashMap<String, (u64, String)>,
+    /// Never reset, so responses from before a `clear` can't match a new request.
+    next_request_id: u64,
+}
+
+impl Default for ShowCache {
+    fn default() -> Self {
+        Self::new(SHOW_CACHE_CAPACITY)
+    }
+}
+
+impl ShowCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity: capacity.max(1),
+            clock: 0,
+            pending: HashMap::new(),
+            next_request_id: 0,
+        }
+    }
+
+    /// The response for this version of the model, marking it as recently used.
+    pub fn get(&mut self, name: &str, digest: &str) -> Option<&ShowModelResponse> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(name).filter(|e| e.digest == digest)?;
+        entry.last_used = self.clock;
+        Some(&entry.response)
+    }
+
+    /// The response for this version of the model, without affecting eviction.
+    pub fn peek(&self, name: &str, digest: &str) -> Option<&ShowModelResponse> {
+        self.entries.get(name).filter(|e| e.digest == digest).map(|e| &e.response)
+    }
+
+    pub fn contains(&self, name: &str, digest: &str) -> bool {
+        self.peek(name, digest).is_some()
+    }
+
+    pub fn insert(&mut self, name: String, digest: String, response: ShowModelResponse) {
+        self.clock += 1;
+        if !self.entries.contains_key(&name)
+            && self.entries.len() >= self.capacity
+            && let Some(oldest) = self.entries.iter().min_by_key(|(_, e)| e.last_used).map(|(n, _)| n.clone())
+        {
+            self.entries.remove(&oldest);
+        }
+        self.entries.insert(name, Entry { digest, response, last_used: self.clock });
+    }
+
+    /// Records a new request for the model and returns its id. Any earlier request becomes stale.
+    pub fn begin_request(&mut self, name: &str, digest: &str) -> u64 {
+        self.next_request_id += 1;
+        self.pending.insert(name.to_string(), (self.next_request_id, digest.to_string()));
+        self.next_request_id
+    }
+
+    pub fn is_pending(&self, name: &str) -> bool {
+        self.pending.contains_key(name)
+    }
+
+    /// Ends the request and returns the digest it was made for, or `None` if it is stale.
+    pub fn finish_request(&mut self, name: &str, request_id: u64) -> Option<String> {
+        match self.pending.get(name) {
+            Some((id, _)) if *id == request_id => self.pending.remove(name).map(|(_, digest)| digest),
+            _ => None,
+        }
+    }
+
+    /// Drops responses for models that were removed or whose digest changed, e.g. after a re-pull.
+    pub fn retain_models(&mut self, models: &[ModelInfo]) {
+        self.entries.retain(|name, e| models.iter().any(|m| &m.name == name && m.digest == e.digest));
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Forgets all responses and requests in flight, e.g. after switching hosts.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Map;
+
+    /// A response told apart by its license text.
+    fn response(license: &str) -> ShowModelResponse {
+        ShowModelResponse {
+            license: Some(license.to_string()),
+            modelfile: None,
+            parameters: None,
+            template: None,
+            details: None,
+            capabilities: Vec::new(),
+            model_info: None,
+            modified_at: None,
+            tensors: Vec::new(),
+            other: Map::new(),
+        }
+    }
+
+    fn cached(cache: &mut ShowCache, name: &str, digest: &str) -> Option<String> {
+        cache.get(name, digest).and_then(|r| r.license.clone())
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used() {
+        let mut cache = ShowCache::new(2);
+        cache.insert("a".into(), "1".into(), response("a"));
+        cache.insert("b".into(), "1".into(), response("b"));
+        // Reading `a` leaves `b` as the oldest
+        assert_eq!(cached(&mut cache, "a", "1").as_deref(), Some("a"));
+        cache.insert("c".into(), "1".into(), response("c"));
+        assert!(cache.contains("a", "1"));
+        assert!(!cache.contains("b", "1"));
+
+        // A new version replaces the old one instead of evicting another model
+        cache.insert("c".into(), "2".into(), response("c2"));
+        assert!(cache.contains("a", "1"));
+        assert_eq!(cached(&mut cache, "c", "1"), None);
+        assert_eq!(cached(&mut cache, "c", "2").as_deref(), Some("c2"));
+    }
+
+    #[test]
+    fn peeking_leaves_the_order_alone() {
+        let mut cache = ShowCache::new(2);
+        cache.insert("a".into(), "1".into(), response("a"));
+        cache.insert("b".into(), "1".into(), response("b"));
+        assert!(cache.peek("a", "1").is_some());
+        cache.insert("c".into(), "1".into(), response("c"));
+        assert!(!cache.contains("a", "1"));
+        assert_eq!(ShowCache::new(0).capacity(), 1);
+    }
+
+    #[test]
+    fn only_the_latest_request_finishes() {
+        let mut cache = ShowCache::default();
+        let first = cache.begin_request("llama3", "old");
+        let second = cache.begin_request("llama3", "new");
+        assert_eq!(cache.finish_request("llama3", first), None);
+        assert!(cache.is_pending("llama3"));
+        assert_eq!(cache.finish_request("llama3", second).as_deref(), Some("new"));
+        assert!(!cache.is_pending("llama3"));
+        assert_eq!(cache.finish_request("llama3", second), None);
+    }
+
+    #[test]
+    fn ids_survive_a_clear() {
+        let mut cache = ShowCache::default();
+        let before = cache.begin_request("llama3", "a");
+        cache.clear();
+        assert!(!cache.is_pending("llama3"));
+        let after = cache.begin_request("llama3", "a");
+        assert_eq!(cache.finish_request("llama3", before), None);
+        assert_eq!(cache.finish_request("llama3", after).as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn retain_drops_removed_and_repulled_models() {
+        let mut cache = ShowCache::default();
+        cache.insert("llama3".into(), "old".into(), response("a"));
+        cache.insert("gone".into(), "1".into(), response("b"));
+        let models = [ModelInfo {
+            name: "llama3".to_string(),
+            modified_at: String::new(),
+            size: 1,
+            digest: "new".to_string(),
+            details: None,
+        }];
+        cache.retain_models(&models);
+        assert!(!cache.contains("llama3", "old"));
+        assert!(!cache.contains("gone", "1"));
+    }
+}
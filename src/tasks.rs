@@ -1,34 +1,146 @@
 use crate::{
-    error::AppError,
+    copy_dialog::CopyMode,
+    error::{ApiError, AppError, Result},
     events::AppEvent,
-    ollama_api::OllamaClient,
-    registry_api,
+    ollama_api::{ChatMessage, CreateModelRequest, OllamaClient},
+    registry_api::{self, RegistryClient},
     tui,
 };
+use std::io::Write;
 use tokio::sync::mpsc;
 
 type EventSender = mpsc::Sender<AppEvent>;
 
 /// Fetches details for a specific model.
-pub async fn fetch_model_details(client: OllamaClient, tx: EventSender, name: String) {
+pub async fn fetch_model_details(client: OllamaClient, tx: EventSender, request_id: u64, name: String) {
     let result = client.show_model_details(&name).await;
     let _ = tx
-        .send(AppEvent::ModelDetailsFetched(result.map_err(AppError::Api)))
+        .send(AppEvent::ModelDetailsFetched(
+            client.host().to_string(),
+            request_id,
+            name,
+            Box::new(result.map_err(AppError::Api)),
+        ))
+        .await;
+}
+
+/// Fetches `/api/show` for each model in turn, for capability badges and filters.
+pub async fn prefetch_model_details(client: OllamaClient, tx: EventSender, requests: Vec<(u64, String)>) {
+    for (request_id, name) in requests {
+        let result = client.show_model_details(&name).await;
+        let event = AppEvent::ModelDetailsFetched(
+            client.host().to_string(),
+            request_id,
+            name,
+            Box::new(result.map_err(AppError::Api)),
+        );
+        if tx.send(event).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Fetches the tensors of a model for the tensor inspector.
+pub async fn fetch_tensors(client: OllamaClient, tx: EventSender, name: String) {
+    let result = client.show_model_details_verbose(&name).await;
+    let _ = tx
+        .send(AppEvent::TensorsFetched(
+            client.host().to_string(),
+            name,
+            result.map(|show| show.tensors).map_err(AppError::Api),
+        ))
+        .await;
+}
+
+/// Fetches the models currently loaded into memory.
+pub async fn fetch_running_models(client: OllamaClient, tx: EventSender) {
+    let result = client.list_running().await;
+    let _ = tx
+        .send(AppEvent::RunningModelsFetched(
+            client.host().to_string(),
+            result.map_err(AppError::Api),
+        ))
+        .await;
+}
+
+/// Loads, pins or unloads a model depending on `keep_alive`.
+pub async fn set_keep_alive(
+    client: OllamaClient,
+    tx: EventSender,
+    model_name: String,
+    keep_alive: Option<i64>,
+) {
+    let result = client.set_keep_alive(&model_name, keep_alive).await;
+    let _ = tx
+        .send(AppEvent::KeepAliveUpdated(
+            model_name,
+            keep_alive,
+            result.map_err(AppError::Api),
+        ))
         .await;
 }
 
 /// Fetches the list of models from the Ollama registry.
-pub async fn fetch_registry_models(tx: EventSender) {
-    let result = registry_api::fetch_registry_models().await;
+pub async fn fetch_registry_models(registry: RegistryClient, tx: EventSender) {
+    let result = registry.fetch_models().await;
     let _ = tx.send(AppEvent::RegistryModelsFetched(result)).await;
 }
 
 /// Fetches the list of tags for a specific model from the Ollama registry.
-pub async fn fetch_registry_tags(tx: EventSender, model_name: String) {
-    let result = registry_api::fetch_registry_tags(&model_name).await;
+pub async fn fetch_registry_tags(registry: RegistryClient, tx: EventSender, model_name: String) {
+    let result = registry.fetch_tags(&model_name).await;
     let _ = tx.send(AppEvent::RegistryTagsFetched(result)).await;
 }
 
+/// Fetches the registry manifest for `model:tag`.
+pub async fn fetch_registry_manifest(
+    registry: RegistryClient,
+    tx: EventSender,
+    model_name: String,
+    tag: String,
+) {
+    let result = registry.fetch_manifest(&model_name, &tag).await;
+    let _ = tx
+        .send(AppEvent::RegistryManifestFetched(model_name, tag, result))
+        .await;
+}
+
+/// Resolves each local model against the registry and reports the remote manifest digest.
+/// Models are checked one at a time to stay polite to the registry.
+pub async fn check_for_updates(registry: RegistryClient, tx: EventSender, model_names: Vec<String>) {
+    for name in model_names {
+        let Some((repository, tag)) = registry_api::split_model_name(&name) else {
+            continue;
+        };
+        let result = registry.fetch_manifest_digest(repository, tag).await;
+        let _ = tx.send(AppEvent::UpdateChecked(name, result)).await;
+    }
+    let _ = tx.send(AppEvent::UpdateCheckFinished).await;
+}
+
+/// Reloads the list of local models.
+pub async fn fetch_local_models(client: OllamaClient, tx: EventSender) {
+    let result = client.list_models().await;
+    let _ = tx
+        .send(AppEvent::LocalModelsRefreshed(
+            client.host().to_string(),
+            result.map_err(AppError::Api),
+        ))
+        .await;
+}
+
+/// Lists the models of one host for the multi-host inventory.
+pub async fn fetch_inventory_host(client: OllamaClient, tx: EventSender, request_id: u64) {
+    let result = client.list_models().await;
+    let _ = tx
+        .send(AppEvent::InventoryFetched(
+            request_id,
+            client.host().to_string(),
+            result.map_err(AppError::Api),
+        ))
+        .await;
+}
+
 /// Deletes a local model and triggers a refresh.
 pub async fn delete_model(client: OllamaClient, tx: EventSender, model_name: String) {
     match client.delete_model(&model_name).await {
@@ -36,68 +148,177 @@ pub async fn delete_model(client: OllamaClient, tx: EventSender, model_name: Str
             let refresh_result = client.list_models().await;
             let _ = tx
                 .send(AppEvent::LocalModelsRefreshed(
+                    client.host().to_string(),
                     refresh_result.map_err(AppError::Api),
                 ))
                 .await;
         }
         Err(e) => {
-            let _ = tx
-                .send(AppEvent::ModelPullCompleted(Err(AppError::Api(e)))) // Reusing event for error reporting
-                .await;
+            let _ = tx.send(AppEvent::ModelDeleteFailed(AppError::Api(e))).await;
         }
     }
 }
 
-/// Pulls a model from the registry and triggers a refresh.
-pub async fn pull_model(
-    client: OllamaClient,
-    tx: EventSender,
-    model: String,
-    tag: String,
-) {
-    let model_tag = format!("{}:{}", model, tag);
+/// Copies or renames a local model, then refreshes the list either way.
+pub async fn copy_model(client: OllamaClient, tx: EventSender, mode: CopyMode, source: String, target: String) {
+    let result = copy_and_verify(&client, mode, &source, &target).await;
+    let refresh_result = client.list_models().await;
+    let _ = tx
+        .send(AppEvent::LocalModelsRefreshed(
+            client.host().to_string(),
+            refresh_result.map_err(AppError::Api),
+        ))
+        .await;
+    let _ = tx
+        .send(AppEvent::ModelCopied(client.host().to_string(), mode, source, target, result))
+        .await;
+}
+
+/// Copies `source` and checks that the copy has the same digest. A rename then deletes `source`,
+/// so a failed or partial copy never loses the model.
+async fn copy_and_verify(client: &OllamaClient, mode: CopyMode, source: &str, target: &str) -> Result<()> {
+    client.copy_model(source, target).await?;
 
-    if let Err(e) = tui::suspend_tui() {
-        eprintln!("Error suspending TUI for pull: {}", e);
-        // Optionally send an error event back?
+    let models = client.list_models().await?;
+    let digest_of = |name: &str| {
+        models
+            .iter()
+            .find(|m| m.name.eq_ignore_ascii_case(name))
+            .map(|m| m.digest.as_str())
+    };
+    let failure = |message: String| Err(AppError::Api(ApiError::ResponseError(message)));
+    match (digest_of(source), digest_of(target)) {
+        (Some(original), Some(copy)) if original == copy => {}
+        (_, None) => return failure(format!("{} isn't listed after copying; {} was kept", target, source)),
+        // Without the source there is nothing to compare with; keep the copy rather than lose both
+        (None, Some(_)) => {
+            return failure(format!("{} isn't listed after copying to {}; both names were kept", source, target));
+        }
+        (Some(_), Some(_)) => {
+            // Remove the mismatching copy so no half-finished alias is left behind
+            return match client.delete_model(target).await {
+                Ok(()) => failure(format!(
+                    "{} doesn't match {} after copying; the copy was removed and {} kept",
+                    target, source, source
+                )),
+                Err(e) => failure(format!(
+                    "{} doesn't match {} after copying, and removing {} failed too ({}); delete it by hand",
+                    target, source, target, e
+                )),
+            };
+        }
     }
 
-    println!("\n--- Starting 'ollama pull {}' ---", model_tag);
-    println!("--- (Application will resume after pull completes) ---");
+    if mode == CopyMode::Rename {
+        client.delete_model(source).await?;
+    }
+    Ok(())
+}
 
-    let command_result = tokio::process::Command::new("ollama")
-        .arg("pull")
-        .arg(&model_tag)
-        .status()
+/// Creates a model, forwarding the server's status lines, and refreshes the list on success.
+pub async fn create_model(client: OllamaClient, tx: EventSender, request_id: u64, request: CreateModelRequest) {
+    let result = stream_create(&client, &tx, request_id, &request).await;
+    if result.is_ok() {
+        let refresh_result = client.list_models().await;
+        let _ = tx
+            .send(AppEvent::LocalModelsRefreshed(
+                client.host().to_string(),
+                refresh_result.map_err(AppError::Api),
+            ))
+            .await;
+    }
+    let _ = tx
+        .send(AppEvent::CreateCompleted(client.host().to_string(), request_id, request.model, result))
         .await;
+}
 
-    if let Err(e) = tui::resume_tui() {
-        eprintln!("Error resuming TUI after pull: {}", e);
-        // Optionally send an error event back?
+async fn stream_create(
+    client: &OllamaClient,
+    tx: &EventSender,
+    request_id: u64,
+    request: &CreateModelRequest,
+) -> Result<()> {
+    let mut stream = client.create_model(request).await?;
+    while let Some(progress) = stream.next().await {
+        let _ = tx.send(AppEvent::CreateProgress(request_id, progress?)).await;
     }
+    Ok(())
+}
 
-    let pull_result = match command_result {
-        Ok(status) if status.success() => Ok(()),
-        Ok(status) => Err(AppError::Command(format!(
-            "ollama pull command failed with status: {}",
-            status
-        ))),
-        Err(e) => Err(AppError::Io(e)),
-    };
+/// Runs a queued pull through the API, forwarding progress, and refreshes the list on success.
+pub async fn pull_model(client: OllamaClient, tx: EventSender, job_id: u64, model_tag: String) {
+    let pull_result = stream_pull(&client, &tx, job_id, &model_tag).await;
+    let succeeded = pull_result.is_ok();
+    let _ = tx.send(AppEvent::DownloadCompleted(job_id, pull_result)).await;
 
-    let _ = tx.send(AppEvent::ModelPullCompleted(pull_result)).await;
+    if succeeded {
+        let refresh_result = client.list_models().await;
+        let _ = tx
+            .send(AppEvent::LocalModelsRefreshed(
+                client.host().to_string(),
+                refresh_result.map_err(AppError::Api),
+            ))
+            .await;
+    }
+}
 
-    // Trigger refresh regardless of pull success/failure
-    let refresh_result = client.list_models().await;
-    let _ = tx
-        .send(AppEvent::LocalModelsRefreshed(
-            refresh_result.map_err(AppError::Api),
-        ))
-        .await;
+/// Reads the `/api/pull` stream to completion, sending each progress line to the main loop.
+async fn stream_pull(
+    client: &OllamaClient,
+    tx: &EventSender,
+    job_id: u64,
+    model_tag: &str,
+) -> Result<()> {
+    let mut stream = client.pull_model(model_tag).await?;
+    while let Some(progress) = stream.next().await {
+        let _ = tx.send(AppEvent::DownloadProgress(job_id, progress?)).await;
+    }
+    Ok(())
 }
 
-/// Runs 'ollama run' for the specified model.
-pub async fn run_ollama(tx: EventSender, model_name: String) {
+/// Streams a chat response, forwarding each token to the chat pane.
+pub async fn stream_chat(
+    client: OllamaClient,
+    tx: EventSender,
+    request_id: u64,
+    model: String,
+    messages: Vec<ChatMessage>,
+) {
+    let result = stream_chat_response(&client, &tx, request_id, &model, messages).await;
+    let _ = tx.send(AppEvent::ChatCompleted(request_id, result)).await;
+}
+
+async fn stream_chat_response(
+    client: &OllamaClient,
+    tx: &EventSender,
+    request_id: u64,
+    model: &str,
+    messages: Vec<ChatMessage>,
+) -> Result<()> {
+    let mut stream = client.chat(model, messages).await?;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        if let Some(message) = chunk.message
+            && !message.content.is_empty()
+        {
+            let _ = tx.send(AppEvent::ChatToken(request_id, message.content)).await;
+        }
+        if chunk.done {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// True if an `ollama` executable is on `PATH`, so `run_ollama` can start it.
+pub fn ollama_cli_available() -> bool {
+    let name = if cfg!(windows) { "ollama.exe" } else { "ollama" };
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+}
+
+/// Runs 'ollama run' for the specified model against `host`.
+pub async fn run_ollama(tx: EventSender, host: String, model_name: String, mouse: bool) {
     let suspend_result = tui::suspend_tui();
     if let Err(e) = &suspend_result {
         eprintln!("Error suspending TUI for run: {}", e);
@@ -112,6 +333,7 @@ pub async fn run_ollama(tx: EventSender, model_name: String) {
             let command_result = std::process::Command::new("ollama")
                 .arg("run")
                 .arg(&model_name)
+                .env("OLLAMA_HOST", &host)
                 .stdin(std::process::Stdio::inherit())
                 .stdout(std::process::Stdio::inherit())
                 .stderr(std::process::Stdio::inherit())
@@ -133,7 +355,7 @@ pub async fn run_ollama(tx: EventSender, model_name: String) {
                 }
             });
 
-            if let Err(e) = tui::resume_tui() {
+            if let Err(e) = tui::resume_tui(mouse) {
                 eprintln!("Error resuming TUI after run: {}", e);
                 // Combine resume error with final_result?
                 // For now, prioritize the command result error.
@@ -144,4 +366,44 @@ pub async fn run_ollama(tx: EventSender, model_name: String) {
     };
 
     let _ = tx.send(AppEvent::OllamaRunCompleted(run_result)).await;
-}
\ No newline at end of file
+}
+
+/// Opens `text` in `$VISUAL` or `$EDITOR` (falling back to `vi`) with the TUI suspended.
+pub async fn edit_externally(tx: EventSender, text: String, mouse: bool) {
+    // The editor owns the terminal until it exits, so wait for it off the async workers
+    let result = tokio::task::spawn_blocking(move || run_editor(&text, mouse))
+        .await
+        .unwrap_or_else(|e| Err(AppError::Command(format!("The editor task failed: {}", e))));
+    let _ = tx.send(AppEvent::ExternalEditClosed(result)).await;
+}
+
+fn run_editor(text: &str, mouse: bool) -> Result<String> {
+    // A fresh file with an unpredictable name, removed when `file` is dropped
+    let mut file = tempfile::Builder::new().prefix("lazyollama-").suffix(".Modelfile").tempfile().map_err(AppError::Io)?;
+    file.write_all(text.as_bytes()).map_err(AppError::Io)?;
+    file.flush().map_err(AppError::Io)?;
+    let path = file.path().to_path_buf();
+
+    // The variable may carry arguments, e.g. `code --wait`
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+
+    tui::suspend_tui()?;
+    let status = std::process::Command::new(program).args(words).arg(&path).status();
+    let resumed = tui::resume_tui(mouse);
+    // Read by path: editors often save by replacing the file rather than writing into it
+    let edited = std::fs::read_to_string(&path);
+    drop(file);
+    resumed?;
+
+    let status = status.map_err(|e| AppError::Command(format!("Couldn't start '{}': {}", program, e)))?;
+    if !status.success() {
+        return Err(AppError::Command(format!("'{}' exited with {}", program, status)));
+    }
+    edited.map_err(AppError::Io)
+}
@@ -0,0 +1,131 @@
+// src/tensors.rs
+// Tensor inspector: the tensors of a verbose `/api/show`, grouped by block, with totals per type.
+
+use crate::ollama_api::TensorInfo;
+use ratatui::widgets::TableState;
+use std::collections::BTreeMap;
+
+/// Tensors of one block (`blk.12`, `v.blk.3`), or of everything outside blocks.
+#[derive(Debug, Clone)]
+pub struct TensorGroup {
+    pub name: String,
+    pub tensors: Vec<TensorInfo>,
+}
+
+impl TensorGroup {
+    pub fn elements(&self) -> u64 {
+        self.tensors.iter().map(TensorInfo::elements).sum()
+    }
+}
+
+/// Tensors of one type (`F32`, `Q4_K`, ...) across the model.
+#[derive(Debug, Clone)]
+pub struct TypeTotal {
+    pub tensor_type: String,
+    pub count: usize,
+    pub elements: u64,
+}
+
+/// A line of the inspector table: a group header or a tensor (group index, tensor index).
+#[derive(Debug, Clone, Copy)]
+pub enum TensorRow {
+    Group(usize),
+    Tensor(usize, usize),
+}
+
+/// Name of the group outside any numbered block, e.g. `token_embd.weight` or `output.weight`.
+pub const UNGROUPED: &str = "other";
+
+/// Group of a tensor name: everything up to its first numeric segment (`blk.0.attn_q.weight` -> `blk.0`).
+fn group_key(name: &str) -> Option<(String, u64)> {
+    let parts: Vec<&str> = name.split('.').collect();
+    let index = parts.iter().position(|p| p.parse::<u64>().is_ok())?;
+    let number = parts[index].parse().ok()?;
+    Some((parts[..index].join("."), number))
+}
+
+#[derive(Debug, Clone)]
+pub struct TensorView {
+    pub model: String,
+    pub groups: Vec<TensorGroup>,
+    /// Biggest share of the weights first.
+    pub totals: Vec<TypeTotal>,
+    pub rows: Vec<TensorRow>,
+    pub is_loading: bool,
+    pub error: Option<String>,
+    pub table_state: TableState,
+    /// Visible table rows, recorded when the view is drawn.
+    pub page_height: u16,
+}
+
+impl TensorView {
+    pub fn new(model: String) -> Self {
+        Self {
+            model,
+            groups: Vec::new(),
+            totals: Vec::new(),
+            rows: Vec::new(),
+            is_loading: true,
+            error: None,
+            table_state: TableState::default(),
+            page_height: 0,
+        }
+    }
+
+    pub fn set_result(&mut self, result: Result<Vec<TensorInfo>, String>) {
+        self.is_loading = false;
+        match result {
+            Ok(tensors) => self.set_tensors(tensors),
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    fn set_tensors(&mut self, tensors: Vec<TensorInfo>) {
+        let mut totals: BTreeMap<String, TypeTotal> = BTreeMap::new();
+        // Ungrouped tensors first, then blocks in numeric order
+        let mut groups: BTreeMap<Option<(String, u64)>, Vec<TensorInfo>> = BTreeMap::new();
+        for tensor in tensors {
+            let total = totals.entry(tensor.tensor_type.clone()).or_insert_with(|| TypeTotal {
+                tensor_type: tensor.tensor_type.clone(),
+                count: 0,
+                elements: 0,
+            });
+            total.count += 1;
+            total.elements += tensor.elements();
+            groups.entry(group_key(&tensor.name)).or_default().push(tensor);
+        }
+
+        self.groups = groups
+            .into_iter()
+            .map(|(key, tensors)| TensorGroup {
+                name: key.map_or_else(|| UNGROUPED.to_string(), |(prefix, n)| format!("{}.{}", prefix, n)),
+                tensors,
+            })
+            .collect();
+        self.totals = totals.into_values().collect();
+        self.totals.sort_by_key(|t| std::cmp::Reverse(t.elements));
+
+        self.rows = self
+            .groups
+            .iter()
+            .enumerate()
+            .flat_map(|(g, group)| {
+                std::iter::once(TensorRow::Group(g)).chain((0..group.tensors.len()).map(move |t| TensorRow::Tensor(g, t)))
+            })
+            .collect();
+        self.table_state.select((!self.rows.is_empty()).then_some(0));
+    }
+
+    pub fn total_elements(&self) -> u64 {
+        self.totals.iter().map(|t| t.elements).sum()
+    }
+
+    /// Moves the selection by `delta` rows without wrapping around.
+    pub fn step(&mut self, delta: i64) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let index = self.table_state.selected().map_or(0, |i| i as i64 + delta);
+        self.table_state.select(Some(index.clamp(0, self.rows.len() as i64 - 1) as usize));
+    }
+}
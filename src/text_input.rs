@@ -0,0 +1,447 @@
+// src/text_input.rs
+// Editable text field shared by the filters, the chat input and the name dialogs.
+// The cursor moves by grapheme, so accents, CJK and emoji are never split.
+
+use crate::keymap::Action;
+use std::collections::VecDeque;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+const KILL_RING_SIZE: usize = 32;
+const HISTORY_SIZE: usize = 100;
+
+/// Text removed by the kill actions. Shared by all inputs, so text can be moved between them.
+#[derive(Debug, Clone, Default)]
+pub struct KillRing {
+    /// Newest first.
+    entries: VecDeque<String>,
+    /// Entry inserted by the last yank; `yank_pop` continues from here.
+    yank_index: usize,
+}
+
+impl KillRing {
+    fn push(&mut self, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        self.entries.push_front(text);
+        self.entries.truncate(KILL_RING_SIZE);
+        self.yank_index = 0;
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    text: String,
+    /// Byte offset into `text`, always on a grapheme boundary.
+    cursor: usize,
+    /// Submitted entries, oldest first.
+    history: Vec<String>,
+    /// History entry being shown, and the unsubmitted text to return to.
+    browsing: Option<(usize, String)>,
+    /// Text inserted by the last yank, replaced by `yank_pop`.
+    last_yank: Option<Range<usize>>,
+}
+
+fn is_word_char(grapheme: &str) -> bool {
+    grapheme.chars().any(char::is_alphanumeric)
+}
+
+fn is_space(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
+
+impl TextInput {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Cursor position as a byte offset into `text()`.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// The text with `cursor` drawn at the cursor position.
+    pub fn with_cursor(&self, cursor: char) -> String {
+        let mut display = self.text.clone();
+        display.insert(self.cursor, cursor);
+        display
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Clears the text. The history is kept.
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+        self.browsing = None;
+        self.last_yank = None;
+    }
+
+    /// Line of the cursor, counting from 0.
+    pub fn cursor_line(&self) -> usize {
+        self.text[..self.cursor].matches('\n').count()
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.text.matches('\n').count() + 1
+    }
+
+    /// Moves the cursor `lines` down (negative: up), keeping its column where the target line is long enough.
+    /// Returns whether it moved.
+    pub fn move_lines(&mut self, lines: isize) -> bool {
+        let current = self.cursor_line();
+        let target = (current as isize + lines).clamp(0, self.line_count() as isize - 1) as usize;
+        if target == current {
+            return false;
+        }
+        let column = self.text[self.line_start(self.cursor)..self.cursor].graphemes(true).count();
+        let start: usize = self.text.split('\n').take(target).map(|line| line.len() + 1).sum();
+        let line = &self.text[start..self.line_end(start)];
+        self.cursor = start + line.graphemes(true).take(column).map(str::len).sum::<usize>();
+        self.last_yank = None;
+        true
+    }
+
+    /// Replaces the text and puts the cursor at its end.
+    pub fn set_text(&mut self, text: &str) {
+        self.clear();
+        self.insert_str(text);
+    }
+
+    /// Returns the text and clears the field.
+    pub fn take(&mut self) -> String {
+        let text = std::mem::take(&mut self.text);
+        self.clear();
+        text
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+        // A combining mark joins the previous grapheme; keep the cursor after it
+        self.cursor = self.next_boundary(self.prev_boundary(self.cursor));
+        self.last_yank = None;
+    }
+
+    fn insert_str(&mut self, s: &str) {
+        self.text.insert_str(self.cursor, s);
+        self.cursor += s.len();
+    }
+
+    /// Adds the current text to the history, unless it is empty or repeats the last entry.
+    pub fn push_history(&mut self) {
+        self.browsing = None;
+        if self.text.trim().is_empty() || self.history.last() == Some(&self.text) {
+            return;
+        }
+        self.history.push(self.text.clone());
+        if self.history.len() > HISTORY_SIZE {
+            self.history.remove(0);
+        }
+    }
+
+    /// Performs an editing action. Returns `None` if `action` isn't one,
+    /// otherwise whether the text changed.
+    pub fn apply(&mut self, action: Action, kill_ring: &mut KillRing) -> Option<bool> {
+        let last_yank = self.last_yank.take();
+        let changed = match action {
+            Action::CursorLeft => self.move_to(self.prev_boundary(self.cursor)),
+            Action::CursorRight => self.move_to(self.next_boundary(self.cursor)),
+            Action::WordLeft => self.move_to(self.word_start_before(self.cursor, is_word_char)),
+            Action::WordRight => self.move_to(self.word_end_after(self.cursor)),
+            Action::LineStart => self.move_to(self.line_start(self.cursor)),
+            Action::LineEnd => self.move_to(self.line_end(self.cursor)),
+            Action::DeleteChar => self.delete(self.prev_boundary(self.cursor)..self.cursor).is_some(),
+            Action::DeleteForward => self.delete(self.cursor..self.next_boundary(self.cursor)).is_some(),
+            // Space-separated like Ctrl+W in a shell, so `size:>4GB` goes in one step
+            Action::DeleteWord => {
+                let start = self.word_start_before(self.cursor, |g| !is_space(g));
+                self.kill(start..self.cursor, kill_ring)
+            }
+            Action::KillToEnd => {
+                // At the end of a line, join it with the next one
+                let end = match self.line_end(self.cursor) {
+                    end if end == self.cursor => self.next_boundary(end),
+                    end => end,
+                };
+                self.kill(self.cursor..end, kill_ring)
+            }
+            Action::KillToStart => self.kill(self.line_start(self.cursor)..self.cursor, kill_ring),
+            Action::Yank => self.yank(kill_ring),
+            Action::YankPop => self.yank_pop(last_yank, kill_ring),
+            Action::HistoryPrev => self.history_prev(),
+            Action::HistoryNext => self.history_next(),
+            Action::ClearInput => {
+                let changed = !self.text.is_empty();
+                self.clear();
+                changed
+            }
+            _ => {
+                self.last_yank = last_yank;
+                return None;
+            }
+        };
+        Some(changed)
+    }
+
+    /// Moves the cursor; never changes the text.
+    fn move_to(&mut self, position: usize) -> bool {
+        self.cursor = position;
+        false
+    }
+
+    fn prev_boundary(&self, position: usize) -> usize {
+        self.text[..position]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self, position: usize) -> usize {
+        self.text[position..]
+            .graphemes(true)
+            .next()
+            .map_or(position, |g| position + g.len())
+    }
+
+    /// Start of the word before `position`: skips graphemes outside words, then those inside.
+    fn word_start_before(&self, position: usize, in_word: fn(&str) -> bool) -> usize {
+        let mut start = position;
+        let mut graphemes = self.text[..position].grapheme_indices(true).rev().peekable();
+        while let Some((i, _)) = graphemes.next_if(|(_, g)| !in_word(g)) {
+            start = i;
+        }
+        while let Some((i, _)) = graphemes.next_if(|(_, g)| in_word(g)) {
+            start = i;
+        }
+        start
+    }
+
+    /// End of the word after `position`, skipping punctuation and spaces first.
+    fn word_end_after(&self, position: usize) -> usize {
+        let mut end = position;
+        let mut graphemes = self.text[position..].graphemes(true).peekable();
+        while let Some(g) = graphemes.next_if(|g| !is_word_char(g)) {
+            end += g.len();
+        }
+        while let Some(g) = graphemes.next_if(|g| is_word_char(g)) {
+            end += g.len();
+        }
+        end
+    }
+
+    fn line_start(&self, position: usize) -> usize {
+        self.text[..position].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self, position: usize) -> usize {
+        self.text[position..].find('\n').map_or(self.text.len(), |i| position + i)
+    }
+
+    /// Removes `range` and puts the cursor where it started.
+    fn delete(&mut self, range: Range<usize>) -> Option<String> {
+        if range.is_empty() {
+            return None;
+        }
+        self.cursor = range.start;
+        Some(self.text.drain(range).collect())
+    }
+
+    fn kill(&mut self, range: Range<usize>, kill_ring: &mut KillRing) -> bool {
+        match self.delete(range) {
+            Some(killed) => {
+                kill_ring.push(killed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn yank(&mut self, kill_ring: &mut KillRing) -> bool {
+        let Some(entry) = kill_ring.entries.front().cloned() else {
+            return false;
+        };
+        kill_ring.yank_index = 0;
+        let start = self.cursor;
+        self.insert_str(&entry);
+        self.last_yank = Some(start..self.cursor);
+        true
+    }
+
+    /// Replaces the text just yanked with the next older kill.
+    fn yank_pop(&mut self, last_yank: Option<Range<usize>>, kill_ring: &mut KillRing) -> bool {
+        let Some(range) = last_yank else {
+            return false;
+        };
+        if kill_ring.entries.len() < 2 {
+            self.last_yank = Some(range);
+            return false;
+        }
+        kill_ring.yank_index = (kill_ring.yank_index + 1) % kill_ring.entries.len();
+        let entry = kill_ring.entries[kill_ring.yank_index].clone();
+        let start = range.start;
+        self.delete(range);
+        self.insert_str(&entry);
+        self.last_yank = Some(start..self.cursor);
+        true
+    }
+
+    fn show(&mut self, text: String) {
+        self.text = text;
+        self.cursor = self.text.len();
+    }
+
+    fn history_prev(&mut self) -> bool {
+        let index = match &self.browsing {
+            None if self.history.is_empty() => return false,
+            None => self.history.len() - 1,
+            Some((0, _)) => return false,
+            Some((i, _)) => i - 1,
+        };
+        let draft = match self.browsing.take() {
+            Some((_, draft)) => draft,
+            None => self.text.clone(),
+        };
+        self.browsing = Some((index, draft));
+        self.show(self.history[index].clone());
+        true
+    }
+
+    fn history_next(&mut self) -> bool {
+        let Some((index, draft)) = self.browsing.take() else {
+            return false;
+        };
+        if index + 1 < self.history.len() {
+            self.show(self.history[index + 1].clone());
+            self.browsing = Some((index + 1, draft));
+        } else {
+            self.show(draft);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replaces the field's text by typing `text`, keeping its history.
+    fn retype(input: &mut TextInput, text: &str) {
+        input.clear();
+        text.chars().for_each(|c| input.insert_char(c));
+    }
+
+    fn typed(text: &str) -> TextInput {
+        let mut input = TextInput::default();
+        retype(&mut input, text);
+        input
+    }
+
+    fn apply(input: &mut TextInput, actions: &[Action]) {
+        let mut kill_ring = KillRing::default();
+        for &action in actions {
+            input.apply(action, &mut kill_ring);
+        }
+    }
+
+    /// Text after the cursor.
+    fn after_cursor(input: &TextInput) -> &str {
+        &input.text()[input.cursor()..]
+    }
+
+    #[test]
+    fn moves_and_deletes_whole_graphemes() {
+        // `e` + combining acute, a family emoji joined by ZWJs, and a CJK character
+        let mut input = typed("e\u{301}👨‍👩‍👧漢");
+        apply(&mut input, &[Action::CursorLeft]);
+        assert_eq!(after_cursor(&input), "漢");
+        apply(&mut input, &[Action::CursorLeft]);
+        assert_eq!(after_cursor(&input), "👨‍👩‍👧漢");
+        apply(&mut input, &[Action::DeleteChar]);
+        assert_eq!(input.text(), "👨‍👩‍👧漢");
+        apply(&mut input, &[Action::DeleteForward]);
+        assert_eq!(input.text(), "漢");
+        apply(&mut input, &[Action::LineEnd, Action::CursorRight]);
+        assert_eq!(input.cursor(), "漢".len());
+    }
+
+    #[test]
+    fn combining_marks_join_the_character_before() {
+        let mut input = typed("ae");
+        apply(&mut input, &[Action::CursorLeft]);
+        input.insert_char('\u{301}');
+        assert_eq!(input.text(), "a\u{301}e");
+        assert_eq!(input.with_cursor('|'), "a\u{301}|e");
+    }
+
+    #[test]
+    fn word_motions_stop_at_punctuation() {
+        let mut input = typed("größe:>4GB café");
+        apply(&mut input, &[Action::WordLeft]);
+        assert_eq!(after_cursor(&input), "café");
+        apply(&mut input, &[Action::WordLeft]);
+        assert_eq!(after_cursor(&input), "4GB café");
+        apply(&mut input, &[Action::WordLeft, Action::WordRight]);
+        assert_eq!(after_cursor(&input), ":>4GB café");
+    }
+
+    #[test]
+    fn kills_can_be_yanked_back() {
+        let mut input = typed("name:llama size:>4GB");
+        let mut kill_ring = KillRing::default();
+        // Ctrl+W removes up to the space, punctuation included
+        input.apply(Action::DeleteWord, &mut kill_ring);
+        assert_eq!(input.text(), "name:llama ");
+        input.apply(Action::LineStart, &mut kill_ring);
+        input.apply(Action::KillToEnd, &mut kill_ring);
+        assert!(input.is_empty());
+
+        input.apply(Action::Yank, &mut kill_ring);
+        assert_eq!(input.text(), "name:llama ");
+        input.apply(Action::YankPop, &mut kill_ring);
+        assert_eq!(input.text(), "size:>4GB");
+        // Only right after a yank
+        input.apply(Action::CursorLeft, &mut kill_ring);
+        assert_eq!(input.apply(Action::YankPop, &mut kill_ring), Some(false));
+        assert_eq!(input.apply(Action::Submit, &mut kill_ring), None);
+    }
+
+    #[test]
+    fn vertical_moves_keep_the_grapheme_column() {
+        let mut input = TextInput::default();
+        input.set_text("日本語\nab\nxyzw");
+        assert_eq!(input.line_count(), 3);
+        apply(&mut input, &[Action::CursorLeft]);
+        assert!(input.move_lines(-2));
+        assert_eq!(input.text()[..input.cursor()].chars().count(), 3);
+        // "ab" is shorter, so the cursor stops at its end
+        assert!(input.move_lines(1));
+        assert_eq!(after_cursor(&input), "\nxyzw");
+        assert!(input.move_lines(-5));
+        assert_eq!(input.cursor_line(), 0);
+        assert!(!input.move_lines(-1));
+    }
+
+    #[test]
+    fn history_returns_to_the_draft() {
+        let mut input = TextInput::default();
+        for entry in ["first", "second", "second", " "] {
+            retype(&mut input, entry);
+            input.push_history();
+        }
+        assert_eq!(input.history, ["first", "second"]);
+
+        retype(&mut input, "draft");
+        apply(&mut input, &[Action::HistoryPrev]);
+        assert_eq!(input.text(), "second");
+        apply(&mut input, &[Action::HistoryPrev, Action::HistoryPrev]);
+        assert_eq!(input.text(), "first");
+        apply(&mut input, &[Action::HistoryNext, Action::HistoryNext]);
+        assert_eq!(input.text(), "draft");
+        assert_eq!(input.take(), "draft");
+        assert!(input.is_empty());
+    }
+}
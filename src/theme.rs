@@ -0,0 +1,94 @@
+// src/theme.rs
+// Colors used by the UI, overridable from the `[theme]` section of the config.
+
+use crate::config::ThemeConfig;
+use ratatui::style::{Color, Modifier, Style};
+use std::str::FromStr;
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Background of the selected row in lists and tables.
+    pub highlight_bg: Color,
+    /// Background of modal dialogs.
+    pub dialog_bg: Color,
+    pub status_bar_bg: Color,
+    /// Host names and other secondary highlights.
+    pub accent: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    /// De-emphasized text such as hints and sizes.
+    pub muted: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            highlight_bg: Color::LightBlue,
+            dialog_bg: Color::DarkGray,
+            status_bar_bg: Color::DarkGray,
+            accent: Color::Magenta,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            muted: Color::Gray,
+        }
+    }
+}
+
+fn parse_color(field: &str, value: &str) -> Result<Color, String> {
+    Color::from_str(value).map_err(|_| {
+        format!(
+            "theme.{}: unknown color '{}' (use a name like \"lightblue\", \"#rrggbb\" or an index 0-255)",
+            field, value
+        )
+    })
+}
+
+impl Theme {
+    /// Applies the configured overrides on top of the default colors.
+    pub fn from_config(config: &ThemeConfig) -> Result<Self, String> {
+        let mut theme = Self::default();
+        let slots = [
+            ("highlight_bg", &config.highlight_bg, &mut theme.highlight_bg),
+            ("dialog_bg", &config.dialog_bg, &mut theme.dialog_bg),
+            ("status_bar_bg", &config.status_bar_bg, &mut theme.status_bar_bg),
+            ("accent", &config.accent, &mut theme.accent),
+            ("success", &config.success, &mut theme.success),
+            ("warning", &config.warning, &mut theme.warning),
+            ("error", &config.error, &mut theme.error),
+            ("muted", &config.muted, &mut theme.muted),
+        ];
+        for (field, value, slot) in slots {
+            if let Some(value) = value {
+                *slot = parse_color(field, value)?;
+            }
+        }
+        Ok(theme)
+    }
+
+    pub fn highlight(&self) -> Style {
+        Style::default()
+            .bg(self.highlight_bg)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Characters matched by a filter.
+    pub fn matched(&self) -> Style {
+        Style::default()
+            .fg(self.accent)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    }
+
+    /// The search match the view is positioned on.
+    pub fn current_match(&self) -> Style {
+        Style::default()
+            .fg(Color::Black)
+            .bg(self.warning)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    pub fn dialog(&self) -> Style {
+        Style::default().bg(self.dialog_bg)
+    }
+}
@@ -13,11 +13,14 @@ use std::io::{self, Stdout};
 pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 
 /// Initializes the terminal for TUI display.
-/// Sets up raw mode, enters alternate screen, and enables mouse capture.
-pub fn init_terminal() -> Result<Tui> {
+/// Sets up raw mode, enters alternate screen, and enables mouse capture if `mouse` is set.
+pub fn init_terminal(mouse: bool) -> Result<Tui> {
     enable_raw_mode().map_err(AppError::Io)?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture).map_err(AppError::Io)?;
+    execute!(stdout, EnterAlternateScreen).map_err(AppError::Io)?;
+    if mouse {
+        execute!(stdout, EnableMouseCapture).map_err(AppError::Io)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     Terminal::new(backend).map_err(AppError::Io)
 }
@@ -38,13 +41,16 @@ pub fn restore_terminal(terminal: &mut Tui) -> Result<()> {
 /// Temporarily suspends the TUI to allow external command execution.
 pub fn suspend_tui() -> Result<()> {
     disable_raw_mode().map_err(AppError::Io)?;
-    execute!(io::stdout(), LeaveAlternateScreen).map_err(AppError::Io)?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture).map_err(AppError::Io)?;
     Ok(())
 }
 
 /// Resumes the TUI after suspension.
-pub fn resume_tui() -> Result<()> {
+pub fn resume_tui(mouse: bool) -> Result<()> {
     execute!(io::stdout(), EnterAlternateScreen).map_err(AppError::Io)?;
+    if mouse {
+        execute!(io::stdout(), EnableMouseCapture).map_err(AppError::Io)?;
+    }
     enable_raw_mode().map_err(AppError::Io)?;
     Ok(())
 }
\ No newline at end of file
@@ -1,127 +1,267 @@
 // src/ui.rs
 // Handles rendering the TUI layout and widgets.
 
-use crate::app::{AppMode, AppState};
+use crate::app::{AppMode, AppState, ManifestStatus, UpdateStatus};
+use crate::chat::{ChatState, ROLE_USER};
+use crate::copy_dialog::CopyDialog;
+use crate::derive::{DeriveEditor, DeriveFocus};
+use crate::details::DetailsTab;
+use crate::downloads::{format_eta, JobState, PullProgressState};
+use crate::fuzzy;
+use crate::inventory::{InventoryState, RowStatus};
+use crate::keymap::{format_sequence, Action, KeyContext};
+use crate::model_table::{format_parameter_count, format_timestamp, modified_time, split_name_tag, ModelColumn};
+use crate::ollama_api::{Capability, ModelInfo, ModelMetadata};
+use crate::param_form::{FormFocus, ParamForm};
+use crate::quantize::{size_comparison, QuantizeDialog, QUANTIZATIONS};
+use crate::tensors::{TensorRow, TensorView};
+use crate::theme::Theme;
+use humansize::{format_size, BINARY};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style, Stylize},
+    style::{Color, Style, Stylize},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Cell, Clear, Gauge, List, ListItem, Paragraph, Row, Table, TableState, Tabs, Wrap},
     Frame,
 };
+use std::ops::Range;
 
 /// Cursor character for filter input fields
 /// Uses ASCII underline character for maximum terminal compatibility
 const CURSOR_CHAR: char = '_';
 
-fn draw_help_modal(f: &mut Frame) {
+fn draw_help_modal(f: &mut Frame, app: &AppState) {
     let block = Block::default()
         .title("Help - Shortcuts")
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::DarkGray));
+        .style(app.theme.dialog());
 
-    let help_text = vec![
-        Line::from(Span::styled("--- General ---", Style::default().bold().underlined())),
-        Line::from("  q          : Quit"),
-        Line::from("  h / ?      : Show/Hide Help"),
-        Line::from(""),
-        Line::from(Span::styled("--- Model List ---", Style::default().bold().underlined())),
-        Line::from("  ↓ / j      : Move Down"),
-        Line::from("  ↑ / k      : Move Up"),
-        Line::from("  d          : Delete Selected Model (Opens Confirm Dialog)"),
-        Line::from("  i          : Install New Model (Opens Install Dialog)"),
-        Line::from("  Enter      : Run Selected Model (Suspends TUI)"),
-        Line::from("  /          : Filter Models (Type to Search)"),
-        Line::from("  Ctrl+C     : Clear Filter"),
-        Line::from(""),
-        Line::from(Span::styled("--- Filter Mode ---", Style::default().bold().underlined())),
-        Line::from("  Type       : Enter Filter Text"),
-        Line::from("  Backspace  : Remove Character"),
-        Line::from("  Enter      : Confirm Filter"),
-        Line::from("  Esc        : Cancel Filter"),
-        Line::from("  Ctrl+C     : Clear Input"),
-        Line::from(""),
-        Line::from(Span::styled("--- Install Mode ---", Style::default().bold().underlined())),
-        Line::from("  ↓ / ↑ / j / k: Navigate Models/Tags"),
-        Line::from("  /          : Filter Registry Models"),
-        Line::from("  Ctrl+C     : Clear Registry Filter"),
-        Line::from("  Enter      : Select Model/Tag"),
-        Line::from("  Esc / q    : Cancel / Go Back"),
-        Line::from(""),
-        Line::from(Span::styled("--- Dialogs ---", Style::default().bold().underlined())),
-        Line::from("  y / Y      : Confirm Action"),
-        Line::from("  n / N / Esc: Cancel / Go Back"),
-        Line::from(""),
-        Line::from(Span::styled("--- Help Dialog ---", Style::default().bold().underlined())),
-        Line::from("  h/?/q/Esc  : Close Help"),
-    ];
+    // Generated from the active keymap so it always matches the real bindings
+    let mut help_text = Vec::new();
+    for (title, entries) in app.keymap.help_sections() {
+        if entries.is_empty() {
+            continue;
+        }
+        help_text.push(Line::from(Span::styled(
+            format!("--- {} ---", title),
+            Style::default().bold().underlined(),
+        )));
+        for (keys, description) in entries {
+            help_text.push(Line::from(format!("  {:<11}: {}", keys, description)));
+        }
+        help_text.push(Line::from(""));
+    }
+
+    let area = centered_rect(80, 80, f.size());
+    let visible = area.height.saturating_sub(2) as usize;
+    let max_scroll = help_text.len().saturating_sub(visible) as u16;
 
     let paragraph = Paragraph::new(help_text)
         .block(block)
-        .wrap(Wrap { trim: false });
-
-    let area = centered_rect(80, 80, f.size());
+        .scroll((app.help_scroll.min(max_scroll), 0));
 
     f.render_widget(Clear, area);
     f.render_widget(paragraph, area);
 }
 
-pub fn draw(f: &mut Frame, app: &AppState) {
-    // Main layout: 90% for content, 10% for status bar
+/// Where the main screen's panels are. Shared by drawing and mouse hit-testing.
+pub struct ScreenLayout {
+    pub model_list: Rect,
+    /// Filter input above the model list, while filtering.
+    pub filter: Option<Rect>,
+    /// Details or chat pane.
+    pub side_pane: Rect,
+    pub status_bar: Rect,
+}
+
+pub fn screen_layout(area: Rect, app: &AppState) -> ScreenLayout {
+    // Main layout: content, then a one-line status bar
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
-        .split(f.size());
+        .split(area);
 
     let main_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)].as_ref())
         .split(chunks[0]);
 
-    draw_model_list(f, app, main_chunks[0]);
-    draw_model_details(f, app, main_chunks[1]);
-    draw_status_bar(f, app, chunks[1]);
+    // Split the model list area to include filter input if in filter mode
+    let (model_list, filter) = if app.current_mode == AppMode::Filter {
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(main_chunks[0]);
+        (split[1], Some(split[0]))
+    } else {
+        (main_chunks[0], None)
+    };
+
+    ScreenLayout { model_list, filter, side_pane: main_chunks[1], status_bar: chunks[1] }
+}
+
+/// List and filter input areas of the registry model dialog.
+pub fn registry_dialog_areas(area: Rect, app: &AppState) -> (Rect, Option<Rect>) {
+    let dialog = centered_rect(70, 50, area);
+    if app.current_mode == AppMode::InstallSelectModelFilter {
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(dialog);
+        (split[1], Some(split[0]))
+    } else {
+        (dialog, None)
+    }
+}
+
+pub fn tag_dialog_area(area: Rect) -> Rect {
+    centered_rect(60, 50, area)
+}
+
+/// Index of the entry a bordered list or table shows at screen row `row`.
+/// Lists are drawn from a fresh offset each frame, so the selection ends up on the last visible row
+/// once it is past the first page.
+pub fn list_row_at(area: Rect, header_rows: u16, row: u16, selected: Option<usize>, len: usize) -> Option<usize> {
+    let first_row = area.y + 1 + header_rows;
+    let visible = area.height.saturating_sub(2 + header_rows) as usize;
+    if row < first_row || (row - first_row) as usize >= visible {
+        return None;
+    }
+    let offset = selected.map_or(0, |s| (s + 1).saturating_sub(visible));
+    let index = offset + (row - first_row) as usize;
+    (index < len).then_some(index)
+}
+
+pub fn draw(f: &mut Frame, app: &mut AppState) {
+    let layout = screen_layout(f.size(), app);
+
+    draw_model_list(f, app, layout.model_list, layout.filter);
+    match (&app.current_mode, &app.chat) {
+        (AppMode::Chat, Some(chat)) => draw_chat(f, chat, &app.theme, layout.side_pane),
+        (AppMode::Derive, _) if app.derive.is_some() => {
+            if let Some(editor) = app.derive.as_mut() {
+                draw_derive_editor(f, editor, &app.models, &app.theme, layout.side_pane);
+            }
+        }
+        (AppMode::ParamForm, _) if app.param_form.is_some() => {
+            if let Some(form) = app.param_form.as_mut() {
+                draw_param_form(f, form, &app.models, &app.theme, layout.side_pane);
+            }
+        }
+        _ => draw_model_details(f, app, layout.side_pane),
+    }
+    draw_status_bar(f, app, layout.status_bar);
 
     // --- Render Modals ---
     match app.current_mode {
         AppMode::ConfirmDelete => {
             if let Some(model_name) = app.get_selected_model_name() {
-                draw_confirmation_dialog(f, &model_name);
+                draw_confirmation_dialog(f, &app.theme, &format!("'{}'", model_name));
             }
         }
         AppMode::InstallSelectModel => draw_install_model_select_dialog(f, app),
         AppMode::InstallSelectModelFilter => draw_install_model_select_dialog(f, app),
         AppMode::InstallSelectTag => draw_install_tag_select_dialog(f, app),
         AppMode::InstallConfirm => draw_install_confirm_dialog(f, app),
-        AppMode::Help => draw_help_modal(f),
+        AppMode::Downloads => draw_downloads_panel(f, app),
+        AppMode::RunningModels => draw_running_models_panel(f, app),
+        AppMode::HostSelect => draw_host_select_dialog(f, app),
+        AppMode::Inventory => {
+            if let Some(inventory) = &app.inventory {
+                draw_inventory_panel(f, inventory, &app.theme);
+            }
+        }
+        AppMode::InventoryConfirmDelete => {
+            if let Some(inventory) = &app.inventory {
+                draw_inventory_panel(f, inventory, &app.theme);
+                if let (Some(row), Some(host)) = (inventory.selected_row(), inventory.selected_host()) {
+                    draw_confirmation_dialog(f, &app.theme, &format!("'{}' on {}", row.model, host.name));
+                }
+            }
+        }
+        AppMode::Help => draw_help_modal(f, app),
+        AppMode::CopyInput => {
+            if let Some(dialog) = &app.copy_dialog {
+                draw_copy_dialog(f, dialog, &app.models, &app.theme);
+            }
+        }
+        AppMode::Tensors => {
+            let theme = app.theme.clone();
+            if let Some(view) = app.tensors.as_mut() {
+                draw_tensors_panel(f, view, &theme);
+            }
+        }
+        AppMode::Quantize => {
+            if let Some(dialog) = app.quantize.as_mut() {
+                draw_quantize_dialog(f, dialog, &app.models, &app.theme);
+            }
+        }
         _ => {}
     }
     // --- End Render Modals ---
 }
 
-fn draw_model_list(f: &mut Frame, app: &AppState, area: Rect) {
-    // Split the model list area to include filter input if in filter mode
-    let (list_area, filter_area) = if app.current_mode == AppMode::Filter {
-        let split = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
-            .split(area);
-        (split[1], Some(split[0]))
-    } else {
-        (area, None)
-    };
+fn draw_model_list(f: &mut Frame, app: &AppState, list_area: Rect, filter_area: Option<Rect>) {
+    let theme = &app.theme;
 
     // Get the current models (filtered or full list)
     let current_models = app.get_current_models();
-    let items: Vec<ListItem> = current_models
+    let now = chrono::Utc::now();
+    let columns = &app.model_columns;
+    // Without a tag column the name column shows the full `model:tag`
+    let show_tag = columns.contains(&ModelColumn::Tag);
+
+    let header = Row::new(columns.iter().map(|column| {
+        if *column == app.model_sort.column {
+            Cell::from(format!("{} {}", column.title(), app.model_sort.indicator()))
+                .style(Style::default().fg(theme.accent))
+        } else {
+            Cell::from(column.title())
+        }
+    }))
+    .style(Style::default().bold());
+
+    let rows: Vec<Row> = current_models
         .iter()
         .map(|m| {
-            ListItem::new(Line::from(Span::styled(
-                m.name.clone(),
-                Style::default(),
-            )))
+            let matched = app.filter_matches.get(&m.name).map(Vec::as_slice).unwrap_or_default();
+            // Offset of the tag within the full name, if the name has one
+            let tag_offset = (split_name_tag(&m.name).0.len() < m.name.len())
+                .then(|| split_name_tag(&m.name).0.chars().count() + 1);
+            Row::new(columns.iter().map(|column| {
+                if *column == ModelColumn::Tag
+                    && let Some(offset) = tag_offset
+                {
+                    let text = column.cell(m, now);
+                    return Cell::from(Line::from(highlight_matches(&text, matched, offset, theme)));
+                }
+                if *column != ModelColumn::Name {
+                    return Cell::from(column.cell(m, now));
+                }
+                let text = if show_tag { column.cell(m, now) } else { m.name.clone() };
+                // Mark models that are currently loaded into memory
+                let marker = if app.is_model_running(&m.name) {
+                    Span::styled("● ", Style::default().fg(theme.success))
+                } else {
+                    Span::raw("  ")
+                };
+                let mut spans = vec![marker];
+                spans.extend(highlight_matches(&text, matched, 0, theme));
+                if app.is_model_outdated(m) {
+                    spans.push(Span::styled(" ↑", Style::default().fg(theme.warning)));
+                }
+                if let Some(show) = app.show_cache.peek(&m.name, &m.digest) {
+                    let badges = capability_badges(&show.capabilities, false, theme);
+                    if !badges.is_empty() {
+                        spans.push(Span::raw(" "));
+                        spans.extend(badges);
+                    }
+                }
+                Cell::from(Line::from(spans))
+            }))
         })
         .collect();
+    let widths: Vec<Constraint> = columns.iter().map(|c| model_column_width(*c)).collect();
 
     // Create title with filter indicator
     let title = if app.is_filtered {
@@ -130,17 +270,14 @@ fn draw_model_list(f: &mut Frame, app: &AppState, area: Rect) {
         "Models".to_string()
     };
 
-    let list = List::new(items)
+    let table = Table::new(rows, widths)
+        .header(header)
         .block(Block::default().borders(Borders::ALL).title(title))
-        .highlight_style(
-            Style::default()
-                .bg(Color::LightBlue)
-                .add_modifier(Modifier::BOLD),
-        )
+        .highlight_style(theme.highlight())
         .highlight_symbol("> ");
 
-    let mut list_state = app.list_state.clone();
-    f.render_stateful_widget(list, list_area, &mut list_state);
+    let mut table_state = app.list_state.clone();
+    f.render_stateful_widget(table, list_area, &mut table_state);
 
     // Draw filter input if in filter mode
     if let Some(filter_area) = filter_area {
@@ -148,126 +285,678 @@ fn draw_model_list(f: &mut Frame, app: &AppState, area: Rect) {
     }
 }
 
+/// Splits `text` into spans, styling the chars whose index plus `offset` is in `positions`.
+fn highlight_matches(text: &str, positions: &[usize], offset: usize, theme: &Theme) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in text.chars().enumerate() {
+        let matched = positions.binary_search(&(i + offset)).is_ok();
+        if matched != run_matched && !run.is_empty() {
+            let run = std::mem::take(&mut run);
+            spans.push(if run_matched { Span::styled(run, theme.matched()) } else { Span::raw(run) });
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(if run_matched { Span::styled(run, theme.matched()) } else { Span::raw(run) });
+    }
+    spans
+}
+
+fn model_column_width(column: ModelColumn) -> Constraint {
+    match column {
+        ModelColumn::Name => Constraint::Min(16),
+        ModelColumn::Tag => Constraint::Min(8),
+        ModelColumn::Size => Constraint::Length(10),
+        ModelColumn::Parameters => Constraint::Length(8),
+        ModelColumn::Quantization => Constraint::Length(8),
+        ModelColumn::Family => Constraint::Length(8),
+        ModelColumn::Modified => Constraint::Length(14),
+    }
+}
+
 fn draw_filter_input(f: &mut Frame, app: &AppState, area: Rect) {
+    let theme = &app.theme;
     let input_style = if app.current_mode == AppMode::Filter {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.warning)
     } else {
         Style::default()
     };
 
+    let title = match app.filter_query.errors.first() {
+        Some(error) => Line::from(vec![
+            Span::raw("Filter "),
+            Span::styled(format!("({})", error.message), Style::default().fg(theme.error)),
+        ]),
+        None => Line::from("Filter"),
+    };
     let block = Block::default()
         .borders(Borders::ALL)
-        .title("Filter")
+        .title(title)
         .border_style(input_style);
 
-    // Create the input display with cursor
-    let mut input_display = app.filter_input.clone();
-    if app.current_mode == AppMode::Filter {
-        // Insert cursor character at cursor position (using ASCII-safe cursor)
-        let cursor_pos = std::cmp::min(app.filter_cursor_pos, input_display.len());
-        input_display.insert(cursor_pos, CURSOR_CHAR);
+    // Invalid terms are underlined in the error color
+    let error_style = Style::default().fg(theme.error).underlined();
+    let is_error = |i: usize| app.filter_query.errors.iter().any(|e| e.range.contains(&i));
+    let input = app.filter_input.text();
+    let cursor_pos = (app.current_mode == AppMode::Filter).then(|| app.filter_input.cursor());
+
+    let mut spans: Vec<Span> = Vec::new();
+    let mut run = String::new();
+    let mut run_is_error = false;
+    for (i, c) in input.char_indices().chain(std::iter::once((input.len(), '\0'))) {
+        let at_cursor = cursor_pos == Some(i);
+        let at_end = i == input.len();
+        let char_is_error = !at_end && is_error(i);
+        if (at_cursor || at_end || char_is_error != run_is_error) && !run.is_empty() {
+            let style = if run_is_error { error_style } else { input_style };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        if at_cursor {
+            spans.push(Span::styled(CURSOR_CHAR.to_string(), input_style));
+        }
+        if !at_end {
+            run_is_error = char_is_error;
+            run.push(c);
+        }
     }
 
-    let input_paragraph = Paragraph::new(input_display)
+    let input_paragraph = Paragraph::new(Line::from(spans))
         .block(block)
         .style(input_style);
 
     f.render_widget(input_paragraph, area);
 }
 
-fn draw_model_details(f: &mut Frame, app: &AppState, area: Rect) {
-    let block = Block::default().borders(Borders::ALL).title("Details");
-
+/// Overview tab: list entry, update status and the `details` block of `/api/show`.
+fn overview_lines(app: &AppState) -> Vec<Line<'static>> {
+    let theme = &app.theme;
     let mut text_lines: Vec<Line> = Vec::new();
+    let Some(basic_info) = app.list_state.selected().and_then(|i| app.get_current_models().get(i).cloned()) else {
+        text_lines.push(Line::from("Select a model to see details."));
+        return text_lines;
+    };
 
-    if let Some(selected_index) = app.list_state.selected() {
-        let current_models = app.get_current_models();
-        if let Some(basic_info) = current_models.get(selected_index) {
-            // Display basic info first
-            text_lines.push(Line::from(vec![
-                Span::styled("Name: ", Style::default().bold()),
-                Span::raw(basic_info.name.clone()),
-            ]));
-            text_lines.push(Line::from(vec![
-                Span::styled("Size: ", Style::default().bold()),
-                Span::raw(basic_info.size_formatted()),
-            ]));
-            text_lines.push(Line::from(vec![
-                Span::styled("Modified: ", Style::default().bold()),
-                Span::raw(basic_info.modified_at.clone()),
-            ]));
-            text_lines.push(Line::from(vec![
-                Span::styled("Digest: ", Style::default().bold()),
-                Span::raw(basic_info.digest.chars().take(12).collect::<String>() + "..."),
-            ]));
-            text_lines.push(Line::from(""));
-
-            // Check if detailed info is available
-            if let Some(details) = &app.selected_model_details {
-                text_lines.push(Line::from(Span::styled("--- Details ---", Style::default().italic())));
+    text_lines.push(detail_field("Name", basic_info.name.clone()));
+    text_lines.push(detail_field("Size", basic_info.size_formatted()));
+    // The list entry's time is shown until `/api/show` has answered
+    let modified = app
+        .selected_model_details()
+        .and_then(|d| d.modified_at)
+        .or_else(|| modified_time(&basic_info));
+    text_lines.push(detail_field(
+        "Modified",
+        match modified {
+            Some(time) => format_timestamp(time, chrono::Utc::now()),
+            None => basic_info.modified_at.clone(),
+        },
+    ));
+    text_lines.push(detail_field("Digest", basic_info.digest.chars().take(12).collect::<String>() + "..."));
+    if let Some(update) = app.update_status.get(&basic_info.name) {
+        let update_span = match update {
+            UpdateStatus::Checking => Span::styled("checking...", Style::default().italic()),
+            UpdateStatus::Checked(remote) if *remote != basic_info.digest => {
+                Span::styled("update available", Style::default().fg(theme.warning))
+            }
+            UpdateStatus::Checked(_) => Span::styled("up to date", Style::default().fg(theme.success)),
+            UpdateStatus::Failed(err) => Span::styled(format!("unknown ({})", err), Style::default().fg(theme.muted)),
+        };
+        text_lines.push(Line::from(vec![Span::styled("Update: ", Style::default().bold()), update_span]));
+    }
+    text_lines.push(Line::from(""));
 
-                if let Some(extra) = &details.details {
-                    if let Some(val) = &extra.family { 
-                        text_lines.push(Line::from(vec![Span::styled("Family: ", Style::default().bold()), Span::raw(val)])); 
-                    }
-                    if let Some(val) = &extra.format { 
-                        text_lines.push(Line::from(vec![Span::styled("Format: ", Style::default().bold()), Span::raw(val)])); 
-                    }
-                    if let Some(val) = &extra.parameter_size { 
-                        text_lines.push(Line::from(vec![Span::styled("Param Size: ", Style::default().bold()), Span::raw(val)])); 
-                    }
-                    if let Some(val) = &extra.quantization_level { 
-                        text_lines.push(Line::from(vec![Span::styled("Quant Level: ", Style::default().bold()), Span::raw(val)])); 
-                    }
-                    if let Some(families) = &extra.families {
-                        if !families.is_empty() {
-                            text_lines.push(Line::from(vec![Span::styled("Families: ", Style::default().bold()), Span::raw(families.join(", "))]));
-                        }
+    match app.selected_model_details() {
+        Some(details) => {
+            if let Some(extra) = &details.details {
+                let values = [
+                    ("Family", extra.family.clone()),
+                    ("Format", extra.format.clone()),
+                    ("Param Size", extra.parameter_size.clone()),
+                    ("Quant Level", extra.quantization_level.clone()),
+                    ("Families", extra.families.as_ref().filter(|f| !f.is_empty()).map(|f| f.join(", "))),
+                ];
+                for (label, value) in values {
+                    if let Some(value) = value {
+                        text_lines.push(detail_field(label, value));
                     }
                 }
+            }
+            if let Some(length) = details.model_info.as_ref().and_then(|i| i.context_length) {
+                text_lines.push(detail_field("Context Length", length.to_string()));
+            }
+            if !details.capabilities.is_empty() {
+                let mut spans = vec![Span::styled("Capabilities: ", Style::default().bold())];
+                spans.extend(capability_badges(&details.capabilities, true, theme));
+                text_lines.push(Line::from(spans));
+            }
+        }
+        None => text_lines.push(fetching_line(app)),
+    }
+    text_lines
+}
 
-                if let Some(val) = &details.parameters { 
-                    text_lines.push(Line::from("")); 
-                    text_lines.push(Line::from(Span::styled("Parameters:", Style::default().bold()))); 
-                    text_lines.push(Line::from(Span::raw(val.clone()))); 
-                }
-                if let Some(val) = &details.template { 
-                    text_lines.push(Line::from("")); 
-                    text_lines.push(Line::from(Span::styled("Template:", Style::default().bold()))); 
-                    text_lines.push(Line::from(Span::raw(val.clone()))); 
-                }
-                if let Some(val) = &details.modelfile { 
-                    text_lines.push(Line::from("")); 
-                    text_lines.push(Line::from(Span::styled("Modelfile:", Style::default().bold()))); 
-                    text_lines.push(Line::from(Span::raw(val.clone()))); 
-                }
-                if let Some(val) = &details.license { 
-                    text_lines.push(Line::from("")); 
-                    text_lines.push(Line::from(Span::styled("License:", Style::default().bold()))); 
-                    text_lines.push(Line::from(Span::raw(val.clone()))); 
-                }
-            } else {
-                if let Some(status) = &app.status_message {
-                    if status.contains("Fetching") {
-                        text_lines.push(Line::from(Span::styled("Fetching details...", Style::default().italic())));
-                    }
-                }
+fn fetching_line(app: &AppState) -> Line<'static> {
+    if app.is_fetching_details() {
+        Line::from(Span::styled("Fetching details...", Style::default().italic()))
+    } else {
+        Line::from("")
+    }
+}
+
+fn detail_field(label: &str, value: String) -> Line<'static> {
+    Line::from(vec![Span::styled(format!("{}: ", label), Style::default().bold()), Span::raw(value)])
+}
+
+/// Badges for the capabilities of a model, e.g. `vision tools`.
+/// `all` includes plain completion, which the model list leaves out.
+fn capability_badges(capabilities: &[Capability], all: bool, theme: &Theme) -> Vec<Span<'static>> {
+    let style = Style::default().fg(theme.accent).bold();
+    let labels = capabilities
+        .iter()
+        .filter_map(|c| if all { Some(c.name()) } else { c.badge() });
+    let mut spans = Vec::new();
+    for label in labels {
+        if !spans.is_empty() {
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::styled(label.to_string(), style));
+    }
+    spans
+}
+
+/// The typed fields first, then every other key. Long arrays such as token lists are summarized.
+fn model_info_lines(info: &ModelMetadata) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    if let Some(architecture) = &info.architecture {
+        lines.push(detail_field("Architecture", architecture.clone()));
+    }
+    let numbers = [
+        ("Parameters", info.parameter_count.map(format_parameter_count)),
+        ("Context Length", info.context_length.map(|n| n.to_string())),
+        ("Embedding Length", info.embedding_length.map(|n| n.to_string())),
+        ("Layers", info.block_count.map(|n| n.to_string())),
+        ("Attention Heads", info.head_count.map(|n| n.to_string())),
+        ("KV Heads", info.head_count_kv.map(|n| n.to_string())),
+        ("Vocab Size", info.vocab_size.map(|n| n.to_string())),
+    ];
+    for (label, value) in numbers {
+        if let Some(value) = value {
+            lines.push(detail_field(label, value));
+        }
+    }
+    if !lines.is_empty() && !info.other.is_empty() {
+        lines.push(Line::from(""));
+    }
+    for (key, value) in &info.other {
+        let value = match value {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Array(items) if items.len() > 8 => format!("[{} items]", items.len()),
+            other => other.to_string(),
+        };
+        lines.push(detail_field(key, value));
+    }
+    lines
+}
+
+fn details_tab_lines(app: &AppState, tab: DetailsTab) -> Vec<Line<'static>> {
+    let text = match (tab, app.selected_model_details()) {
+        (DetailsTab::Overview, _) => return overview_lines(app),
+        _ if app.list_state.selected().is_none() => return vec![Line::from("Select a model to see details.")],
+        (_, None) => return vec![fetching_line(app)],
+        (DetailsTab::Parameters, Some(details)) => details.parameters.as_deref(),
+        (DetailsTab::Template, Some(details)) => details.template.as_deref(),
+        (DetailsTab::Modelfile, Some(details)) => details.modelfile.as_deref(),
+        (DetailsTab::License, Some(details)) => details.license.as_deref(),
+        (DetailsTab::ModelInfo, Some(details)) => {
+            let lines = details.model_info.as_ref().map(model_info_lines).unwrap_or_default();
+            if !lines.is_empty() {
+                return lines;
             }
-        } else {
-            text_lines.push(Line::from("Error: Selected index out of bounds."));
+            None
         }
+    };
+    match text.filter(|t| !t.trim().is_empty()) {
+        Some(text) => text.lines().map(|l| Line::from(l.to_string())).collect(),
+        None => vec![Line::from(Span::styled(
+            format!("No {} for this model.", tab.title().to_lowercase()),
+            Style::default().fg(app.theme.muted).italic(),
+        ))],
+    }
+}
+
+/// Restyles the chars of `line` inside `ranges` (char indices across all spans).
+fn highlight_ranges(mut line: Line<'static>, ranges: &[(Range<usize>, Style)]) -> Line<'static> {
+    if ranges.is_empty() {
+        return line;
+    }
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for span in std::mem::take(&mut line.spans) {
+        let mut run = String::new();
+        let mut run_patch: Option<Style> = None;
+        for c in span.content.chars() {
+            let patch = ranges.iter().find(|(r, _)| r.contains(&offset)).map(|(_, style)| *style);
+            if patch != run_patch && !run.is_empty() {
+                let style = run_patch.map_or(span.style, |p| span.style.patch(p));
+                spans.push(Span::styled(std::mem::take(&mut run), style));
+            }
+            run_patch = patch;
+            run.push(c);
+            offset += 1;
+        }
+        if !run.is_empty() {
+            spans.push(Span::styled(run, run_patch.map_or(span.style, |p| span.style.patch(p))));
+        }
+    }
+    Line { spans, ..line }
+}
+
+/// Tab titles for a tab bar `width` columns wide, shortened if the full ones don't fit.
+fn details_tab_titles(width: u16) -> Vec<&'static str> {
+    // Each title is padded by a space on both sides, with a divider in between
+    let bar_width = |titles: &[&str]| titles.iter().map(|t| t.chars().count() + 3).sum::<usize>() - 1;
+    let titles: Vec<&str> = DetailsTab::ALL.iter().map(|t| t.title()).collect();
+    if bar_width(&titles) <= width as usize {
+        titles
     } else {
-        text_lines.push(Line::from("Select a model to see details."));
+        DetailsTab::ALL.iter().map(|t| t.short_title()).collect()
+    }
+}
+
+/// Tab under screen position (`column`, `row`) of the details pane, if any.
+pub fn details_tab_at(area: Rect, column: u16, row: u16) -> Option<DetailsTab> {
+    if row != area.y + 1 {
+        return None;
+    }
+    let mut x = area.x + 1;
+    for (tab, title) in DetailsTab::ALL.into_iter().zip(details_tab_titles(area.width.saturating_sub(2))) {
+        let end = x + title.chars().count() as u16 + 2;
+        if (x..end).contains(&column) {
+            return Some(tab);
+        }
+        x = end + 1;
+    }
+    None
+}
+
+/// Draws the tabbed details pane and records its scroll limits and search matches in `app.details`.
+fn draw_model_details(f: &mut Frame, app: &mut AppState, area: Rect) {
+    let theme = app.theme.clone();
+    let border_style = if matches!(app.current_mode, AppMode::Details | AppMode::DetailsSearch) {
+        Style::default().fg(theme.warning)
+    } else {
+        Style::default()
+    };
+    let block = Block::default().borders(Borders::ALL).title("Details").border_style(border_style);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let searching = app.current_mode == AppMode::DetailsSearch || app.details.is_searching();
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [Constraint::Length(1), Constraint::Min(0), Constraint::Length(u16::from(searching))].as_ref(),
+        )
+        .split(inner);
+
+    let titles = details_tab_titles(rows[0].width);
+    let selected_tab = DetailsTab::ALL.iter().position(|&t| t == app.details.tab).unwrap_or(0);
+    let tabs = Tabs::new(titles)
+        .select(selected_tab)
+        .style(Style::default().fg(theme.muted))
+        .highlight_style(Style::default().fg(theme.accent).bold().underlined());
+    f.render_widget(tabs, rows[0]);
+
+    let content_area = rows[1];
+    let width = content_area.width.max(1) as usize;
+    let lines = details_tab_lines(app, app.details.tab);
+
+    // Case-insensitive matches of the search, in reading order
+    let pattern = app.details.search.text().to_string();
+    let matches: Vec<(usize, Range<usize>)> = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+            fuzzy::find_all(&pattern, &text).into_iter().map(move |r| (i, r))
+        })
+        .collect();
+    let pane = &mut app.details;
+    pane.match_count = matches.len();
+    pane.current_match = pane.current_match.min(matches.len().saturating_sub(1));
+
+    // Rows are estimated like the wrapping `Paragraph` lays them out
+    let mut line_rows = Vec::with_capacity(lines.len());
+    let mut wrapped_height = 0;
+    for line in &lines {
+        line_rows.push(wrapped_height);
+        wrapped_height += line.width().max(1).div_ceil(width);
     }
+    pane.page_height = content_area.height;
+    pane.max_scroll = (wrapped_height as u16).saturating_sub(content_area.height);
+    if pane.reveal_match {
+        pane.reveal_match = false;
+        if let Some((line, range)) = matches.get(pane.current_match) {
+            let row = (line_rows[*line] + range.start / width) as u16;
+            let height = content_area.height.max(1);
+            if row < pane.scroll() || row >= pane.scroll() + height {
+                pane.set_scroll(row.saturating_sub(height / 3));
+            }
+        }
+    }
+    let scroll = pane.scroll().min(pane.max_scroll);
+
+    let current = pane.current_match;
+    let text_lines: Vec<Line> = lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let ranges: Vec<(Range<usize>, Style)> = matches
+                .iter()
+                .enumerate()
+                .filter(|(_, (l, _))| *l == i)
+                .map(|(n, (_, r))| (r.clone(), if n == current { theme.current_match() } else { theme.matched() }))
+                .collect();
+            highlight_ranges(line, &ranges)
+        })
+        .collect();
 
     let paragraph = Paragraph::new(Text::from(text_lines))
-        .block(block)
-        .wrap(Wrap { trim: false });
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    f.render_widget(paragraph, content_area);
 
-    f.render_widget(paragraph, area);
+    if searching {
+        let editing = app.current_mode == AppMode::DetailsSearch;
+        let input = if editing { app.details.search.with_cursor(CURSOR_CHAR) } else { pattern.clone() };
+        let count = match app.details.match_count {
+            0 if !pattern.is_empty() => Span::styled("  no matches", Style::default().fg(theme.error)),
+            0 => Span::raw(""),
+            n => Span::styled(format!("  {}/{}", app.details.current_match + 1, n), Style::default().fg(theme.muted)),
+        };
+        let input_style = if editing { Style::default().fg(theme.warning) } else { Style::default() };
+        let search_line = Line::from(vec![Span::styled(format!("/{}", input), input_style), count]);
+        f.render_widget(Paragraph::new(search_line), rows[2]);
+    }
+}
+
+fn draw_chat(f: &mut Frame, chat: &ChatState, theme: &Theme, area: Rect) {
+    // Input box grows with the number of lines typed, up to a limit
+    let input_lines = chat.input.text().split('\n').count() as u16;
+    let visible_input_lines = input_lines.clamp(1, 6);
+    let input_height = visible_input_lines + 2;
+    let split = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(input_height)].as_ref())
+        .split(area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    for (i, message) in chat.messages.iter().enumerate() {
+        let (label, style) = if message.role == ROLE_USER {
+            ("You".to_string(), Style::default().fg(Color::Cyan).bold())
+        } else {
+            (chat.model.clone(), Style::default().fg(theme.success).bold())
+        };
+        lines.push(Line::from(Span::styled(format!("{}:", label), style)));
+        for text_line in message.content.split('\n') {
+            lines.push(Line::from(text_line.to_string()));
+        }
+        if chat.interrupted.contains(&i) {
+            lines.push(Line::from(Span::styled("(stopped; not sent back to the model)", Style::default().italic().fg(theme.muted))));
+        }
+        lines.push(Line::from(""));
+    }
+    if chat.is_streaming() {
+        lines.push(Line::from(Span::styled("generating...", Style::default().italic().fg(theme.muted))));
+    }
+    if let Some(err) = &chat.error {
+        lines.push(Line::from(Span::styled(format!("Error: {}", err), Style::default().fg(theme.error))));
+    }
+
+    // Follow the end of the transcript unless the user scrolled up
+    let inner_width = split[0].width.saturating_sub(2).max(1) as usize;
+    let inner_height = split[0].height.saturating_sub(2);
+    let wrapped_height: usize = lines
+        .iter()
+        .map(|l| l.width().max(1).div_ceil(inner_width))
+        .sum();
+    let max_scroll = (wrapped_height as u16).saturating_sub(inner_height);
+    let scroll = max_scroll.saturating_sub(chat.scroll_from_bottom);
+
+    let transcript = Paragraph::new(Text::from(lines))
+        .block(Block::default().borders(Borders::ALL).title(match &chat.host {
+            Some(host) => format!("Chat: {} on {}", chat.model, host.name),
+            None => format!("Chat: {}", chat.model),
+        }))
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    f.render_widget(transcript, split[0]);
+
+    // Keep the cursor's line in view once the input is taller than the box
+    let cursor_line = chat.input.text()[..chat.input.cursor()].matches('\n').count() as u16;
+    let input_scroll = cursor_line.saturating_sub(visible_input_lines - 1);
+    let input = Paragraph::new(chat.input.with_cursor(CURSOR_CHAR))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Message")
+                .border_style(Style::default().fg(theme.warning)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((input_scroll, 0));
+    f.render_widget(input, split[1]);
+}
+
+/// Name field, Modelfile with line numbers, and the problems or the progress of `/api/create`.
+fn draw_derive_editor(f: &mut Frame, editor: &mut DeriveEditor, models: &[ModelInfo], theme: &Theme, area: Rect) {
+    let messages: Vec<Line> = if editor.errors.is_empty() {
+        editor.progress.iter().map(|status| Line::from(status.clone())).collect()
+    } else {
+        let style = Style::default().fg(theme.error);
+        editor.errors.iter().map(|e| Line::from(Span::styled(e.to_string(), style))).collect()
+    };
+    let messages_height = if messages.is_empty() { 0 } else { messages.len().min(6) as u16 + 2 };
+    let split = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3), Constraint::Length(messages_height)].as_ref())
+        .split(area);
+    let focused = Style::default().fg(theme.warning);
+    let border = |focus: DeriveFocus| if editor.focus == focus { focused } else { Style::default() };
+
+    let name_title = match editor.target(models) {
+        Ok((target, false)) => Line::from(vec![
+            Span::raw("Name "),
+            Span::styled(format!("→ {}", target), Style::default().fg(theme.success)),
+        ]),
+        Ok((target, true)) => Line::from(vec![
+            Span::raw("Name "),
+            Span::styled(format!("→ replaces {}", target), Style::default().fg(theme.warning)),
+        ]),
+        Err(error) => Line::from(vec![Span::raw("Name "), Span::styled(format!("({})", error), Style::default().fg(theme.error))]),
+    };
+    let name_text = if editor.focus == DeriveFocus::Name {
+        editor.name.with_cursor(CURSOR_CHAR)
+    } else {
+        editor.name.text().to_string()
+    };
+    let name = Paragraph::new(name_text)
+        .block(Block::default().borders(Borders::ALL).title(name_title).border_style(border(DeriveFocus::Name)));
+    f.render_widget(name, split[0]);
+
+    // Keep the cursor's line in view
+    let height = split[1].height.saturating_sub(2);
+    editor.page_height = height;
+    let cursor_line = editor.modelfile.cursor_line() as u16;
+    if cursor_line < editor.scroll {
+        editor.scroll = cursor_line;
+    } else if height > 0 && cursor_line >= editor.scroll + height {
+        editor.scroll = cursor_line + 1 - height;
+    }
+
+    let text = if editor.focus == DeriveFocus::Modelfile {
+        editor.modelfile.with_cursor(CURSOR_CHAR)
+    } else {
+        editor.modelfile.text().to_string()
+    };
+    let error_lines = editor.error_lines();
+    let gutter_width = editor.modelfile.line_count().to_string().len();
+    let lines: Vec<Line> = text
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            let number_style = if error_lines.contains(&(i + 1)) {
+                Style::default().fg(theme.error).bold()
+            } else {
+                Style::default().fg(theme.muted)
+            };
+            Line::from(vec![
+                Span::styled(format!("{:>width$} ", i + 1, width = gutter_width), number_style),
+                Span::raw(line.to_string()),
+            ])
+        })
+        .collect();
+    let modelfile = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Modelfile (from {})", editor.source))
+                .border_style(border(DeriveFocus::Modelfile)),
+        )
+        .scroll((editor.scroll, 0));
+    f.render_widget(modelfile, split[1]);
+
+    if messages_height > 0 {
+        let title = if editor.is_creating() {
+            "Creating..."
+        } else if editor.errors.is_empty() {
+            "Progress"
+        } else {
+            "Problems"
+        };
+        // Latest status lines last
+        let scroll = (messages.len() as u16).saturating_sub(messages_height - 2);
+        let messages = Paragraph::new(messages)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((if editor.errors.is_empty() { scroll } else { 0 }, 0));
+        f.render_widget(messages, split[2]);
+    }
+}
+
+/// Name field, a row per parameter with its current and new value, and the pending changes.
+fn draw_param_form(f: &mut Frame, form: &mut ParamForm, models: &[ModelInfo], theme: &Theme, area: Rect) {
+    let changes = form.changes();
+    let spec = form.fields[form.selected].spec;
+    let mut messages = vec![
+        Line::from(vec![
+            Span::styled(format!("{} ({}): ", spec.name, spec.range()), Style::default().bold()),
+            Span::raw(spec.description),
+        ]),
+        Line::from(""),
+    ];
+    // The selected field's own problem shows while it is being typed
+    let field_error = form.fields[form.selected].change().err();
+    if let Some(error) = form.error.as_ref().or(field_error.as_ref()) {
+        messages.push(Line::from(Span::styled(error.clone(), Style::default().fg(theme.error))));
+    } else if !form.progress.is_empty() {
+        messages.extend(form.progress.iter().map(|status| Line::from(status.clone())));
+    } else if changes.is_empty() {
+        messages.push(Line::from(Span::styled("No changes yet", Style::default().fg(theme.muted))));
+    } else {
+        for (i, _) in &changes {
+            let field = &form.fields[*i];
+            messages.push(Line::from(vec![
+                Span::raw(format!("{}: ", field.spec.name)),
+                Span::styled(field.current.clone().unwrap_or_else(|| "default".to_string()), Style::default().fg(theme.muted)),
+                Span::raw(" → "),
+                Span::styled(field.input.text().trim().to_string(), Style::default().fg(theme.warning)),
+            ]));
+        }
+    }
+    // One spare line for the description to wrap into
+    let messages_height = (messages.len() as u16).min(10) + 3;
+    let split = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(5), Constraint::Length(messages_height)].as_ref())
+        .split(area);
+    let focused = Style::default().fg(theme.warning);
+    let border = |focus: FormFocus| if form.focus == focus { focused } else { Style::default() };
+
+    let name_title = match form.target(models) {
+        Ok((target, false)) => Line::from(vec![
+            Span::raw("Name "),
+            Span::styled(format!("→ {}", target), Style::default().fg(theme.success)),
+        ]),
+        Ok((target, true)) => Line::from(vec![
+            Span::raw("Name "),
+            Span::styled(format!("→ replaces {}", target), Style::default().fg(theme.warning)),
+        ]),
+        Err(error) => Line::from(vec![Span::raw("Name "), Span::styled(format!("({})", error), Style::default().fg(theme.error))]),
+    };
+    let name_text = if form.focus == FormFocus::Name {
+        form.name.with_cursor(CURSOR_CHAR)
+    } else {
+        form.name.text().to_string()
+    };
+    let name = Paragraph::new(name_text)
+        .block(Block::default().borders(Borders::ALL).title(name_title).border_style(border(FormFocus::Name)));
+    f.render_widget(name, split[0]);
+
+    let rows: Vec<Row> = form
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let editing = form.focus == FormFocus::Fields && i == form.selected;
+            let value = if editing { field.input.with_cursor(CURSOR_CHAR) } else { field.input.text().to_string() };
+            let value_style = match field.change() {
+                Err(_) => Style::default().fg(theme.error),
+                Ok(Some(_)) => Style::default().fg(theme.warning),
+                Ok(None) => Style::default(),
+            };
+            let current = match &field.current {
+                Some(current) => Cell::from(current.clone()),
+                None => Cell::from("default").style(Style::default().fg(theme.muted)),
+            };
+            Row::new(vec![Cell::from(field.spec.name), current, Cell::from(value).style(value_style)])
+        })
+        .collect();
+    let table = Table::new(rows, [Constraint::Length(18), Constraint::Min(10), Constraint::Min(10)])
+        .header(Row::new(["Parameter", "Current", "New"]).style(Style::default().bold().underlined()))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Parameters (from {})", form.source))
+                .border_style(border(FormFocus::Fields)),
+        )
+        .highlight_style(theme.highlight())
+        .highlight_symbol("> ");
+    form.page_height = split[1].height.saturating_sub(3);
+    let mut table_state = TableState::default();
+    table_state.select(Some(form.selected));
+    f.render_stateful_widget(table, split[1], &mut table_state);
+
+    let title = if form.is_creating() {
+        "Creating..."
+    } else if form.error.is_some() || field_error.is_some() {
+        "Problem"
+    } else {
+        "Changes"
+    };
+    let messages = Paragraph::new(messages)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false });
+    f.render_widget(messages, split[2]);
 }
 
 fn draw_status_bar(f: &mut Frame, app: &AppState, area: Rect) {
+    let theme = &app.theme;
+    let keys = &app.keymap;
+    let filter_hints = keys.hints(
+        KeyContext::Filter,
+        &[(Action::Submit, "Confirm"), (Action::Cancel, "Cancel"), (Action::ClearInput, "Clear")],
+    );
     let status_text = if let Some(err) = &app.install_error {
         format!("Error: {}", err).red().to_string()
     } else if let Some(status) = &app.install_status {
@@ -276,50 +965,362 @@ fn draw_status_bar(f: &mut Frame, app: &AppState, area: Rect) {
         match app.current_mode {
             AppMode::Normal => {
                 if app.is_filtered {
-                    format!("Filter: '{}' ({} models) | /: Filter | Ctrl+C: Clear | q: Quit", 
-                            app.filter_input, app.get_current_models().len())
-                } else {
-                    app.status_message.clone().unwrap_or_else(||
-                        "q: Quit | ↓/j: Down | ↑/k: Up | d: Delete | i: Install | Enter: Run | /: Filter".to_string()
+                    format!(
+                        "Filter: '{}' ({} models) | {}",
+                        app.filter_input.text(),
+                        app.get_current_models().len(),
+                        keys.hints(
+                            KeyContext::Models,
+                            &[(Action::Filter, "Filter"), (Action::ClearFilter, "Clear"), (Action::Quit, "Quit")],
+                        )
                     )
+                } else {
+                    app.status_message.clone().unwrap_or_else(|| {
+                        keys.hints(
+                            KeyContext::Models,
+                            &[
+                                (Action::Quit, "Quit"),
+                                (Action::MoveDown, "Down"),
+                                (Action::MoveUp, "Up"),
+                                (Action::Delete, "Delete"),
+                                (Action::Install, "Install"),
+                                (Action::ShowDownloads, "Downloads"),
+                                (Action::ShowRunning, "Loaded"),
+                                (Action::Chat, "Chat"),
+                                (Action::Run, "Run"),
+                                (Action::Filter, "Filter"),
+                            ],
+                        )
+                    })
                 }
             }
-            AppMode::Filter => {
-                format!("Filter Mode: Type to search | Enter: Confirm | Esc: Cancel | Ctrl+C: Clear")
+            AppMode::Filter | AppMode::InstallSelectModelFilter => {
+                format!("Filter Mode: Type to search | {}", filter_hints)
             }
-            AppMode::ConfirmDelete => "Confirm delete? (y/N)".to_string(),
+            AppMode::CopyInput => format!("Type the new name | {}", filter_hints),
+            AppMode::ConfirmDelete | AppMode::InventoryConfirmDelete => format!(
+                "Confirm delete? | {}",
+                keys.hints(KeyContext::Confirm, &[(Action::Confirm, "Yes"), (Action::Cancel, "No")])
+            ),
             AppMode::InstallSelectModel => {
+                let hints = keys.hints(
+                    KeyContext::Install,
+                    &[
+                        (Action::MoveDown, "Down"),
+                        (Action::MoveUp, "Up"),
+                        (Action::Select, "Choose Tags"),
+                        (Action::Filter, "Filter"),
+                        (Action::ClearFilter, "Clear Filter"),
+                        (Action::Back, "Cancel"),
+                    ],
+                );
                 if app.is_registry_filtered {
-                    format!("Filter: '{}' ({} models) | /: Filter | Ctrl+C: Clear | ↑/↓: Select | Enter: Choose Tags | Esc: Cancel", 
-                            app.registry_filter_input, app.get_current_registry_models().len())
+                    format!(
+                        "Filter: '{}' ({} models) | {}",
+                        app.registry_filter_input.text(),
+                        app.get_current_registry_models().len(),
+                        hints
+                    )
                 } else {
-                    "↑/↓: Select | Enter: Choose Tags | /: Filter | Esc: Cancel".to_string()
+                    hints
                 }
-            },
-            AppMode::InstallSelectModelFilter => "Filter Mode: Type to search | Enter: Confirm | Esc: Cancel | Ctrl+C: Clear".to_string(),
-            AppMode::InstallSelectTag => "↑/↓: Select | Enter: Confirm | Esc: Back".to_string(),
-            AppMode::InstallConfirm => "Confirm install? (y/N) | Esc: Back".to_string(),
-            AppMode::Installing => app.install_status.clone().unwrap_or_else(|| "Installing...".to_string()),
+            }
+            AppMode::InstallSelectTag => keys.hints(
+                KeyContext::Install,
+                &[
+                    (Action::MoveDown, "Down"),
+                    (Action::MoveUp, "Up"),
+                    (Action::Select, "Confirm"),
+                    (Action::Back, "Back"),
+                ],
+            ),
+            AppMode::InstallConfirm => format!(
+                "Confirm install? | {}",
+                keys.hints(KeyContext::Confirm, &[(Action::Confirm, "Yes"), (Action::Cancel, "Back")])
+            ),
+            AppMode::RunningModels => app.status_message.clone().unwrap_or_else(|| {
+                keys.hints(
+                    KeyContext::Running,
+                    &[
+                        (Action::MoveDown, "Down"),
+                        (Action::MoveUp, "Up"),
+                        (Action::LoadModel, "Load"),
+                        (Action::UnloadModel, "Unload"),
+                        (Action::PinModel, "Pin (Keep Loaded)"),
+                        (Action::Back, "Close"),
+                    ],
+                )
+            }),
+            AppMode::Downloads => format!(
+                "{} | Concurrency: {}",
+                keys.hints(
+                    KeyContext::Downloads,
+                    &[
+                        (Action::CancelDownload, "Cancel"),
+                        (Action::RetryDownload, "Retry"),
+                        (Action::ClearFinished, "Clear Finished"),
+                        (Action::MoreConcurrent, "More"),
+                        (Action::FewerConcurrent, "Fewer"),
+                        (Action::Back, "Close"),
+                    ],
+                ),
+                app.downloads.max_concurrent
+            ),
+            AppMode::HostSelect => keys.hints(
+                KeyContext::Hosts,
+                &[
+                    (Action::MoveDown, "Down"),
+                    (Action::MoveUp, "Up"),
+                    (Action::Select, "Switch Host"),
+                    (Action::Back, "Cancel"),
+                ],
+            ),
+            AppMode::Inventory => app.status_message.clone().unwrap_or_else(|| {
+                keys.hints(
+                    KeyContext::Inventory,
+                    &[
+                        (Action::Pull, "Pull Here"),
+                        (Action::Delete, "Delete"),
+                        (Action::Chat, "Chat Here"),
+                        (Action::Refresh, "Refresh"),
+                        (Action::Back, "Close"),
+                    ],
+                )
+            }),
             AppMode::RunningOllama => "Running ollama... (TUI Suspended)".to_string(),
-            AppMode::Help => "h/?/q/Esc: Close Help".to_string(),
+            AppMode::EditingExternally => "Editing the Modelfile in $EDITOR... (TUI Suspended)".to_string(),
+            AppMode::Quantize => keys.hints(
+                KeyContext::Quantize,
+                &[(Action::Submit, "Quantize"), (Action::MoveDown, "Next Type"), (Action::Cancel, "Close")],
+            ),
+            AppMode::ParamForm => keys.hints(
+                KeyContext::Form,
+                &[
+                    (Action::CreateModel, "Create"),
+                    (Action::SwitchPane, "Name/Parameters"),
+                    (Action::ResetField, "Reset Field"),
+                    (Action::Cancel, "Close"),
+                ],
+            ),
+            AppMode::Derive => keys.hints(
+                KeyContext::Editor,
+                &[
+                    (Action::CreateModel, "Create"),
+                    (Action::SwitchPane, "Name/Modelfile"),
+                    (Action::ExternalEditor, "$EDITOR"),
+                    (Action::Cancel, "Close"),
+                ],
+            ),
+            AppMode::Chat => app.status_message.clone().unwrap_or_else(|| {
+                keys.hints(
+                    KeyContext::Chat,
+                    &[
+                        (Action::SendMessage, "Send"),
+                        (Action::InsertNewline, "Newline"),
+                        (Action::PageUp, "Scroll"),
+                        (Action::StopOrClear, "Stop"),
+                        (Action::ClearTranscript, "Clear"),
+                        (Action::Back, "Back"),
+                    ],
+                )
+            }),
+            AppMode::Help => keys.hints(
+                KeyContext::Help,
+                &[(Action::MoveDown, "Scroll Down"), (Action::MoveUp, "Scroll Up"), (Action::Back, "Close Help")],
+            ),
+            AppMode::Tensors => keys.hints(
+                KeyContext::Tensors,
+                &[
+                    (Action::MoveDown, "Down"),
+                    (Action::MoveUp, "Up"),
+                    (Action::PageDown, "Page Down"),
+                    (Action::PageUp, "Page Up"),
+                    (Action::Back, "Close"),
+                ],
+            ),
+            AppMode::Details => keys.hints(
+                KeyContext::Details,
+                &[
+                    (Action::MoveDown, "Scroll Down"),
+                    (Action::PageDown, "Page Down"),
+                    (Action::NextTab, "Next Tab"),
+                    (Action::Search, "Search"),
+                    (Action::NextMatch, "Next Match"),
+                    (Action::SwitchPane, "Model List"),
+                    (Action::Back, "Back"),
+                ],
+            ),
+            AppMode::DetailsSearch => format!("Search: Type to find text in the tab | {}", filter_hints),
         }
     };
 
-    let status_line = Line::from(status_text);
+    let mut spans = Vec::new();
+    if let Some(host) = app.active_host() {
+        spans.push(Span::styled(
+            format!(" {} ", host.name),
+            Style::default().bg(theme.accent).fg(Color::White).bold(),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if !app.pending_keys.is_empty() {
+        // First keys of a sequence such as `gg`, waiting for the rest
+        spans.push(Span::styled(
+            format!("{}- ", format_sequence(&app.pending_keys)),
+            Style::default().fg(theme.warning).bold(),
+        ));
+    }
+    spans.push(Span::raw(status_text));
+    let status_line = Line::from(spans);
 
     let paragraph = Paragraph::new(status_line)
-        .style(Style::default().bg(Color::DarkGray));
+        .style(Style::default().bg(theme.status_bar_bg));
+
+    f.render_widget(paragraph, area);
+
+    // Background downloads summary on the right edge
+    let active = app.downloads.count(JobState::Active);
+    let pending = app.downloads.count(JobState::Pending);
+    if active + pending > 0 {
+        let summary = format!(" ↓ {} active, {} queued ", active, pending);
+        let width = summary.chars().count() as u16;
+        let summary_area = Rect {
+            x: area.x + area.width.saturating_sub(width),
+            width: width.min(area.width),
+            ..area
+        };
+        let summary_paragraph = Paragraph::new(summary)
+            .style(Style::default().bg(Color::Blue).fg(Color::White));
+        f.render_widget(summary_paragraph, summary_area);
+    }
+}
+
+/// Name input for copying or renaming, with the validated target or why it can't be used.
+fn draw_copy_dialog(f: &mut Frame, dialog: &CopyDialog, models: &[ModelInfo], theme: &Theme) {
+    let block = Block::default()
+        .title(dialog.mode.title())
+        .borders(Borders::ALL)
+        .style(theme.dialog());
 
+    let outcome = match dialog.target(models) {
+        Ok(target) => Span::styled(format!("→ {}", target), Style::default().fg(theme.success)),
+        Err(error) => Span::styled(error, Style::default().fg(theme.error)),
+    };
+    let lines = vec![
+        Line::from(format!("New name for '{}':", dialog.source)),
+        Line::from(Span::styled(dialog.input.with_cursor(CURSOR_CHAR), Style::default().fg(theme.warning))),
+        Line::from(outcome),
+    ];
+    let paragraph = Paragraph::new(lines).block(block);
+
+    let area = centered_rect(60, 20, f.size());
+    f.render_widget(Clear, area);
     f.render_widget(paragraph, area);
 }
 
-fn draw_confirmation_dialog(f: &mut Frame, model_name: &str) {
+/// Quantization types, the name of the result, progress, and the models made so far.
+fn draw_quantize_dialog(f: &mut Frame, dialog: &mut QuantizeDialog, models: &[ModelInfo], theme: &Theme) {
+    let title = format!(
+        "Quantize {} ({}, {})",
+        dialog.source,
+        dialog.source_type,
+        format_size(dialog.source_size, BINARY)
+    );
+    let block = Block::default().title(title).borders(Borders::ALL).style(theme.dialog());
+    let area = centered_rect(70, 70, f.size());
+    f.render_widget(Clear, area);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let results_height = if dialog.results.is_empty() { 0 } else { dialog.results.len().min(5) as u16 + 1 };
+    let split = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Min(3),
+                Constraint::Length(2),
+                Constraint::Length(2),
+                Constraint::Length(results_height),
+            ]
+            .as_ref(),
+        )
+        .split(inner);
+
+    let items: Vec<ListItem> = QUANTIZATIONS
+        .iter()
+        .map(|(quantization, description)| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:<8}", quantization)),
+                Span::styled(*description, Style::default().fg(theme.muted)),
+            ]))
+        })
+        .collect();
+    let list = List::new(items).highlight_style(theme.highlight()).highlight_symbol("> ");
+    f.render_stateful_widget(list, split[0], &mut dialog.list_state);
+
+    let outcome = match dialog.target(models) {
+        Ok((target, false)) => Span::styled(format!("→ {}", target), Style::default().fg(theme.success)),
+        Ok((target, true)) => Span::styled(format!("→ replaces {}", target), Style::default().fg(theme.warning)),
+        Err(error) => Span::styled(error, Style::default().fg(theme.error)),
+    };
+    let name = Paragraph::new(vec![
+        Line::from(vec![
+            Span::raw("Name: "),
+            Span::styled(dialog.name.with_cursor(CURSOR_CHAR), Style::default().fg(theme.warning)),
+        ]),
+        Line::from(outcome),
+    ]);
+    f.render_widget(name, split[1]);
+
+    // Progress of the request in flight, or why the last one failed
+    let progress_area = Rect { height: 1, ..split[2] };
+    match (&dialog.error, &dialog.status, dialog.ratio) {
+        (Some(error), _, _) => {
+            let error = Paragraph::new(Span::styled(error.clone(), Style::default().fg(theme.error)));
+            f.render_widget(error, progress_area);
+        }
+        (None, Some(status), Some(ratio)) => {
+            let gauge = Gauge::default()
+                .gauge_style(Style::default().fg(theme.success).bg(Color::Black))
+                .ratio(ratio)
+                .label(format!("{} {:.0}%", status, ratio * 100.0));
+            f.render_widget(gauge, progress_area);
+        }
+        (None, Some(status), None) => f.render_widget(Paragraph::new(status.clone()), progress_area),
+        (None, None, _) if dialog.is_creating() => f.render_widget(Paragraph::new("Starting..."), progress_area),
+        (None, None, _) => {}
+    }
+
+    if results_height > 0 {
+        let lines: Vec<Line> = dialog
+            .results
+            .iter()
+            .rev()
+            .take(5)
+            .map(|result| {
+                let size = match result.size {
+                    Some(size) => size_comparison(dialog.source_size, size),
+                    None => "not in the model list".to_string(),
+                };
+                Line::from(vec![
+                    Span::styled(format!("{} ", result.model), Style::default().fg(theme.success)),
+                    Span::styled(format!("({}) ", result.quantization), Style::default().fg(theme.muted)),
+                    Span::raw(size),
+                ])
+            })
+            .collect();
+        let results = Paragraph::new(lines).block(Block::default().borders(Borders::TOP).title("Made"));
+        f.render_widget(results, split[3]);
+    }
+}
+
+/// `target` is shown verbatim, e.g. `'llama3:8b'` or `'llama3:8b' on gpu1`.
+fn draw_confirmation_dialog(f: &mut Frame, theme: &Theme, target: &str) {
     let block = Block::default()
         .title("Confirm Deletion")
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::DarkGray));
+        .style(theme.dialog());
 
-    let text = format!("Are you sure you want to delete '{}'? (y/N)", model_name);
+    let text = format!("Are you sure you want to delete {}? (y/N)", target);
     let paragraph = Paragraph::new(text)
         .block(block)
         .wrap(Wrap { trim: true });
@@ -331,16 +1332,8 @@ fn draw_confirmation_dialog(f: &mut Frame, model_name: &str) {
 }
 
 fn draw_install_model_select_dialog(f: &mut Frame, app: &AppState) {
-    // Split the dialog area to include filter input if in filter mode
-    let (list_area, filter_area) = if app.current_mode == AppMode::InstallSelectModelFilter {
-        let split = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
-            .split(centered_rect(70, 50, f.size()));
-        (split[1], Some(split[0]))
-    } else {
-        (centered_rect(70, 50, f.size()), None)
-    };
+    let theme = &app.theme;
+    let (list_area, filter_area) = registry_dialog_areas(f.size(), app);
 
     // Create title with filter indicator
     let title = if app.is_registry_filtered {
@@ -353,7 +1346,7 @@ fn draw_install_model_select_dialog(f: &mut Frame, app: &AppState) {
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::DarkGray));
+        .style(theme.dialog());
 
     f.render_widget(Clear, list_area);
     if let Some(filter_area) = filter_area {
@@ -369,15 +1362,16 @@ fn draw_install_model_select_dialog(f: &mut Frame, app: &AppState) {
         let current_models = app.get_current_registry_models();
         let items: Vec<ListItem> = current_models
             .iter()
-            .map(|m| ListItem::new(Line::from(m.clone())))
+            .map(|m| {
+                let matched = app.registry_filter_matches.get(m).map(Vec::as_slice).unwrap_or_default();
+                ListItem::new(Line::from(highlight_matches(m, matched, 0, theme)))
+            })
             .collect();
 
         let list = List::new(items)
             .block(block)
             .highlight_style(
-                Style::default()
-                    .bg(Color::LightBlue)
-                    .add_modifier(Modifier::BOLD),
+                theme.highlight(),
             )
             .highlight_symbol("> ");
 
@@ -392,8 +1386,9 @@ fn draw_install_model_select_dialog(f: &mut Frame, app: &AppState) {
 }
 
 fn draw_registry_filter_input(f: &mut Frame, app: &AppState, area: Rect) {
+    let theme = &app.theme;
     let input_style = if app.current_mode == AppMode::InstallSelectModelFilter {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.warning)
     } else {
         Style::default()
     };
@@ -402,15 +1397,14 @@ fn draw_registry_filter_input(f: &mut Frame, app: &AppState, area: Rect) {
         .borders(Borders::ALL)
         .title("Filter Registry Models")
         .border_style(input_style)
-        .style(Style::default().bg(Color::DarkGray));
+        .style(theme.dialog());
 
     // Create the input display with cursor
-    let mut input_display = app.registry_filter_input.clone();
-    if app.current_mode == AppMode::InstallSelectModelFilter {
-        // Insert cursor character at cursor position (using ASCII-safe cursor)
-        let cursor_pos = std::cmp::min(app.registry_filter_cursor_pos, input_display.len());
-        input_display.insert(cursor_pos, CURSOR_CHAR);
-    }
+    let input_display = if app.current_mode == AppMode::InstallSelectModelFilter {
+        app.registry_filter_input.with_cursor(CURSOR_CHAR)
+    } else {
+        app.registry_filter_input.text().to_string()
+    };
 
     let input_paragraph = Paragraph::new(input_display)
         .block(block)
@@ -420,14 +1414,15 @@ fn draw_registry_filter_input(f: &mut Frame, app: &AppState, area: Rect) {
 }
 
 fn draw_install_tag_select_dialog(f: &mut Frame, app: &AppState) {
+    let theme = &app.theme;
     let model_name = app.selected_registry_model.as_deref().unwrap_or("Unknown");
     let title = format!("Install Model: Select Tag for '{}'", model_name);
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::DarkGray));
+        .style(theme.dialog());
 
-    let area = centered_rect(60, 50, f.size());
+    let area = tag_dialog_area(f.size());
 
     f.render_widget(Clear, area);
 
@@ -440,15 +1435,24 @@ fn draw_install_tag_select_dialog(f: &mut Frame, app: &AppState) {
         let items: Vec<ListItem> = app
             .registry_tags
             .iter()
-            .map(|t| ListItem::new(Line::from(t.clone())))
+            .map(|t| {
+                // Download size once the tag's manifest has been fetched
+                let size = match app.registry_manifests.get(t) {
+                    Some(ManifestStatus::Loaded(manifest)) => manifest.download_size_formatted(),
+                    Some(ManifestStatus::Loading) => "...".to_string(),
+                    _ => String::new(),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{:<40}", t)),
+                    Span::styled(size, Style::default().fg(theme.muted)),
+                ]))
+            })
             .collect();
 
         let list = List::new(items)
             .block(block)
             .highlight_style(
-                Style::default()
-                    .bg(Color::LightBlue)
-                    .add_modifier(Modifier::BOLD),
+                theme.highlight(),
             )
             .highlight_symbol("> ");
 
@@ -458,25 +1462,472 @@ fn draw_install_tag_select_dialog(f: &mut Frame, app: &AppState) {
 }
 
 fn draw_install_confirm_dialog(f: &mut Frame, app: &AppState) {
+    let theme = &app.theme;
     let model = app.selected_registry_model.as_deref().unwrap_or("??");
     let tag = app.selected_registry_tag.as_deref().unwrap_or("??");
     let block = Block::default()
         .title("Confirm Installation")
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::DarkGray));
+        .style(theme.dialog());
 
-    let text = format!("Install model '{}:{}'? (y/N)", model, tag);
+    let mut text = vec![
+        Line::from(format!("Install model '{}:{}'? (y/N)", model, tag)),
+        Line::from(""),
+    ];
+    match app.registry_manifests.get(tag) {
+        Some(ManifestStatus::Loaded(manifest)) => {
+            text.push(Line::from(vec![
+                Span::styled("Download size: ", Style::default().bold()),
+                Span::raw(manifest.download_size_formatted()),
+            ]));
+            text.push(Line::from(vec![
+                Span::styled("Layers: ", Style::default().bold()),
+                Span::raw(manifest.layer_kinds().join(", ")),
+            ]));
+            let config_digest = manifest.config.digest.trim_start_matches("sha256:");
+            text.push(Line::from(vec![
+                Span::styled("Config: ", Style::default().bold()),
+                Span::raw(format!("{} ({})", config_digest.chars().take(12).collect::<String>(), manifest.config.media_type)),
+            ]));
+        }
+        Some(ManifestStatus::Loading) => text.push(Line::from("Fetching manifest...")),
+        Some(ManifestStatus::Failed(err)) => text.push(Line::from(Span::styled(
+            format!("Manifest unavailable: {}", err),
+            Style::default().fg(theme.error),
+        ))),
+        None => {}
+    }
     let paragraph = Paragraph::new(text)
         .block(block)
         .wrap(Wrap { trim: true })
         .alignment(ratatui::layout::Alignment::Center);
 
-    let area = centered_rect(60, 20, f.size());
+    let area = centered_rect(60, 30, f.size());
 
     f.render_widget(Clear, area);
     f.render_widget(paragraph, area);
 }
 
+fn draw_running_models_panel(f: &mut Frame, app: &AppState) {
+    let theme = &app.theme;
+    let area = centered_rect(80, 50, f.size());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!("Loaded Models ({})", app.running_models.len()))
+        .borders(Borders::ALL)
+        .style(theme.dialog());
+
+    if let Some(err) = &app.running_models_error {
+        let paragraph = Paragraph::new(format!("Error fetching loaded models: {}", err))
+            .block(block)
+            .wrap(Wrap { trim: true });
+        f.render_widget(paragraph, area);
+        return;
+    }
+    if app.running_models.is_empty() {
+        let paragraph = Paragraph::new("No models are loaded.").block(block);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let header = Row::new(["Name", "Size", "VRAM", "Processor", "Context", "Unloads In"])
+        .style(Style::default().bold().underlined());
+    let rows: Vec<Row> = app
+        .running_models
+        .iter()
+        .map(|m| {
+            Row::new(vec![
+                Cell::from(m.name.clone()),
+                Cell::from(m.size_formatted()),
+                Cell::from(format_size(m.size_vram, BINARY)),
+                Cell::from(m.processor_split()),
+                Cell::from(m.context_length.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string())),
+                Cell::from(format_expiry(m.expires_in())),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(30),
+            Constraint::Length(11),
+            Constraint::Length(11),
+            Constraint::Length(16),
+            Constraint::Length(8),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header)
+    .block(block)
+    .highlight_style(
+        theme.highlight(),
+    )
+    .highlight_symbol("> ");
+
+    let mut table_state = app.running_table_state.clone();
+    f.render_stateful_widget(table, area, &mut table_state);
+}
+
+/// Tensor inspector: totals per tensor type above the tensors grouped by block.
+fn draw_tensors_panel(f: &mut Frame, view: &mut TensorView, theme: &Theme) {
+    let area = centered_rect(90, 90, f.size());
+    f.render_widget(Clear, area);
+
+    let total = view.total_elements();
+    let title = if view.rows.is_empty() {
+        format!("Tensors - {}", view.model)
+    } else {
+        let count: usize = view.groups.iter().map(|g| g.tensors.len()).sum();
+        format!("Tensors - {} ({} tensors, {} weights)", view.model, count, format_parameter_count(total))
+    };
+    let block = Block::default().title(title).borders(Borders::ALL).style(theme.dialog());
+
+    let message = match (&view.error, view.is_loading) {
+        (Some(err), _) => Some(format!("Error fetching tensors: {}", err)),
+        (None, true) => Some("Fetching tensors...".to_string()),
+        (None, false) if view.rows.is_empty() => Some("The server reported no tensors for this model.".to_string()),
+        _ => None,
+    };
+    if let Some(message) = message {
+        f.render_widget(Paragraph::new(message).block(block).wrap(Wrap { trim: true }), area);
+        return;
+    }
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    // Header plus one row per type, and a blank line before the tensors
+    let totals_height = (view.totals.len() as u16 + 2).min(inner.height / 3);
+    let split = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(totals_height), Constraint::Min(0)].as_ref())
+        .split(inner);
+
+    let header_style = Style::default().bold().underlined();
+    let total_rows: Vec<Row> = view
+        .totals
+        .iter()
+        .map(|t| {
+            let share = if total == 0 { 0.0 } else { t.elements as f64 * 100.0 / total as f64 };
+            Row::new(vec![
+                Cell::from(t.tensor_type.clone()),
+                Cell::from(t.count.to_string()),
+                Cell::from(format_parameter_count(t.elements)),
+                Cell::from(format!("{:.1}%", share)),
+            ])
+        })
+        .collect();
+    let totals_table = Table::new(
+        total_rows,
+        [Constraint::Length(12), Constraint::Length(9), Constraint::Length(10), Constraint::Length(8)],
+    )
+    .header(Row::new(["Type", "Tensors", "Weights", "Share"]).style(header_style));
+    f.render_widget(totals_table, split[0]);
+
+    let group_style = Style::default().fg(theme.accent).bold();
+    let rows: Vec<Row> = view
+        .rows
+        .iter()
+        .map(|row| match *row {
+            TensorRow::Group(g) => {
+                let group = &view.groups[g];
+                Row::new(vec![
+                    Cell::from(group.name.clone()),
+                    Cell::from(format!("{} tensors", group.tensors.len())),
+                    Cell::from(""),
+                    Cell::from(format_parameter_count(group.elements())),
+                ])
+                .style(group_style)
+            }
+            TensorRow::Tensor(g, t) => {
+                let group = &view.groups[g];
+                let tensor = &group.tensors[t];
+                // Within a block the block prefix is implied by the group row
+                let name = tensor
+                    .name
+                    .strip_prefix(&format!("{}.", group.name))
+                    .unwrap_or(&tensor.name);
+                let shape: Vec<String> = tensor.shape.iter().map(|d| d.to_string()).collect();
+                Row::new(vec![
+                    Cell::from(format!("  {}", name)),
+                    Cell::from(tensor.tensor_type.clone()),
+                    Cell::from(shape.join(" × ")),
+                    Cell::from(format_parameter_count(tensor.elements())),
+                ])
+            }
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [Constraint::Min(24), Constraint::Length(12), Constraint::Length(20), Constraint::Length(10)],
+    )
+    .header(Row::new(["Name", "Type", "Shape", "Weights"]).style(header_style))
+    .highlight_style(theme.highlight())
+    .highlight_symbol("> ");
+
+    view.page_height = split[1].height.saturating_sub(1);
+    let mut table_state = view.table_state.clone();
+    f.render_stateful_widget(table, split[1], &mut table_state);
+}
+
+/// Formats the time until a loaded model is unloaded as a countdown.
+fn format_expiry(remaining: Option<chrono::Duration>) -> String {
+    let Some(remaining) = remaining else {
+        return "Stopping...".to_string();
+    };
+    // A negative keep_alive pins the model, which Ollama reports as a far-future expiry.
+    if remaining.num_days() > 365 {
+        return "Forever".to_string();
+    }
+    let secs = remaining.num_seconds();
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{}h {:02}m {:02}s", h, m, s)
+    } else if m > 0 {
+        format!("{}m {:02}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
+fn draw_downloads_panel(f: &mut Frame, app: &AppState) {
+    let theme = &app.theme;
+    let downloads = &app.downloads;
+    let area = centered_rect(80, 70, f.size());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(
+            "Downloads ({} active, {} queued, max {} at once)",
+            downloads.count(JobState::Active),
+            downloads.count(JobState::Pending),
+            downloads.max_concurrent
+        ))
+        .borders(Borders::ALL)
+        .style(theme.dialog());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let split = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(45), Constraint::Min(0)].as_ref())
+        .split(inner);
+
+    if downloads.jobs.is_empty() {
+        f.render_widget(Paragraph::new("No downloads. Press 'i' to install a model."), split[0]);
+        return;
+    }
+
+    let items: Vec<ListItem> = downloads
+        .jobs
+        .iter()
+        .map(|job| {
+            let state_style = match job.state {
+                JobState::Active => Style::default().fg(theme.success),
+                JobState::Pending => Style::default().fg(theme.warning),
+                JobState::Failed => Style::default().fg(theme.error),
+                JobState::Cancelled => Style::default().fg(theme.muted),
+                JobState::Finished => Style::default().fg(Color::Cyan),
+            };
+            let detail = match job.state {
+                JobState::Active => format!("{:.0}% {}", job.progress.ratio() * 100.0, job.progress.status),
+                JobState::Failed => job.error.clone().unwrap_or_default(),
+                _ => String::new(),
+            };
+            let mut spans = vec![
+                Span::styled(format!("[{:<9}] ", job.state.label()), state_style),
+                Span::raw(format!("{} ", job.model)),
+            ];
+            // Only worth showing once more than one host is configured
+            if app.hosts.len() > 1 {
+                let host_name = app
+                    .hosts
+                    .iter()
+                    .find(|h| h.url == job.host)
+                    .map_or(job.host.as_str(), |h| h.name.as_str());
+                spans.push(Span::styled(format!("@{} ", host_name), Style::default().fg(theme.accent)));
+            }
+            spans.push(Span::styled(detail, Style::default().italic()));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::BOTTOM))
+        .highlight_style(
+            theme.highlight(),
+        )
+        .highlight_symbol("> ");
+    let mut list_state = downloads.list_state.clone();
+    f.render_stateful_widget(list, split[0], &mut list_state);
+
+    if let Some(job) = downloads.selected() {
+        draw_pull_progress(f, &job.progress, &app.theme, split[1]);
+    }
+}
+
+fn draw_inventory_panel(f: &mut Frame, inventory: &InventoryState, theme: &Theme) {
+    let area = centered_rect(90, 80, f.size());
+    f.render_widget(Clear, area);
+
+    let loading = if inventory.is_loading() { ", loading..." } else { "" };
+    let block = Block::default()
+        .title(format!("Inventory ({} hosts{})", inventory.hosts.len(), loading))
+        .borders(Borders::ALL)
+        .style(theme.dialog());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Unreachable hosts are listed above the table instead of producing "missing" rows
+    let errors: Vec<Line> = inventory
+        .hosts
+        .iter()
+        .filter_map(|h| match &h.models {
+            Some(Err(e)) => Some(Line::from(Span::styled(
+                format!("{} unreachable: {}", h.host.name, e),
+                Style::default().fg(theme.error),
+            ))),
+            _ => None,
+        })
+        .collect();
+    let split = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(errors.len() as u16), Constraint::Min(0)].as_ref())
+        .split(inner);
+    f.render_widget(Paragraph::new(errors), split[0]);
+
+    let header = Row::new(["Model", "Host", "Size", "Digest", "Status"])
+        .style(Style::default().bold().underlined());
+    let rows: Vec<Row> = inventory
+        .rows
+        .iter()
+        .map(|row| {
+            let host_name = inventory
+                .hosts
+                .get(row.host_index)
+                .map_or("", |h| h.host.name.as_str());
+            let (size, digest) = match &row.info {
+                Some(info) => (info.size_formatted(), info.digest.chars().take(12).collect()),
+                None => ("-".to_string(), "-".to_string()),
+            };
+            let status_color = match row.status {
+                RowStatus::InSync => theme.success,
+                RowStatus::Partial | RowStatus::Differs => theme.warning,
+                RowStatus::Missing => theme.error,
+            };
+            Row::new(vec![
+                Cell::from(row.model.clone()),
+                Cell::from(host_name.to_string()),
+                Cell::from(size),
+                Cell::from(digest),
+                Cell::from(Span::styled(row.status.label(), Style::default().fg(status_color))),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(35),
+            Constraint::Length(16),
+            Constraint::Length(11),
+            Constraint::Length(13),
+            Constraint::Min(16),
+        ],
+    )
+    .header(header)
+    .highlight_style(
+        theme.highlight(),
+    )
+    .highlight_symbol("> ");
+    let mut table_state = inventory.table_state.clone();
+    f.render_stateful_widget(table, split[1], &mut table_state);
+}
+
+fn draw_host_select_dialog(f: &mut Frame, app: &AppState) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 50, f.size());
+    f.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = app
+        .hosts
+        .iter()
+        .enumerate()
+        .map(|(i, host)| {
+            let marker = if i == app.active_host { "● " } else { "  " };
+            ListItem::new(Line::from(vec![
+                Span::styled(marker, Style::default().fg(theme.success)),
+                Span::styled(format!("{:<16} ", host.name), Style::default().bold()),
+                Span::styled(host.url.clone(), Style::default().italic()),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title("Select Ollama Host")
+                .borders(Borders::ALL)
+                .style(theme.dialog()),
+        )
+        .highlight_style(
+            theme.highlight(),
+        )
+        .highlight_symbol("> ");
+    let mut list_state = app.host_list_state.clone();
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+/// Renders the status line, an overall gauge and one gauge per layer of a pull.
+fn draw_pull_progress(f: &mut Frame, progress: &PullProgressState, theme: &Theme, area: Rect) {
+    let mut constraints = vec![Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)];
+    let max_layers = area.height.saturating_sub(4) as usize;
+    let shown_layers = progress.layers.len().min(max_layers);
+    constraints.extend(std::iter::repeat_n(Constraint::Length(1), shown_layers));
+    constraints.push(Constraint::Min(0));
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+
+    f.render_widget(Paragraph::new(format!("{}: {}", progress.model, progress.status)), rows[0]);
+
+    let total = progress.total_bytes();
+    let mut overall_label = format!(
+        "{} / {} ({:.0}%)",
+        format_size(progress.completed_bytes(), BINARY),
+        format_size(total, BINARY),
+        progress.ratio() * 100.0
+    );
+    if total > 0 {
+        overall_label.push_str(&format!(" | {}/s", format_size(progress.bytes_per_sec() as u64, BINARY)));
+        if let Some(eta) = progress.eta() {
+            overall_label.push_str(&format!(" | ETA {}", format_eta(eta)));
+        }
+    }
+    let overall = Gauge::default()
+        .gauge_style(Style::default().fg(theme.success).bg(Color::Black))
+        .ratio(progress.ratio())
+        .label(overall_label);
+    f.render_widget(overall, rows[1]);
+
+    for (i, layer) in progress.layers.iter().take(shown_layers).enumerate() {
+        let digest = layer.digest.trim_start_matches("sha256:");
+        let label = format!(
+            "{} {} / {}",
+            digest.chars().take(12).collect::<String>(),
+            format_size(layer.completed, BINARY),
+            format_size(layer.total, BINARY)
+        );
+        let gauge = Gauge::default()
+            .gauge_style(Style::default().fg(theme.highlight_bg).bg(Color::Black))
+            .ratio(layer.ratio())
+            .label(label);
+        f.render_widget(gauge, rows[3 + i]);
+    }
+}
+
 /// Helper function to create a centered rectangle.
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()